@@ -0,0 +1,485 @@
+//! Binario headless para operar sobre modpacks sin sesión de escritorio.
+//! Reutiliza la misma lógica que `ModUpdaterApp` (escaneo, resolución, descarga y activación)
+//! e imprime los `DownloadEvent` como líneas de progreso.
+//!
+//! La carpeta del juego se detecta igual que en la app; en servidores de build
+//! se puede forzar con la variable de entorno `MODS_UPDATER_GAME_DIR`.
+
+use crossbeam_channel::unbounded;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use mods_updater::common::calculate_worker_count;
+use mods_updater::fetch::async_download::{identify_jobs, prefetch_jobs, spawn_workers, DownloadEvent, DownloadJob};
+use mods_updater::fetch::fetch_from_api::ModDownloadInfo;
+use mods_updater::fetch::progress::TransferStats;
+use mods_updater::fetch::search_provider::ContentType;
+use mods_updater::local_mods_ops::{change_mods, list_modpacks, read_mods_in_folder, ModInfo};
+use mods_updater::paths_vars::{get_default_game_folder, PATHS};
+use mods_updater::pack_formats::{export_pack, import_pack, ExportOptions, PackFormat};
+use mods_updater::profiles::{load_profiles, save_profiles};
+
+// ── Códigos de salida ────────────────────────────────────────
+
+/// Todo correcto.
+const EXIT_OK: u8 = 0;
+/// La operación terminó pero alguna descarga falló.
+const EXIT_PARTIAL: u8 = 1;
+/// Argumentos inválidos.
+const EXIT_USAGE: u8 = 2;
+/// Error fatal (carpeta inexistente, perfil no encontrado, fallo al activar...).
+const EXIT_FATAL: u8 = 3;
+
+const USAGE: &str = "\
+Uso: tetanos-cli <comando> [opciones]
+
+Comandos:
+  scan [--modpack <nombre> | --folder <ruta>]
+      Lista los mods de la carpeta (por defecto .minecraft/mods).
+  update --version <mc> --loader <loader> [--modpack <origen>] [--output <carpeta>]
+      Descarga la versión compatible de cada mod en modpacks/<carpeta> (por defecto mods<mc>).
+  activate <modpack>
+      Activa un modpack (enlaza modpacks/<modpack> como carpeta mods).
+  profile list
+      Lista los perfiles guardados.
+  profile install <nombre> --version <mc> --loader <loader> [--output <carpeta>]
+      Descarga los mods de un perfil en modpacks/<carpeta> (por defecto el nombre del perfil).
+  profile export <nombre> --loader-version <v> [--version <mc>] [--loader <loader>]
+                 [--format mrpack|curseforge|packwiz] [--output <archivo>]
+      Exporta un perfil como .mrpack, zip de CurseForge o carpeta packwiz (por defecto en modpacks/).
+  import <archivo.mrpack | archivo.zip | carpeta packwiz>
+      Crea un perfil y el modpack modpacks/<nombre> desde un pack de Modrinth, CurseForge o packwiz y descarga sus archivos.
+
+Variables de entorno:
+  MODS_UPDATER_GAME_DIR   Carpeta del juego (si no se detecta automáticamente).
+  CURSEFORGE_API_KEY      API key de CurseForge (opcional).";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::from(if args.is_empty() { EXIT_USAGE } else { EXIT_OK });
+    }
+
+    // Evitar el panic de PATHS si no hay carpeta del juego
+    if get_default_game_folder().is_none() {
+        eprintln!("[ERROR] No se pudo detectar la carpeta de Minecraft. Usa MODS_UPDATER_GAME_DIR.");
+        return ExitCode::from(EXIT_FATAL);
+    }
+
+    if !mods_updater::local_mods_ops::cache::init() {
+        eprintln!("[WARN] Caché deshabilitada: se re-escanearán todos los jars.");
+    }
+
+    let command = args.remove(0);
+    let code = match command.as_str() {
+        "scan" => cmd_scan(&mut args),
+        "update" => cmd_update(&mut args),
+        "activate" => cmd_activate(&mut args),
+        "profile" => cmd_profile(&mut args),
+        "import" => cmd_import(&mut args),
+        other => {
+            eprintln!("[ERROR] Comando desconocido: '{}'\n\n{}", other, USAGE);
+            EXIT_USAGE
+        }
+    };
+    ExitCode::from(code)
+}
+
+// ── Parsing de argumentos ────────────────────────────────────
+
+/// Extrae `--flag <valor>` de la lista de argumentos (y lo elimina).
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|a| a == flag) {
+        Some(pos) => {
+            if pos + 1 >= args.len() {
+                return Err(format!("Falta el valor de {}", flag));
+            }
+            let value = args.remove(pos + 1);
+            args.remove(pos);
+            Ok(Some(value))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Igual que `take_flag`, pero el flag es obligatorio.
+fn require_flag(args: &mut Vec<String>, flag: &str) -> Result<String, String> {
+    take_flag(args, flag)?.ok_or_else(|| format!("Falta el argumento obligatorio {}", flag))
+}
+
+/// Falla si quedan argumentos sin consumir (typos en flags, etc.).
+fn ensure_consumed(args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Argumentos no reconocidos: {}", args.join(" ")))
+    }
+}
+
+fn usage_error(msg: &str) -> u8 {
+    eprintln!("[ERROR] {}\n\n{}", msg, USAGE);
+    EXIT_USAGE
+}
+
+/// Resuelve la carpeta de origen: `--folder` tiene prioridad, luego `--modpack`, si no la carpeta mods.
+fn source_folder(folder: Option<String>, modpack: Option<String>) -> PathBuf {
+    if let Some(f) = folder {
+        PathBuf::from(f)
+    } else if let Some(mp) = modpack {
+        PATHS.modpacks_folder.join(mp)
+    } else {
+        PATHS.mods_folder.clone()
+    }
+}
+
+// ── Comandos ─────────────────────────────────────────────────
+
+fn cmd_scan(args: &mut Vec<String>) -> u8 {
+    let parsed = (|| -> Result<PathBuf, String> {
+        let folder = take_flag(args, "--folder")?;
+        let modpack = take_flag(args, "--modpack")?;
+        ensure_consumed(args)?;
+        Ok(source_folder(folder, modpack))
+    })();
+    let folder = match parsed {
+        Ok(f) => f,
+        Err(e) => return usage_error(&e),
+    };
+
+    if !folder.is_dir() {
+        eprintln!("[ERROR] La carpeta '{}' no existe.", folder.display());
+        return EXIT_FATAL;
+    }
+
+    let mods = read_mods_in_folder(&folder.to_string_lossy());
+    for m in mods.values() {
+        println!(
+            "{}\t{}\t{}\t{}",
+            m.key,
+            m.name,
+            m.version_local.as_deref().unwrap_or("?"),
+            m.confirmed_project_id.as_deref().or(m.detected_project_id.as_deref()).unwrap_or("-"),
+        );
+    }
+    println!("Total: {} mods en '{}'", mods.len(), folder.display());
+    EXIT_OK
+}
+
+fn cmd_update(args: &mut Vec<String>) -> u8 {
+    let parsed = (|| -> Result<(String, String, PathBuf, String), String> {
+        let version = require_flag(args, "--version")?;
+        let loader = require_flag(args, "--loader")?;
+        let modpack = take_flag(args, "--modpack")?;
+        let folder = take_flag(args, "--folder")?;
+        let output = take_flag(args, "--output")?.unwrap_or_else(|| format!("mods{}", version));
+        ensure_consumed(args)?;
+        Ok((version, loader, source_folder(folder, modpack), output))
+    })();
+    let (version, loader, source, output) = match parsed {
+        Ok(p) => p,
+        Err(e) => return usage_error(&e),
+    };
+
+    if !source.is_dir() {
+        eprintln!("[ERROR] La carpeta '{}' no existe.", source.display());
+        return EXIT_FATAL;
+    }
+
+    let mods: Vec<ModInfo> = read_mods_in_folder(&source.to_string_lossy())
+        .into_values()
+        .filter(|m| m.selected)
+        .collect();
+    println!("Actualizando {} mods de '{}' a {} [{}]...", mods.len(), source.display(), version, loader);
+
+    run_downloads(mods, &version, &loader, &output, false)
+}
+
+fn cmd_activate(args: &mut Vec<String>) -> u8 {
+    if args.len() != 1 {
+        return usage_error("activate requiere exactamente un nombre de modpack");
+    }
+    let modpack = args.remove(0);
+
+    if !list_modpacks().contains(&modpack) {
+        eprintln!("[ERROR] El modpack '{}' no existe en '{}'.", modpack, PATHS.modpacks_folder.display());
+        return EXIT_FATAL;
+    }
+
+    match change_mods(&modpack) {
+        Ok(msg) => {
+            println!("{}", msg);
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("[ERROR] {}", e);
+            EXIT_FATAL
+        }
+    }
+}
+
+fn cmd_import(args: &mut Vec<String>) -> u8 {
+    if args.len() != 1 {
+        return usage_error("import requiere exactamente la ruta del pack");
+    }
+    let path = PathBuf::from(args.remove(0));
+
+    let mut db = load_profiles();
+    let imported = match import_pack(&path, &db, &PATHS.modpacks_folder) {
+        Ok(imported) => imported,
+        Err(e) => {
+            eprintln!("[ERROR] {}", e);
+            return EXIT_FATAL;
+        }
+    };
+    for warning in &imported.warnings {
+        eprintln!("[WARN] {}", warning);
+    }
+    for manual in &imported.manual_downloads {
+        eprintln!("[MANUAL] {} → descárgalo de {} en '{}'", manual.name, manual.url, manual.folder.display());
+    }
+
+    let jobs = imported.download_jobs();
+    let folder = imported.folder.clone();
+    println!(
+        "Perfil '{}' creado ({} {} [{}]): {} descargas, {} overrides en '{}'",
+        imported.profile.name,
+        imported.profile.mods.len(),
+        imported.profile.game_version.as_deref().unwrap_or("?"),
+        imported.profile.loader.as_deref().unwrap_or("?"),
+        jobs.len(),
+        imported.overrides_extracted,
+        folder.display(),
+    );
+    db.add_profile(imported.profile);
+    save_profiles(&db);
+
+    let manual_pending = !imported.manual_downloads.is_empty();
+    let code = if jobs.is_empty() { EXIT_OK } else { run_jobs(jobs, &folder) };
+    if manual_pending && code == EXIT_OK { EXIT_PARTIAL } else { code }
+}
+
+fn cmd_profile(args: &mut Vec<String>) -> u8 {
+    if args.is_empty() {
+        return usage_error("profile requiere un subcomando (list | install | export)");
+    }
+    let sub = args.remove(0);
+    match sub.as_str() {
+        "list" => {
+            if let Err(e) = ensure_consumed(args) {
+                return usage_error(&e);
+            }
+            let db = load_profiles();
+            for profile in db.profiles.values() {
+                println!("{}\t{} mods", profile.name, profile.mods.len());
+            }
+            EXIT_OK
+        }
+        "install" => {
+            let parsed = (|| -> Result<(String, String, String, Option<String>), String> {
+                let version = require_flag(args, "--version")?;
+                let loader = require_flag(args, "--loader")?;
+                let output = take_flag(args, "--output")?;
+                if args.len() != 1 {
+                    return Err("profile install requiere exactamente un nombre de perfil".to_string());
+                }
+                let name = args.remove(0);
+                Ok((name, version, loader, output))
+            })();
+            let (name, version, loader, output) = match parsed {
+                Ok(p) => p,
+                Err(e) => return usage_error(&e),
+            };
+
+            let db = load_profiles();
+            let Some(profile) = db.get_profile(&name) else {
+                eprintln!("[ERROR] El perfil '{}' no existe.", name);
+                return EXIT_FATAL;
+            };
+
+            let output = output.unwrap_or_else(|| profile.name.clone());
+            let mods: Vec<ModInfo> = profile.mods.values().cloned().collect();
+            let use_pins = profile.game_version.as_deref() == Some(version.as_str());
+            println!("Instalando perfil '{}' ({} mods) en '{}' para {} [{}]...", name, mods.len(), output, version, loader);
+
+            run_downloads(mods, &version, &loader, &output, use_pins)
+        }
+        "export" => cmd_profile_export(args),
+        other => usage_error(&format!("Subcomando de profile desconocido: '{}'", other)),
+    }
+}
+
+/// Argumentos de `profile export`.
+struct ExportArgs {
+    name: String,
+    version: Option<String>,
+    loader: Option<String>,
+    loader_version: String,
+    format: PackFormat,
+    output: Option<String>,
+}
+
+fn cmd_profile_export(args: &mut Vec<String>) -> u8 {
+    let parsed = (|| -> Result<ExportArgs, String> {
+        let version = take_flag(args, "--version")?;
+        let loader = take_flag(args, "--loader")?;
+        let loader_version = require_flag(args, "--loader-version")?;
+        let output = take_flag(args, "--output")?;
+        let format = match take_flag(args, "--format")?.as_deref() {
+            None | Some("mrpack") => PackFormat::Mrpack,
+            Some("curseforge") => PackFormat::CurseForge,
+            Some("packwiz") => PackFormat::Packwiz,
+            Some(other) => return Err(format!("Formato desconocido: '{}' (mrpack | curseforge | packwiz)", other)),
+        };
+        if args.len() != 1 {
+            return Err("profile export requiere exactamente un nombre de perfil".to_string());
+        }
+        let name = args.remove(0);
+        Ok(ExportArgs { name, version, loader, loader_version, format, output })
+    })();
+    let ExportArgs { name, version, loader, loader_version, format, output } = match parsed {
+        Ok(p) => p,
+        Err(e) => return usage_error(&e),
+    };
+
+    let db = load_profiles();
+    let Some(profile) = db.get_profile(&name) else {
+        eprintln!("[ERROR] El perfil '{}' no existe.", name);
+        return EXIT_FATAL;
+    };
+
+    // Versión y loader: los del flag o los guardados en el perfil (packs importados)
+    let Some(version) = version.or_else(|| profile.game_version.clone()) else {
+        return usage_error("El perfil no tiene versión de Minecraft guardada: usa --version");
+    };
+    let Some(loader) = loader.or_else(|| profile.loader.clone()) else {
+        return usage_error("El perfil no tiene loader guardado: usa --loader");
+    };
+
+    let output = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PATHS.modpacks_folder.join(format.file_name(&profile.name, &version)));
+    let local_folder = PATHS.modpacks_folder.join(&profile.name);
+    let opts = ExportOptions {
+        game_version: version.clone(),
+        loader: loader.clone(),
+        loader_version,
+        version_id: "1.0.0".to_string(),
+        local_folder: local_folder.is_dir().then_some(local_folder),
+    };
+
+    println!("Exportando perfil '{}' ({} mods) para {} [{}]...", name, profile.mods.len(), version, loader);
+    match export_pack(profile, format, &opts, &output) {
+        Ok(report) => {
+            for key in &report.missing {
+                eprintln!("[WARN] Sin archivo para '{}': no se incluye en el pack", key);
+            }
+            println!(
+                "Pack escrito en '{}': {} referenciados, {} incluidos en overrides, {} sin resolver",
+                output.display(),
+                report.indexed.len(),
+                report.bundled.len(),
+                report.missing.len(),
+            );
+            if report.missing.is_empty() { EXIT_OK } else { EXIT_PARTIAL }
+        }
+        Err(e) => {
+            eprintln!("[ERROR] {}", e);
+            EXIT_FATAL
+        }
+    }
+}
+
+// ── Descargas ────────────────────────────────────────────────
+
+/// Encola un `DownloadJob` por mod en el pool de workers y consume los eventos hasta que terminan.
+/// Devuelve `EXIT_PARTIAL` si alguna descarga falló.
+/// `use_pins`: respetar los archivos fijados de un pack importado (solo si `version` es la del pack).
+fn run_downloads(mods: Vec<ModInfo>, version: &str, loader: &str, output: &str, use_pins: bool) -> u8 {
+    if mods.is_empty() {
+        println!("No hay mods que descargar.");
+        return EXIT_OK;
+    }
+
+    let output_folder = PATHS.modpacks_folder.join(output);
+    if let Err(e) = std::fs::create_dir_all(&output_folder) {
+        eprintln!("[ERROR] No se pudo crear '{}': {}", output_folder.display(), e);
+        return EXIT_FATAL;
+    }
+
+    let mut jobs: Vec<DownloadJob> = mods.into_iter()
+        .map(|m| DownloadJob {
+            key: m.key.clone(),
+            // Archivos fijados de un pack importado: se descargan tal cual, sin resolver
+            pre_resolved: if use_pins { ModDownloadInfo::from_pinned(&m) } else { None },
+            modinfo: m,
+            output_folder: output_folder.to_string_lossy().to_string(),
+            selected_version: version.to_string(),
+            selected_loader: loader.to_string(),
+            content_type: ContentType::Mod,
+            replaces_filename: None,
+            raw_game_version: version.to_string(),
+        })
+        .collect();
+
+    // Identificación exacta por hash antes de caer en la búsqueda por nombre
+    identify_jobs(&mut jobs);
+    prefetch_jobs(&mut jobs);
+    run_jobs(jobs, &output_folder)
+}
+
+/// Envía los jobs al pool de workers e imprime los eventos hasta que terminan todos.
+fn run_jobs(jobs: Vec<DownloadJob>, output_folder: &Path) -> u8 {
+    let (tx_jobs, rx_jobs) = unbounded::<DownloadJob>();
+    let (tx_events, rx_events) = unbounded::<DownloadEvent>();
+    spawn_workers(calculate_worker_count(jobs.len()), rx_jobs, tx_events);
+
+    let total = jobs.len();
+    for job in jobs {
+        let _ = tx_jobs.send(job);
+    }
+    // Al soltar el sender los workers terminan cuando vacían la cola
+    drop(tx_jobs);
+
+    let mut done = 0usize;
+    let mut failed = 0usize;
+    let mut stats = TransferStats::new();
+    for ev in rx_events.iter() {
+        match ev {
+            DownloadEvent::Resolving { key } => println!("[RESOLVING] {}", key),
+            DownloadEvent::Resolved { .. } => {}
+            DownloadEvent::ResolvedInfo { key, confirmed_project_id, version_remote } => {
+                mods_updater::local_mods_ops::cache::update_remote_info(&key, confirmed_project_id, version_remote);
+            }
+            DownloadEvent::Started { key } => {
+                stats.start(&key);
+                println!("[START] {}", key);
+            }
+            DownloadEvent::Progress { key, downloaded, total: file_total } => {
+                stats.record(&key, downloaded, file_total);
+                if let Some(t) = file_total.filter(|t| *t > 0) {
+                    println!("[{:>3.0}%] {} ({})", downloaded as f64 * 100.0 / t as f64, key, stats.summary(total));
+                }
+            }
+            DownloadEvent::Retrying { key, attempt, max, reason } => {
+                eprintln!("[RETRY {}/{}] {}: {}", attempt, max, key, reason);
+            }
+            DownloadEvent::Done { key } => {
+                stats.finish(&key);
+                done += 1;
+                println!("[OK] {} ({}/{})", key, done + failed, total);
+            }
+            DownloadEvent::Error { key, msg } => {
+                stats.finish(&key);
+                failed += 1;
+                eprintln!("[FAIL] {}: {} ({}/{})", key, msg, done + failed, total);
+            }
+        }
+        if done + failed == total {
+            break;
+        }
+    }
+
+    println!("Completados: {}/{}  Errores: {}  Carpeta: {}", done, total, failed, output_folder.display());
+    if failed > 0 { EXIT_PARTIAL } else { EXIT_OK }
+}
@@ -1,6 +1,8 @@
 /// Módulo de utilidades compartidas entre local_mods_ops, fetch y futuros módulos (datapacks, etc.).
 /// Aquí solo viven abstracciones genéricas que NO dependen de ninguna lógica de dominio.
 
+pub mod version_range;
+
 use crossbeam_channel::Receiver;
 use std::thread;
 
@@ -0,0 +1,297 @@
+//! Versiones y rangos de versiones de mods.
+//!
+//! - Predicados de Fabric/Quilt: `">=1.2 <2"` (AND), `"1.19 || 1.20"` (OR; los arrays de
+//!   fabric.mod.json llegan unidos así), `"~1.20"`, `"^2.1"`, `"1.20.x"`, `"*"`.
+//! - Rangos Maven de Forge/NeoForge: `"[47,)"`, `"(1.0,2.0]"`, `"[1.2]"`, uniones `"[1,2),[3,)"`.
+//! - Versiones "sucias" de los jars: `"v1.2"`, `"0.5.3+mc1.20.1"` (el build tras '+' no cuenta),
+//!   `"1.20.1-pre2"` (pre-release, menor que `"1.20.1"`).
+
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Num(u64),
+    Text(String),
+}
+
+impl Ord for Part {
+    // Como en semver: los identificadores numéricos van antes que los alfanuméricos
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Part::Num(a), Part::Num(b)) => a.cmp(b),
+            (Part::Num(_), Part::Text(_)) => Ordering::Less,
+            (Part::Text(_), Part::Num(_)) => Ordering::Greater,
+            (Part::Text(a), Part::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Part {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn parse_part(s: &str) -> Part {
+    s.parse::<u64>().map(Part::Num).unwrap_or_else(|_| Part::Text(s.to_string()))
+}
+
+/// "pre10" → "pre", "10": así "pre2" < "pre10".
+fn split_runs(s: &str) -> Vec<&str> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for (i, c) in s.char_indices().skip(1) {
+        let prev = s[..i].chars().next_back().is_some_and(|p| p.is_ascii_digit());
+        if prev != c.is_ascii_digit() {
+            runs.push(&s[start..i]);
+            start = i;
+        }
+    }
+    runs.push(&s[start..]);
+    runs
+}
+
+/// Versión parseada de forma tolerante: nunca falla, lo que no es número se compara como texto.
+#[derive(Debug, Clone)]
+pub struct Version {
+    core: Vec<Part>,
+    /// Some = pre-release. `"1.20.1-"` (guion final de Fabric) es Some(vacío): la menor de todas.
+    pre: Option<Vec<Part>>,
+}
+
+impl Version {
+    pub fn parse(s: &str) -> Version {
+        let s = s.trim();
+        let s = s.strip_prefix(['v', 'V']).filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit())).unwrap_or(s);
+        let s = s.split('+').next().unwrap_or("");
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (s, None),
+        };
+        Version {
+            core: core.split('.').map(parse_part).collect(),
+            pre: pre.map(|p| p.split('.').filter(|x| !x.is_empty()).flat_map(split_runs).map(parse_part).collect()),
+        }
+    }
+
+    /// Componente `i` del núcleo; los que faltan valen 0 ("1.20" == "1.20.0").
+    fn component(&self, i: usize) -> Part {
+        self.core.get(i).cloned().unwrap_or(Part::Num(0))
+    }
+
+    /// Primera versión fuera de "~"/"^": sube el componente `index` y corta el resto.
+    fn bump(&self, index: usize) -> Version {
+        let mut core: Vec<Part> = (0..index).map(|i| self.component(i)).collect();
+        core.push(match self.component(index) {
+            Part::Num(n) => Part::Num(n + 1),
+            text => text,
+        });
+        // Con el pre-release mínimo, "^1.2" excluye también "2.0-beta"
+        Version { core, pre: Some(Vec::new()) }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.core.len().max(other.core.len());
+        for i in 0..len {
+            let ord = self.component(i).cmp(&other.component(i));
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+        match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// Compara dos versiones de mods tal como vienen en los jars o en las APIs.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    Version::parse(a).cmp(&Version::parse(b))
+}
+
+// ── Rangos ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// Mismos componentes que el prefijo dado ("1.20.x")
+    Prefix(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Predicate {
+    op: Op,
+    version: Version,
+}
+
+impl Predicate {
+    fn matches(&self, v: &Version) -> bool {
+        match self.op {
+            Op::Eq => v == &self.version,
+            Op::Gt => v > &self.version,
+            Op::Ge => v >= &self.version,
+            Op::Lt => v < &self.version,
+            Op::Le => v <= &self.version,
+            Op::Prefix(n) => (0..n).all(|i| v.component(i) == self.version.component(i)),
+        }
+    }
+}
+
+/// Rango de versiones: alternativas (OR) de condiciones que se cumplen a la vez (AND).
+#[derive(Debug, Clone)]
+pub struct VersionRange {
+    alternatives: Vec<Vec<Predicate>>,
+}
+
+impl VersionRange {
+    /// Detecta la sintaxis: con corchetes/paréntesis es Maven, si no, predicados de Fabric.
+    pub fn parse(s: &str) -> Option<VersionRange> {
+        let s = s.trim();
+        if s.starts_with(['[', '(']) {
+            Self::parse_maven(s)
+        } else {
+            Self::parse_fabric(s)
+        }
+    }
+
+    /// Predicados de Fabric/Quilt. None si algún predicado no se entiende (placeholders "${...}").
+    pub fn parse_fabric(s: &str) -> Option<VersionRange> {
+        if s.contains("${") {
+            return None;
+        }
+        let mut alternatives = Vec::new();
+        for alternative in s.split("||") {
+            let mut predicates = Vec::new();
+            for token in alternative.split_whitespace() {
+                predicates.extend(parse_fabric_predicate(token)?);
+            }
+            alternatives.push(predicates);
+        }
+        Some(VersionRange { alternatives })
+    }
+
+    /// Rangos Maven. Una versión suelta sin corchetes es una "recomendación" en Maven: acepta todo.
+    pub fn parse_maven(s: &str) -> Option<VersionRange> {
+        let s = s.trim();
+        if s.contains("${") {
+            return None;
+        }
+        if !s.starts_with(['[', '(']) {
+            return Some(VersionRange { alternatives: vec![Vec::new()] });
+        }
+        let mut alternatives = Vec::new();
+        let mut rest = s;
+        while !rest.is_empty() {
+            if !rest.starts_with(['[', '(']) {
+                return None;
+            }
+            let end = rest.find([']', ')'])?;
+            let (part, tail) = rest.split_at(end + 1);
+            let inner = &part[1..part.len() - 1];
+            let predicates = match inner.split_once(',') {
+                None if part.starts_with('[') && part.ends_with(']') && !inner.trim().is_empty() => {
+                    vec![Predicate { op: Op::Eq, version: Version::parse(inner) }]
+                }
+                None => return None,
+                Some((low, high)) => {
+                    let mut predicates = Vec::new();
+                    if !low.trim().is_empty() {
+                        let op = if part.starts_with('[') { Op::Ge } else { Op::Gt };
+                        predicates.push(Predicate { op, version: Version::parse(low) });
+                    }
+                    if !high.trim().is_empty() {
+                        let op = if part.ends_with(']') { Op::Le } else { Op::Lt };
+                        predicates.push(Predicate { op, version: Version::parse(high) });
+                    }
+                    predicates
+                }
+            };
+            alternatives.push(predicates);
+            rest = tail.trim_start().trim_start_matches(',').trim_start();
+        }
+        if alternatives.is_empty() { None } else { Some(VersionRange { alternatives }) }
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        self.matches_version(&Version::parse(version))
+    }
+
+    pub fn matches_version(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|all| all.iter().all(|p| p.matches(version)))
+    }
+}
+
+fn parse_fabric_predicate(token: &str) -> Option<Vec<Predicate>> {
+    if token == "*" {
+        return Some(Vec::new());
+    }
+    let (op, rest) = [">=", "<=", ">", "<", "=", "~", "^"].iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    if rest.is_empty() {
+        return None;
+    }
+
+    // Comodines: "1.20.x" solo fija los componentes anteriores
+    let parts: Vec<&str> = rest.split(['-', '+']).next().unwrap_or("").split('.').collect();
+    if let Some(wildcard) = parts.iter().position(|p| matches!(*p, "x" | "X" | "*")) {
+        if wildcard == 0 {
+            return Some(Vec::new());
+        }
+        let prefix = Version::parse(&parts[..wildcard].join("."));
+        let next = prefix.bump(wildcard - 1);
+        return Some(match op {
+            ">=" => vec![Predicate { op: Op::Ge, version: prefix }],
+            ">" => vec![Predicate { op: Op::Ge, version: next }],
+            "<" => vec![Predicate { op: Op::Lt, version: prefix }],
+            "<=" => vec![Predicate { op: Op::Lt, version: next }],
+            _ => vec![Predicate { op: Op::Prefix(wildcard), version: prefix }],
+        });
+    }
+
+    let version = Version::parse(rest);
+    Some(match op {
+        ">=" => vec![Predicate { op: Op::Ge, version }],
+        "<=" => vec![Predicate { op: Op::Le, version }],
+        ">" => vec![Predicate { op: Op::Gt, version }],
+        "<" => vec![Predicate { op: Op::Lt, version }],
+        // "~1.20.1" → misma minor; "~1" → misma major
+        "~" => {
+            let upper = version.bump(if version.core.len() > 1 { 1 } else { 0 });
+            vec![Predicate { op: Op::Ge, version }, Predicate { op: Op::Lt, version: upper }]
+        }
+        "^" => {
+            let upper = version.bump(0);
+            vec![Predicate { op: Op::Ge, version }, Predicate { op: Op::Lt, version: upper }]
+        }
+        _ => vec![Predicate { op: Op::Eq, version }],
+    })
+}
+
+/// ¿Cumple `version` el rango? None si el rango no se entiende.
+pub fn satisfies(range: &str, version: &str) -> Option<bool> {
+    VersionRange::parse(range).map(|r| r.matches(version))
+}
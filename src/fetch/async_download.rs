@@ -2,6 +2,7 @@ use crossbeam_channel::{Sender, Receiver};
 use std::sync::Arc;
 use crate::local_mods_ops::ModInfo;
 use crate::fetch::fetch_from_api;
+use crate::fetch::progress::TransferUpdate;
 use crate::common::spawn_worker_pool;
 use crate::fetch::search_provider::ContentType;
 
@@ -11,7 +12,10 @@ pub enum DownloadEvent {
     Resolved { key: String },
     ResolvedInfo { key: String, confirmed_project_id: Option<String>, version_remote: Option<String> },
     Started { key: String },
-    Progress(String, f32),
+    /// Bytes descargados de un archivo; `total` es None si el servidor no informa del tamaño.
+    Progress { key: String, downloaded: u64, total: Option<u64> },
+    /// Fallo transitorio: la descarga se reintenta (intento `attempt` de `max`).
+    Retrying { key: String, attempt: u32, max: u32, reason: String },
     Done { key: String },
     Error { key: String, msg: String },
 }
@@ -29,6 +33,72 @@ pub struct DownloadJob {
     pub pre_resolved: Option<crate::fetch::fetch_from_api::ModDownloadInfo>,
 }
 
+/// Mejor ID de proyecto conocido para un mod: el confirmado, el confirmado en caché para su
+/// detected_project_id, o el detectado en el jar.
+pub fn best_known_project_id(mi: &ModInfo) -> Option<String> {
+    mi.confirmed_project_id.clone()
+        .or_else(|| {
+            mi.detected_project_id.as_deref()
+                .and_then(crate::local_mods_ops::cache::get_confirmed_id)
+        })
+        .or(mi.detected_project_id.clone())
+}
+
+/// Identifica por hash (Modrinth `version_files` + fingerprints de CurseForge) los mods de un lote
+/// antes de encolarlo, fijando `confirmed_project_id` y guardándolo en caché.
+/// Los mods no reconocidos se quedan como estaban y se resuelven por nombre en el worker.
+pub fn identify_jobs(jobs: &mut [DownloadJob]) {
+    let mut mods: Vec<&mut ModInfo> = jobs.iter_mut()
+        .filter(|j| j.pre_resolved.is_none() && j.content_type == ContentType::Mod)
+        .map(|j| &mut j.modinfo)
+        .collect();
+    identify_mods(&mut mods);
+}
+
+/// Igual que `identify_jobs`, sobre una lista de mods (comprobación de actualizaciones, CLI...).
+pub fn identify_mods(mods: &mut [&mut ModInfo]) {
+    if mods.iter().all(|m| m.hashes.is_none()) {
+        return;
+    }
+
+    let snapshot: Vec<ModInfo> = mods.iter().map(|m| (**m).clone()).collect();
+    let identified = fetch_from_api::identify_mods_by_hash(&snapshot, &crate::fetch::cf_api_key());
+    for m in mods.iter_mut() {
+        if let Some(project_id) = identified.get(&m.key) {
+            m.confirmed_project_id = Some(project_id.clone());
+            crate::local_mods_ops::cache::set_confirmed_project_id(&m.key, project_id);
+        }
+    }
+}
+
+/// Resuelve en bloque (Modrinth por hash, CurseForge por ID) los jobs de mods que aún no tienen
+/// descarga, fijando `pre_resolved`. Un lote de cientos de mods son unas pocas peticiones;
+/// los que no se resuelven así los busca el worker uno a uno.
+pub fn prefetch_jobs(jobs: &mut [DownloadJob]) {
+    let wanted = |j: &DownloadJob| {
+        j.pre_resolved.is_none() && j.content_type == ContentType::Mod
+            && (j.modinfo.hashes.is_some() || j.modinfo.confirmed_project_id.is_some())
+    };
+    let mut targets: Vec<(String, String)> = jobs.iter()
+        .filter(|j| wanted(j))
+        .map(|j| (j.raw_game_version.clone(), j.selected_loader.clone()))
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    for (game_version, loader) in targets {
+        let pending: Vec<&mut DownloadJob> = jobs.iter_mut()
+            .filter(|j| wanted(j))
+            .filter(|j| j.raw_game_version == game_version && j.selected_loader == loader)
+            .collect();
+        let mods: Vec<ModInfo> = pending.iter().map(|j| j.modinfo.clone()).collect();
+        let mut found = fetch_from_api::find_mod_downloads_in_bulk(&mods, &game_version, &loader, &crate::fetch::cf_api_key());
+        for job in pending {
+            job.pre_resolved = found.remove(&job.modinfo.key);
+        }
+    }
+}
+
 pub fn spawn_workers(n: usize, rx: Receiver<DownloadJob>, tx_events: Sender<DownloadEvent>) {
     let tx_events = Arc::new(tx_events);
 
@@ -43,12 +113,7 @@ pub fn spawn_workers(n: usize, rx: Receiver<DownloadJob>, tx_events: Sender<Down
             Some(pre)
         } else {
             let cf_key = crate::fetch::cf_api_key();
-            let resolved_id: Option<String> = mi.confirmed_project_id.clone()
-                .or_else(|| {
-                    mi.detected_project_id.as_deref()
-                        .and_then(crate::local_mods_ops::cache::get_confirmed_id)
-                })
-                .or(mi.detected_project_id.clone());
+            let resolved_id = best_known_project_id(&mi);
 
             fetch_from_api::find_mod_download(&mi.name, resolved_id.as_deref(), &job.raw_game_version, &job.selected_loader, &cf_key, &job.content_type)
         };
@@ -62,7 +127,13 @@ pub fn spawn_workers(n: usize, rx: Receiver<DownloadJob>, tx_events: Sender<Down
                 let _ = tx.send(DownloadEvent::ResolvedInfo { key: key.clone(), confirmed_project_id, version_remote });
                 let _ = tx.send(DownloadEvent::Started { key: key.clone() });
 
-                let res = fetch_from_api::download_mod_file(&info.url, &job.output_folder, &info.filename);
+                let res = fetch_from_api::download_mod_file(&info.url, &job.output_folder, &info.filename, &info.expected, |update| {
+                    let ev = match update {
+                        TransferUpdate::Bytes { downloaded, total } => DownloadEvent::Progress { key: key.clone(), downloaded, total },
+                        TransferUpdate::Retry { attempt, reason } => DownloadEvent::Retrying { key: key.clone(), attempt, max: fetch_from_api::MAX_DOWNLOAD_RETRIES, reason },
+                    };
+                    let _ = tx.send(ev);
+                });
                 match res {
                     Ok(_) => { 
                         // If replacing, delete the old file safely after new one is successfully downloaded
@@ -1,10 +1,12 @@
 use reqwest::blocking::Client;
 use reqwest::header;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::Instant;
 use once_cell::sync::Lazy;
 use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::hashing::ExpectedFile;
 
 #[derive(Debug, Deserialize)]
 struct ApiResponse<T> {
@@ -20,14 +22,28 @@ pub struct CurseMod {
     pub summary: Option<String>,
     pub logo: Option<CurseLogo>,
     pub links: Option<CurseLinks>,
+    /// Último archivo por versión de MC + loader (solo viene en `/mods` y `/mods/{id}`)
+    #[serde(default)]
+    pub latest_files_indexes: Vec<CurseFileIndex>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseFileIndex {
+    pub game_version: String,
+    pub file_id: u32,
+    #[serde(default)]
+    pub mod_loader: Option<u32>, // Mismos IDs que `modLoaderType`
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CurseLogo {
     pub thumbnail_url: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CurseLinks {
     pub website_url: String,
 }
@@ -36,14 +52,78 @@ pub struct CurseLinks {
 #[serde(rename_all = "camelCase")]
 pub struct CurseFile {
     pub id: u32,
+    #[serde(default)]
+    pub mod_id: u32,
     pub display_name: String,
     pub file_name: String,
     pub file_date: String,
     pub release_type: u32, // 1=Release, 2=Beta, 3=Alpha
     pub download_url: Option<String>,
     pub game_versions: Vec<String>,
+    #[serde(default)] // La API no siempre lo incluye: el loader también aparece en game_versions
     pub mod_loaders: Vec<String>,
     pub dependencies: Option<Vec<CurseDependency>>,
+    #[serde(default)]
+    pub file_fingerprint: u32,
+    #[serde(default)]
+    pub hashes: Vec<CurseFileHash>,
+    #[serde(default)]
+    pub file_length: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CurseFileHash {
+    pub value: String,
+    pub algo: u32, // 1 = SHA-1, 2 = MD5
+}
+
+impl CurseFile {
+    /// Dependencias del archivo con su tipo (las herramientas se descartan).
+    pub fn unified_deps(&self) -> Vec<crate::fetch::fetch_from_api::UnifiedDependency> {
+        self.dependencies.as_ref()
+            .map(|deps| {
+                deps.iter()
+                    .filter_map(|d| {
+                        let kind = crate::fetch::fetch_from_api::DependencyKind::from_curseforge(d.relation_type)?;
+                        Some(crate::fetch::fetch_from_api::UnifiedDependency { mod_id: d.mod_id.to_string(), kind, version_id: None })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// SHA-1 publicado para el archivo, si CurseForge lo incluye.
+    pub fn sha1(&self) -> Option<String> {
+        self.hashes.iter().find(|h| h.algo == 1).map(|h| h.value.to_lowercase())
+    }
+
+    /// Tamaño y SHA-1 publicados, para verificar la descarga (CurseForge no publica SHA-512).
+    pub fn expected(&self) -> ExpectedFile {
+        ExpectedFile {
+            size: Some(self.file_length).filter(|s| *s > 0),
+            sha1: self.sha1(),
+            ..Default::default()
+        }
+    }
+
+    /// Fecha del archivo como "YYYY-MM-DD".
+    pub fn date_only(&self) -> String {
+        self.file_date.split('T').next().unwrap_or(&self.file_date).to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FingerprintMatches {
+    #[serde(default)]
+    exact_matches: Vec<FingerprintMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintMatch {
+    pub id: u32, // ID del mod
+    pub file: CurseFile,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -80,6 +160,8 @@ impl TokenBucket {
         self.last_refill = now;
     }
 
+    /// Un token por petición HTTP: una llamada batch (`/mods`, `/mods/files`, fingerprints) cuesta
+    /// lo mismo que una individual, lleve los IDs que lleve.
     fn try_acquire(&mut self) -> Option<f64> {
         self.refill();
         if self.tokens >= 1.0 {
@@ -107,12 +189,16 @@ pub fn has_capacity() -> bool {
 
 /// Comprueba si CurseForge está disponible (API key configurada + capacidad).
 pub fn is_available() -> bool {
-    std::env::var("CURSEFORGE_API_KEY").is_ok_and(|k| !k.is_empty()) && has_capacity()
+    !crate::fetch::cf_api_key().is_empty() && has_capacity()
 }
 
+/// Espera hasta tener un token. Se llama una vez por petición HTTP (por cada trozo en los batch).
 fn wait_for_ratelimit() {
     loop {
-        let wait = RATE_LIMIT.lock().unwrap().try_acquire();
+        let wait = match RATE_LIMIT.lock() {
+            Ok(mut bucket) => bucket.try_acquire(),
+            Err(_) => None, // Mutex envenenado: mejor seguir que bloquear las descargas
+        };
         match wait {
             None => return, // Token adquirido
             Some(secs) => {
@@ -125,20 +211,51 @@ fn wait_for_ratelimit() {
 
 // ── Client ───────────────────────────────────────────────────
 
+/// Un cliente por API key, compartido entre llamadas: reutiliza conexiones y sesiones TLS.
+static CURSE_CLIENTS: Lazy<Mutex<HashMap<String, Client>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
 fn build_curse_client(api_key: &str) -> Client {
+    if let Ok(clients) = CURSE_CLIENTS.lock()
+        && let Some(client) = clients.get(api_key)
+    {
+        return client.clone(); // `Client` es un Arc: clonar no crea otro pool
+    }
+
     let mut headers = header::HeaderMap::new();
-    headers.insert("x-api-key", header::HeaderValue::from_str(api_key).unwrap());
-    Client::builder()
+    match header::HeaderValue::from_str(api_key) {
+        Ok(value) => { headers.insert("x-api-key", value); }
+        Err(_) => println!("❌ API key de CurseForge no válida: las peticiones fallarán"),
+    }
+    let client = Client::builder()
         .default_headers(headers)
         .build()
-        .unwrap()
+        .unwrap_or_default();
+    if let Ok(mut clients) = CURSE_CLIENTS.lock() {
+        clients.insert(api_key.to_string(), client.clone());
+    }
+    client
+}
+
+/// ID de `modLoaderType` de CurseForge para un loader de la app.
+/// 0 = Any, 1 = Forge, 2 = Cauldron, 3 = LiteLoader, 4 = Fabric, 5 = Quilt, 6 = NeoForge
+pub fn loader_type_id(loader: &str) -> Option<u32> {
+    match loader.to_lowercase().as_str() {
+        "any" => Some(0),
+        "forge" => Some(1),
+        "cauldron" => Some(2),
+        "liteloader" => Some(3),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
 }
 
 // ── API Functions ────────────────────────────────────────────
 
 pub fn search_curseforge(query: &str, api_key: &str, loader: &Option<String>, version: &Option<String>, offset: u32, limit: u32, class_id: Option<u32>) -> Vec<CurseMod> {
     let client = build_curse_client(api_key);
-    let search_url = "https://api.curseforge.com/v1/mods/search";
+    let search_url = format!("{}/mods/search", crate::fetch::curseforge_api_url());
     
     let limit_str = limit.to_string();
     let offset_str = offset.to_string();
@@ -163,30 +280,14 @@ pub fn search_curseforge(query: &str, api_key: &str, loader: &Option<String>, ve
         }
     }
     
-    // Map loader to CurseForge ID
-    // 1 = Forge, 4 = Fabric, 5 = Quilt, 6 = NeoForge
-    let loader_id_opt = if let Some(l) = loader {
-         match l.to_lowercase().as_str() {
-            "any" => Some("0"),
-            "forge" => Some("1"),
-            "cauldron" => Some("2"),
-            "liteloader" => Some("3"),
-            "fabric" => Some("4"),
-            "quilt" => Some("5"),
-            "neoforge" => Some("6"),
-            _ => None, 
-        }
-    } else {
-        None
-    };
-
-    if let Some(lid) = loader_id_opt {
+    let loader_id_str = loader.as_deref().and_then(loader_type_id).map(|id| id.to_string());
+    if let Some(ref lid) = loader_id_str {
         params.push(("modLoaderType", lid));
     }
 
     wait_for_ratelimit();
 
-    match client.get(search_url).query(&params).send() {
+    match client.get(&search_url).query(&params).send() {
         Ok(resp) => {
             if resp.status().is_success() {
                 let results: ApiResponse<Vec<CurseMod>> = resp.json().unwrap_or(ApiResponse { data: vec![] });
@@ -209,27 +310,17 @@ pub fn fetch_curseforge_project_id(mod_name: &str, api_key: &str) -> Option<u32>
 
 pub fn fetch_curseforge_version_file(mod_id: u32, game_version: &str, loader: &str, api_key: &str, content_type: &ContentType) -> Option<CurseFile> {
     let client = build_curse_client(api_key);
-    let api_url = format!("https://api.curseforge.com/v1/mods/{}/files", mod_id);
+    let api_url = format!("{}/mods/{}/files", crate::fetch::curseforge_api_url(), mod_id);
 
-    // Map loader to CurseForge ID
-    // 0 = Any (used for datapacks), 1 = Forge, 4 = Fabric, 5 = Quilt, 6 = NeoForge
+    // Datapacks: 0 = Any; si el loader no se conoce, Fabric
     let loader_type = match content_type {
-        ContentType::Datapack => "0",
-        _ => match loader.to_lowercase().as_str() {
-            "any" => "0",
-            "forge" => "1",
-            "cauldron" => "2",
-            "liteloader" => "3",
-            "fabric" => "4",
-            "quilt" => "5",
-            "neoforge" => "6",
-            _ => "4",
-        },
+        ContentType::Datapack => "0".to_string(),
+        _ => loader_type_id(loader).unwrap_or(4).to_string(),
     };
 
     let params = [
         ("gameVersion", game_version),
-        ("modLoaderType", loader_type)
+        ("modLoaderType", loader_type.as_str())
     ];
 
     wait_for_ratelimit();
@@ -237,7 +328,7 @@ pub fn fetch_curseforge_version_file(mod_id: u32, game_version: &str, loader: &s
     match client.get(&api_url).query(&params).send() {
         Ok(resp) => {
             if resp.status().is_success() {
-                let files: ApiResponse<Vec<CurseFile>> = resp.json().unwrap();
+                let files: ApiResponse<Vec<CurseFile>> = resp.json().unwrap_or(ApiResponse { data: vec![] });
                 if files.data.is_empty() {
                     None
                 } else {
@@ -246,8 +337,10 @@ pub fn fetch_curseforge_version_file(mod_id: u32, game_version: &str, loader: &s
                         ContentType::Datapack => ".zip",
                         _ => ".jar",
                     };
+                    // El archivo compatible más reciente (la API no garantiza el orden)
                     files.data.into_iter()
-                        .find(|f| f.file_name.to_lowercase().ends_with(target_ext))
+                        .filter(|f| f.file_name.to_lowercase().ends_with(target_ext))
+                        .max_by(|a, b| a.file_date.cmp(&b.file_date))
                 }
             } else {
                 println!("❌ Error en API CurseForge para {}: status {}", mod_id, resp.status());
@@ -263,26 +356,17 @@ pub fn fetch_curseforge_version_file(mod_id: u32, game_version: &str, loader: &s
 
 pub fn fetch_curseforge_project_versions(mod_id: u32, game_version: &str, loader: &str, api_key: &str, content_type: &ContentType) -> Vec<crate::fetch::search_provider::ProjectVersion> {
     let client = build_curse_client(api_key);
-    let api_url = format!("https://api.curseforge.com/v1/mods/{}/files", mod_id);
+    let api_url = format!("{}/mods/{}/files", crate::fetch::curseforge_api_url(), mod_id);
 
-    // Map loader to CurseForge ID
+    // Datapacks: 0 = Any; si el loader no se conoce, Fabric
     let loader_type = match content_type {
-        ContentType::Datapack => "0",
-        _ => match loader.to_lowercase().as_str() {
-            "any" => "0",
-            "forge" => "1",
-            "cauldron" => "2",
-            "liteloader" => "3",
-            "fabric" => "4",
-            "quilt" => "5",
-            "neoforge" => "6",
-            _ => "4",
-        },
+        ContentType::Datapack => "0".to_string(),
+        _ => loader_type_id(loader).unwrap_or(4).to_string(),
     };
 
     let params = [
         ("gameVersion", game_version),
-        ("modLoaderType", loader_type),
+        ("modLoaderType", loader_type.as_str()),
     ];
 
     wait_for_ratelimit();
@@ -305,7 +389,7 @@ pub fn fetch_curseforge_project_versions(mod_id: u32, game_version: &str, loader
                             3 => "A".to_string(),
                             _ => "O".to_string(),
                         };
-                        let date = f.file_date.split('T').next().unwrap_or(&f.file_date).to_string();
+                        let date = f.date_only();
                         crate::fetch::search_provider::ProjectVersion {
                             id: f.id.to_string(),
                             name: f.display_name,
@@ -326,3 +410,143 @@ pub fn fetch_curseforge_project_versions(mod_id: u32, game_version: &str, loader
         }
     }
 }
+
+/// Un archivo concreto de un proyecto (`GET /mods/{modId}/files/{fileId}`), p.ej. las entradas de un manifest.
+pub fn fetch_curseforge_file(mod_id: u32, file_id: u32, api_key: &str) -> Option<CurseFile> {
+    let client = build_curse_client(api_key);
+    let api_url = format!("{}/mods/{}/files/{}", crate::fetch::curseforge_api_url(), mod_id, file_id);
+
+    wait_for_ratelimit();
+
+    match client.get(&api_url).send() {
+        Ok(resp) => {
+            if resp.status().is_success() {
+                resp.json::<ApiResponse<CurseFile>>().ok().map(|r| r.data)
+            } else {
+                println!("❌ Error en API CurseForge para {}/{}: status {}", mod_id, file_id, resp.status());
+                None
+            }
+        }
+        Err(e) => {
+            println!("❌ Error consultando API de CurseForge: {}", e);
+            None
+        }
+    }
+}
+
+/// Identifica archivos por fingerprint (MurmurHash2) con `POST /fingerprints/432`.
+/// Devuelve fingerprint → coincidencia exacta (mod + fichero) solo para los reconocidos.
+pub fn fetch_fingerprint_matches(fingerprints: &[u32], api_key: &str) -> HashMap<u32, FingerprintMatch> {
+    let client = build_curse_client(api_key);
+    let api_url = format!("{}/fingerprints/432", crate::fetch::curseforge_api_url());
+    let body = serde_json::json!({ "fingerprints": fingerprints });
+
+    wait_for_ratelimit();
+
+    match client.post(&api_url).json(&body).send() {
+        Ok(resp) => {
+            if resp.status().is_success() {
+                let matches: ApiResponse<FingerprintMatches> = resp.json()
+                    .unwrap_or(ApiResponse { data: FingerprintMatches { exact_matches: vec![] } });
+                matches.data.exact_matches.into_iter()
+                    .map(|m| (m.file.file_fingerprint, m))
+                    .collect()
+            } else {
+                println!("❌ Error en API CurseForge (fingerprints): status {}", resp.status());
+                HashMap::new()
+            }
+        }
+        Err(e) => {
+            println!("❌ Error consultando API de CurseForge: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Máximo de IDs por petición batch (`/mods`, `/mods/files`).
+const MAX_IDS_PER_REQUEST: usize = 500;
+
+/// Varios proyectos en una petición (`POST /mods`), indexados por ID. Incluyen `latest_files_indexes`.
+pub fn fetch_curseforge_mods(mod_ids: &[u32], api_key: &str) -> HashMap<u32, CurseMod> {
+    let client = build_curse_client(api_key);
+    let api_url = format!("{}/mods", crate::fetch::curseforge_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in mod_ids.chunks(MAX_IDS_PER_REQUEST) {
+        let body = serde_json::json!({ "modIds": chunk });
+
+        wait_for_ratelimit();
+
+        match client.post(&api_url).json(&body).send() {
+            Ok(resp) => {
+                if resp.status().is_success() {
+                    let mods: ApiResponse<Vec<CurseMod>> = resp.json().unwrap_or(ApiResponse { data: vec![] });
+                    found.extend(mods.data.into_iter().map(|m| (m.id, m)));
+                } else {
+                    println!("❌ Error en API CurseForge (mods): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de CurseForge: {}", e);
+            }
+        }
+    }
+    found
+}
+
+/// Varios archivos por su ID en una petición (`POST /mods/files`), indexados por ID de archivo.
+pub fn fetch_curseforge_files(file_ids: &[u32], api_key: &str) -> HashMap<u32, CurseFile> {
+    let client = build_curse_client(api_key);
+    let api_url = format!("{}/mods/files", crate::fetch::curseforge_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in file_ids.chunks(MAX_IDS_PER_REQUEST) {
+        let body = serde_json::json!({ "fileIds": chunk });
+
+        wait_for_ratelimit();
+
+        match client.post(&api_url).json(&body).send() {
+            Ok(resp) => {
+                if resp.status().is_success() {
+                    let files: ApiResponse<Vec<CurseFile>> = resp.json().unwrap_or(ApiResponse { data: vec![] });
+                    found.extend(files.data.into_iter().map(|f| (f.id, f)));
+                } else {
+                    println!("❌ Error en API CurseForge (files): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de CurseForge: {}", e);
+            }
+        }
+    }
+    found
+}
+
+/// Último archivo de cada proyecto para `game_version` + `loader`, en dos peticiones batch:
+/// `latest_files_indexes` de `POST /mods` y los archivos elegidos con `POST /mods/files`.
+/// Los proyectos sin archivo para ese destino no aparecen.
+pub fn fetch_curseforge_latest_files(mod_ids: &[u32], game_version: &str, loader: &str, api_key: &str) -> HashMap<u32, CurseFile> {
+    let loader_id = loader_type_id(loader);
+    let mods = fetch_curseforge_mods(mod_ids, api_key);
+
+    let mut chosen: HashMap<u32, u32> = HashMap::new(); // archivo → proyecto
+    for m in mods.values() {
+        let newest = m.latest_files_indexes.iter()
+            .filter(|i| i.game_version == game_version)
+            .filter(|i| loader_id.is_none_or(|l| i.mod_loader.is_none_or(|ml| ml == l)))
+            .map(|i| i.file_id)
+            .max();
+        if let Some(file_id) = newest {
+            chosen.insert(file_id, m.id);
+        }
+    }
+    if chosen.is_empty() {
+        return HashMap::new();
+    }
+
+    let file_ids: Vec<u32> = chosen.keys().copied().collect();
+    fetch_curseforge_files(&file_ids, api_key).into_iter()
+        .filter(|(_, f)| f.file_name.to_lowercase().ends_with(".jar"))
+        .filter_map(|(file_id, f)| chosen.get(&file_id).map(|mod_id| (*mod_id, f)))
+        .collect()
+}
@@ -1,11 +1,15 @@
 use reqwest::blocking::Client;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
 use super::modrinth_api;
 use super::curseforge_api;
 use super::modrinth_api::ModrinthSearchHit;
 use super::search_provider::ContentType;
+use crate::local_mods_ops::hashing::{self, ExpectedFile};
+use super::progress::{TransferUpdate, PROGRESS_INTERVAL};
 
 static DOWNLOAD_CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
@@ -17,7 +21,43 @@ static DOWNLOAD_CLIENT: Lazy<Client> = Lazy::new(|| {
 #[derive(Debug, Clone)]
 pub struct UnifiedDependency {
     pub mod_id: String,
-    // Source identifier could be added here if needed, but for now we'll just store the ID
+    pub kind: DependencyKind,
+    /// Versión exacta que fija el autor (Modrinth `version_id`); None = la más reciente compatible
+    pub version_id: Option<String>,
+}
+
+/// Tipo de dependencia, común a Modrinth (`dependency_type`) y CurseForge (`relationType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+    /// Ya va dentro del jar: no hay que descargarla
+    Embedded,
+}
+
+impl DependencyKind {
+    pub fn from_modrinth(dependency_type: &str) -> Option<Self> {
+        match dependency_type {
+            "required" => Some(DependencyKind::Required),
+            "optional" => Some(DependencyKind::Optional),
+            "incompatible" => Some(DependencyKind::Incompatible),
+            "embedded" => Some(DependencyKind::Embedded),
+            _ => None,
+        }
+    }
+
+    /// 1 = EmbeddedLibrary, 2 = Optional, 3 = Required, 4 = Tool, 5 = Incompatible, 6 = Include.
+    /// Las herramientas (4) no son dependencias del juego.
+    pub fn from_curseforge(relation_type: u32) -> Option<Self> {
+        match relation_type {
+            1 | 6 => Some(DependencyKind::Embedded),
+            2 => Some(DependencyKind::Optional),
+            3 => Some(DependencyKind::Required),
+            5 => Some(DependencyKind::Incompatible),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -27,30 +67,54 @@ pub struct ModDownloadInfo {
     pub slug: String,   // Modrinth slug (same as detected_project_id for scanned mods)
     pub url: String,
     pub project_id: String,
-    pub version_remote: String,
+    pub version_remote: String, // Número de versión real del archivo remoto (no la versión de MC)
+    pub date_published: String, // "YYYY-MM-DD"
+    pub expected: ExpectedFile, // Tamaño y hashes publicados por la plataforma
     pub dependencies: Vec<UnifiedDependency>,
 }
 
+impl ModDownloadInfo {
+    /// Info de descarga a partir del archivo fijado de un pack importado, sin consultar ninguna API.
+    pub fn from_pinned(mi: &crate::local_mods_ops::ModInfo) -> Option<Self> {
+        let pin = mi.pinned.as_ref()?;
+        Some(ModDownloadInfo {
+            filename: pin.filename.clone(),
+            name: mi.name.clone(),
+            slug: String::new(),
+            url: pin.url.clone(),
+            project_id: mi.confirmed_project_id.clone().unwrap_or_default(),
+            version_remote: pin.version_id.clone().unwrap_or_default(),
+            date_published: String::new(),
+            expected: pin.expected.clone(),
+            dependencies: Vec::new(),
+        })
+    }
+}
+
+/// Info de descarga del archivo principal de una versión de Modrinth.
+fn modrinth_download_info(version: &modrinth_api::ModrinthVersion, project_id: &str, title: String, slug: String) -> Option<ModDownloadInfo> {
+    let file = version.first_file()?;
+    Some(ModDownloadInfo {
+        filename: file.filename.clone(),
+        name: title,
+        slug,
+        url: file.url.clone(),
+        project_id: project_id.to_string(),
+        version_remote: version.version_number.clone(),
+        date_published: version.date_only(),
+        expected: file.expected(),
+        dependencies: version.unified_deps(),
+    })
+}
+
 /// Intenta resolver un mod en Modrinth (por ID directo, búsqueda por ID, y búsqueda por nombre).
 fn try_modrinth(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader: &str, content_type: &ContentType) -> Option<ModDownloadInfo> {
     // Helper para verificar y extraer info de un hit de Modrinth
     let try_find_version = |hit: &ModrinthSearchHit| -> Option<ModDownloadInfo> {
-        if let Some(modrinth_version) = modrinth_api::fetch_modrinth_version(&hit.project_id, game_version, loader, content_type) {
-            if let Some(file) = modrinth_version.first_file() {
-                println!("✅ Encontrado en Modrinth: {} (Project: {})", file.filename, hit.title);
-                
-                return Some(ModDownloadInfo {
-                    filename: file.filename.clone(),
-                    name: hit.title.clone(),
-                    slug: hit.slug.clone(),
-                    url: file.url.clone(),
-                    project_id: hit.project_id.clone(),
-                    version_remote: game_version.to_string(),
-                    dependencies: modrinth_version.required_deps(),
-                });
-            }
-        }
-        return None;
+        let modrinth_version = modrinth_api::fetch_modrinth_version(&hit.project_id, game_version, loader, content_type)?;
+        let info = modrinth_download_info(&modrinth_version, &hit.project_id, hit.title.clone(), hit.slug.clone())?;
+        println!("✅ Encontrado en Modrinth: {} (Project: {})", info.filename, hit.title);
+        Some(info)
     };
 
     // 1. Intento por ID directo
@@ -61,15 +125,7 @@ fn try_modrinth(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader
                 
                 let (title, slug) = modrinth_api::fetch_modrinth_project_info(id)
                     .unwrap_or_else(|| (file.filename.clone(), id.to_string()));
-                return Some(ModDownloadInfo {
-                    filename: file.filename.clone(),
-                    name: title,
-                    slug,
-                    url: file.url.clone(),
-                    project_id: id.to_string(),
-                    version_remote: game_version.to_string(),
-                    dependencies: modrinth_version.required_deps(),
-                });
+                return modrinth_download_info(&modrinth_version, id, title, slug);
             }
         }
 
@@ -84,7 +140,10 @@ fn try_modrinth(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader
         }
     }
 
-    // 2. Intento por nombre
+    // 2. Intento por nombre. Sin nombre todo título "coincide": no se busca
+    if mod_name.trim().is_empty() {
+        return None;
+    }
     let hits = modrinth_api::search_modrinth_project(mod_name, &None, &None, 0, 5, &[]);
     for hit in &hits {
         let slug_match = mod_id.map_or(false, |id| hit.slug == id);
@@ -99,25 +158,22 @@ fn try_modrinth(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader
     return None;
 }
 
-/// Intenta resolver un mod en CurseForge (búsqueda por nombre → fichero de versión).
-fn try_curseforge(mod_name: &str, game_version: &str, loader: &str, curseforge_api_key: &str, content_type: &ContentType) -> Option<ModDownloadInfo> {
+/// Intenta resolver un mod en CurseForge (ID numérico directo, o búsqueda por nombre → fichero de versión).
+fn try_curseforge(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader: &str, curseforge_api_key: &str, content_type: &ContentType) -> Option<ModDownloadInfo> {
     if curseforge_api_key.is_empty() {
         return None;
     }
 
-    if let Some(curseforge_id) = curseforge_api::fetch_curseforge_project_id(mod_name, curseforge_api_key) {
+    // Un ID numérico es un project_id de CurseForge (p.ej. identificado por fingerprint): no hace falta buscar
+    let curseforge_id = mod_id.and_then(|id| id.parse::<u32>().ok())
+        .or_else(|| if mod_name.trim().is_empty() { None } else { curseforge_api::fetch_curseforge_project_id(mod_name, curseforge_api_key) });
+
+    if let Some(curseforge_id) = curseforge_id {
         if let Some(curse_file) = curseforge_api::fetch_curseforge_version_file(curseforge_id, game_version, loader, curseforge_api_key, content_type) {
-            if let Some(download_url) = curse_file.download_url {
+            if let Some(download_url) = curse_file.download_url.clone() {
                 println!("✅ Encontrado en CurseForge: {}", curse_file.file_name);
                 
-                let dependencies = curse_file.dependencies.as_ref()
-                    .map(|deps| {
-                        deps.iter()
-                            .filter(|d| d.relation_type == 3) // 3 = requiredDependency
-                            .map(|d| UnifiedDependency { mod_id: d.mod_id.to_string() })
-                            .collect()
-                    })
-                    .unwrap_or_default();
+                let dependencies = curse_file.unified_deps();
 
                 return Some(ModDownloadInfo {
                     filename: curse_file.file_name.clone(),
@@ -125,7 +181,9 @@ fn try_curseforge(mod_name: &str, game_version: &str, loader: &str, curseforge_a
                     slug: String::new(), // CurseForge has no Modrinth slug
                     url: download_url,
                     project_id: curseforge_id.to_string(),
-                    version_remote: curse_file.file_name,
+                    version_remote: curse_file.display_name.clone(),
+                    date_published: curse_file.date_only(),
+                    expected: curse_file.expected(),
                     dependencies,
                 });
             } else {
@@ -136,11 +194,153 @@ fn try_curseforge(mod_name: &str, game_version: &str, loader: &str, curseforge_a
     return None;
 }
 
+/// Identifica mods locales por hash antes de recurrir a la búsqueda por nombre.
+/// Primero Modrinth (SHA-1 en bulk vía `version_files`); los que no reconoce se buscan por
+/// fingerprint en CurseForge. Devuelve clave del mod → project_id exacto.
+pub fn identify_mods_by_hash(mods: &[crate::local_mods_ops::ModInfo], curseforge_api_key: &str) -> HashMap<String, String> {
+    let mut identified: HashMap<String, String> = HashMap::new();
+
+    let hashed: Vec<(&str, &crate::local_mods_ops::hashing::FileHashes)> = mods.iter()
+        .filter_map(|m| m.hashes.as_ref().map(|h| (m.key.as_str(), h)))
+        .collect();
+    if hashed.is_empty() {
+        return identified;
+    }
+
+    // 1. Modrinth
+    let sha1s: Vec<String> = hashed.iter().map(|(_, h)| h.sha1.clone()).collect();
+    let versions = modrinth_api::fetch_versions_by_hashes(&sha1s, "sha1");
+    for (key, hashes) in &hashed {
+        if let Some(version) = versions.get(&hashes.sha1)
+            && !version.project_id.is_empty()
+        {
+            identified.insert(key.to_string(), version.project_id.clone());
+        }
+    }
+
+    // 2. CurseForge (solo los que Modrinth no conoce)
+    if !curseforge_api_key.is_empty() {
+        let pending: Vec<&(&str, &crate::local_mods_ops::hashing::FileHashes)> = hashed.iter()
+            .filter(|(key, _)| !identified.contains_key(*key))
+            .collect();
+        if !pending.is_empty() {
+            let fingerprints: Vec<u32> = pending.iter().map(|(_, h)| h.cf_fingerprint).collect();
+            let matches = curseforge_api::fetch_fingerprint_matches(&fingerprints, curseforge_api_key);
+            for (key, hashes) in pending {
+                if let Some(m) = matches.get(&hashes.cf_fingerprint) {
+                    identified.insert(key.to_string(), m.id.to_string());
+                }
+            }
+        }
+    }
+
+    println!("🔑 Identificados por hash: {}/{} mods", identified.len(), hashed.len());
+    identified
+}
+
+/// Archivo más reciente para `game_version` + `loader` de todos los mods con hash a la vez:
+/// una petición a `version_files/update` y otra a `/projects` (para título y slug) por cada
+/// centenar de mods, en lugar de dos o tres por mod. Devuelve clave del mod → descarga; los que
+/// Modrinth no reconoce o no tienen versión para ese destino se resuelven luego uno a uno.
+pub fn find_mod_downloads_by_hash(mods: &[crate::local_mods_ops::ModInfo], game_version: &str, loader: &str) -> HashMap<String, ModDownloadInfo> {
+    let mut found = HashMap::new();
+
+    let hashed: Vec<(&str, &str)> = mods.iter()
+        .filter_map(|m| m.hashes.as_ref().map(|h| (m.key.as_str(), h.sha1.as_str())))
+        .collect();
+    if hashed.is_empty() {
+        return found;
+    }
+
+    let sha1s: Vec<String> = hashed.iter().map(|(_, sha1)| sha1.to_string()).collect();
+    let latest = modrinth_api::fetch_latest_versions_by_hashes(&sha1s, "sha1", game_version, loader);
+    if latest.is_empty() {
+        return found;
+    }
+
+    let mut project_ids: Vec<String> = latest.values().map(|v| v.project_id.clone()).filter(|id| !id.is_empty()).collect();
+    project_ids.sort();
+    project_ids.dedup();
+    let projects = modrinth_api::fetch_modrinth_projects(&project_ids);
+
+    for (key, sha1) in hashed {
+        let Some(version) = latest.get(sha1) else { continue };
+        let (title, slug) = match projects.get(&version.project_id) {
+            Some(project) => (project.title.clone(), project.slug.clone()),
+            None => (version.name.clone(), version.project_id.clone()),
+        };
+        if let Some(info) = modrinth_download_info(version, &version.project_id, title, slug) {
+            found.insert(key.to_string(), info);
+        }
+    }
+
+    println!("📦 Resueltos en bloque por hash: {}/{} mods", found.len(), mods.len());
+    found
+}
+
+/// Igual que `find_mod_downloads_by_hash`, en CurseForge: los mods con ID numérico confirmado
+/// (identificados por fingerprint) se resuelven con dos peticiones batch en total.
+pub fn find_curseforge_downloads(mods: &[crate::local_mods_ops::ModInfo], game_version: &str, loader: &str, curseforge_api_key: &str) -> HashMap<String, ModDownloadInfo> {
+    let mut found = HashMap::new();
+    if curseforge_api_key.is_empty() {
+        return found;
+    }
+
+    let with_id: Vec<(&str, u32)> = mods.iter()
+        .filter_map(|m| m.confirmed_project_id.as_deref().and_then(|id| id.parse::<u32>().ok()).map(|id| (m.key.as_str(), id)))
+        .collect();
+    if with_id.is_empty() {
+        return found;
+    }
+
+    let mut ids: Vec<u32> = with_id.iter().map(|(_, id)| *id).collect();
+    ids.sort();
+    ids.dedup();
+    let files = curseforge_api::fetch_curseforge_latest_files(&ids, game_version, loader, curseforge_api_key);
+
+    for (key, id) in with_id {
+        let Some(file) = files.get(&id) else { continue };
+        let Some(url) = file.download_url.clone() else { continue };
+        found.insert(key.to_string(), ModDownloadInfo {
+            filename: file.file_name.clone(),
+            name: file.file_name.clone(),
+            slug: String::new(),
+            url,
+            project_id: id.to_string(),
+            version_remote: file.display_name.clone(),
+            date_published: file.date_only(),
+            expected: file.expected(),
+            dependencies: file.unified_deps(),
+        });
+    }
+
+    println!("📦 Resueltos en bloque en CurseForge: {}/{} mods", found.len(), mods.len());
+    found
+}
+
+/// Resolución en bloque de una carpeta entera: Modrinth por hash y, para los que queden,
+/// CurseForge por ID. Lo que no salga aquí se busca luego mod a mod con `find_mod_download`.
+pub fn find_mod_downloads_in_bulk(mods: &[crate::local_mods_ops::ModInfo], game_version: &str, loader: &str, curseforge_api_key: &str) -> HashMap<String, ModDownloadInfo> {
+    let mut found = find_mod_downloads_by_hash(mods, game_version, loader);
+    let rest: Vec<crate::local_mods_ops::ModInfo> = mods.iter().filter(|m| !found.contains_key(&m.key)).cloned().collect();
+    found.extend(find_curseforge_downloads(&rest, game_version, loader, curseforge_api_key));
+    found
+}
+
 /// Intenta encontrar un mod usando balanceo dinámico entre Modrinth y CurseForge.
 /// Ambas APIs se intentan SIEMPRE antes de reportar error — el balanceo solo cambia el orden.
 pub fn find_mod_download(mod_name: &str, mod_id: Option<&str>, game_version: &str, loader: &str, curseforge_api_key: &str, content_type: &ContentType) -> Option<ModDownloadInfo> {
     println!("🔍 Procesando '{}' (ID: {:?}) v{} [{}]...", mod_name, mod_id.unwrap_or("N/A"), game_version, loader);
 
+    // Un ID numérico solo existe en CurseForge (dependencias de CF, fingerprint): Modrinth no lo conoce
+    if mod_id.is_some_and(|id| id.parse::<u32>().is_ok()) {
+        if let Some(info) = try_curseforge(mod_name, mod_id, game_version, loader, curseforge_api_key, content_type) {
+            return Some(info);
+        }
+        println!("❌ No se encontró '{}' (ID: {:?}) en CurseForge.", mod_name, mod_id.unwrap_or("N/A"));
+        return None;
+    }
+
     // Decidir orden según capacidad disponible
     let modrinth_first = modrinth_api::has_capacity() || !curseforge_api::is_available();
 
@@ -150,13 +350,13 @@ pub fn find_mod_download(mod_name: &str, mod_id: Option<&str>, game_version: &st
             return Some(info);
         }
         println!("⚠️ No encontrado en Modrinth. Probando en CurseForge...");
-        if let Some(info) = try_curseforge(mod_name, game_version, loader, curseforge_api_key, content_type) {
+        if let Some(info) = try_curseforge(mod_name, mod_id, game_version, loader, curseforge_api_key, content_type) {
             return Some(info);
         }
     } else {
         // Swap: CurseForge primero (Modrinth agotado)
         println!("🔄 Modrinth rate limit bajo, priorizando CurseForge...");
-        if let Some(info) = try_curseforge(mod_name, game_version, loader, curseforge_api_key, content_type) {
+        if let Some(info) = try_curseforge(mod_name, mod_id, game_version, loader, curseforge_api_key, content_type) {
             return Some(info);
         }
         println!("⚠️ No encontrado en CurseForge. Probando en Modrinth...");
@@ -169,20 +369,42 @@ pub fn find_mod_download(mod_name: &str, mod_id: Option<&str>, game_version: &st
     return None;
 }
 
+/// Dependencia declarada incompatible con algo que ya está en el destino (modpack o perfil)
+/// o con otra de las dependencias que se van a instalar.
+#[derive(Debug, Clone)]
+pub struct DependencyConflict {
+    pub mod_id: String,
+    /// Nombre del mod que declara la incompatibilidad
+    pub declared_by: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DependencyResolution {
+    /// Obligatorias (transitivas) que faltan: se descargan junto al mod
+    pub required: Vec<ModDownloadInfo>,
+    /// Opcionales directas del mod: se ofrecen como sugerencia, no se descargan por defecto
+    pub optional: Vec<ModDownloadInfo>,
+    pub conflicts: Vec<DependencyConflict>,
+}
+
 /// Transitively resolves all **required** dependencies of `root_project_id`.
 /// Uses BFS. Skips IDs in `already_installed` and detects cycles via `visited`.
-/// Returns a flat list of download infos for every NEW dependency found.
+/// Las `embedded` ya van dentro del jar y se ignoran; las opcionales directas del mod se devuelven
+/// aparte como sugerencias; las incompatibles se cruzan con `already_installed` y con lo resuelto.
+/// Si una dependencia fija `version_id`, se descarga esa versión y no la más reciente.
 pub fn resolve_all_dependencies(
     root_project_id: &str,
     game_version: &str,
     loader: &str,
     cf_key: &str,
     already_installed: &HashSet<String>,
-) -> Vec<ModDownloadInfo> {
-    let mut results: Vec<ModDownloadInfo> = Vec::new();
+) -> DependencyResolution {
+    let mut resolution = DependencyResolution::default();
     let mut visited: HashSet<String> = HashSet::new();
-    // Seed the queue with the root mod's direct dependencies
-    let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
+    let mut queue: std::collections::VecDeque<UnifiedDependency> = std::collections::VecDeque::new();
+    let mut optional: Vec<UnifiedDependency> = Vec::new();
+    // (ID incompatible, mod que lo declara)
+    let mut incompatible: Vec<(String, String)> = Vec::new();
 
     // Mark root as visited so we don't download it as its own dep
     visited.insert(root_project_id.to_string());
@@ -192,39 +414,109 @@ pub fn resolve_all_dependencies(
     }
 
     // Fetch the root's deps
-    if let Some(root_info) = find_mod_download("", Some(root_project_id), game_version, loader, cf_key, &ContentType::Mod) {
-        for dep in root_info.dependencies {
-            if !visited.contains(&dep.mod_id) {
-                queue.push_back(dep.mod_id);
-            }
+    let Some(root_info) = find_mod_download("", Some(root_project_id), game_version, loader, cf_key, &ContentType::Mod) else {
+        return resolution;
+    };
+    for dep in &root_info.dependencies {
+        match dep.kind {
+            DependencyKind::Required => queue.push_back(dep.clone()),
+            DependencyKind::Optional => optional.push(dep.clone()),
+            DependencyKind::Incompatible => incompatible.push((dep.mod_id.clone(), root_info.name.clone())),
+            DependencyKind::Embedded => {}
         }
     }
 
-    while let Some(dep_id) = queue.pop_front() {
-        if visited.contains(&dep_id) {
+    while let Some(dep) = queue.pop_front() {
+        if !dep.mod_id.is_empty() && !visited.insert(dep.mod_id.clone()) {
             continue;
         }
-        visited.insert(dep_id.clone());
 
-        if let Some(info) = find_mod_download("", Some(&dep_id), game_version, loader, cf_key, &ContentType::Mod) {
+        if let Some(info) = resolve_dependency(&dep, game_version, loader, cf_key) {
+            // Las que solo fijaban version_id no sabían su proyecto hasta ahora
+            if dep.mod_id.is_empty() && !visited.insert(info.project_id.clone()) {
+                continue;
+            }
+            // Los IDs locales suelen ser el slug (sodium, fabric-api...), incluidos los de jars incluidos
+            if already_installed.contains(&info.slug) {
+                continue;
+            }
             // Enqueue transitive deps
             for transitive in &info.dependencies {
-                if !visited.contains(&transitive.mod_id) {
-                    queue.push_back(transitive.mod_id.clone());
+                match transitive.kind {
+                    DependencyKind::Required if !visited.contains(&transitive.mod_id) => queue.push_back(transitive.clone()),
+                    DependencyKind::Incompatible => incompatible.push((transitive.mod_id.clone(), info.name.clone())),
+                    _ => {}
                 }
             }
-            results.push(info);
+            resolution.required.push(info);
         } else {
-            println!("⚠️  No se pudo resolver la dependencia transitiva: {}", dep_id);
+            println!("⚠️  No se pudo resolver la dependencia transitiva: {}", dep.version_id.as_deref().unwrap_or(&dep.mod_id));
+        }
+    }
+
+    for dep in optional {
+        if dep.mod_id.is_empty() || visited.contains(&dep.mod_id) {
+            continue;
+        }
+        if let Some(info) = resolve_dependency(&dep, game_version, loader, cf_key) {
+            // Lo instalado se conoce por slug, igual que con las obligatorias
+            if already_installed.contains(&info.slug) {
+                continue;
+            }
+            resolution.optional.push(info);
+        }
+    }
+
+    // Modrinth declara las incompatibles por project ID, pero lo instalado se conoce por slug/mod id:
+    // los slugs se piden todos juntos
+    let mut unknown_ids: Vec<String> = incompatible.iter()
+        .map(|(id, _)| id.clone())
+        .filter(|id| !already_installed.contains(id) && id.parse::<u32>().is_err())
+        .collect();
+    unknown_ids.sort();
+    unknown_ids.dedup();
+    let slugs: HashMap<String, String> = if unknown_ids.is_empty() {
+        HashMap::new()
+    } else {
+        modrinth_api::fetch_modrinth_projects(&unknown_ids).into_iter().map(|(id, p)| (id, p.slug)).collect()
+    };
+
+    for (mod_id, declared_by) in incompatible {
+        let slug = slugs.get(&mod_id);
+        let clashes = already_installed.contains(&mod_id)
+            || slug.is_some_and(|s| already_installed.contains(s))
+            || resolution.required.iter().any(|r| r.project_id == mod_id || r.slug == mod_id);
+        // Con el slug el aviso se entiende mejor que con el ID
+        let mod_id = slug.cloned().unwrap_or(mod_id);
+        if clashes && !resolution.conflicts.iter().any(|c| c.mod_id == mod_id && c.declared_by == declared_by) {
+            println!("⛔ {} es incompatible con {}", declared_by, mod_id);
+            resolution.conflicts.push(DependencyConflict { mod_id, declared_by });
         }
     }
 
-    results
+    resolution
+}
+
+/// Resuelve una dependencia: la versión fijada si la hay (solo Modrinth), si no la más reciente.
+fn resolve_dependency(dep: &UnifiedDependency, game_version: &str, loader: &str, cf_key: &str) -> Option<ModDownloadInfo> {
+    if let Some(version_id) = &dep.version_id
+        && let Some(version) = modrinth_api::fetch_modrinth_version_by_id(version_id)
+    {
+        let (title, slug) = modrinth_api::fetch_modrinth_project_info(&version.project_id)
+            .unwrap_or_else(|| (version.name.clone(), version.project_id.clone()));
+        println!("📌 Dependencia fijada: {} {}", title, version.version_number);
+        return modrinth_download_info(&version, &version.project_id, title, slug);
+    }
+    if dep.mod_id.is_empty() {
+        return None;
+    }
+    find_mod_download("", Some(&dep.mod_id), game_version, loader, cf_key, &ContentType::Mod)
 }
 
 /// Obtiene los nombres legibles de las dependencias **requeridas directas** de un proyecto.
 /// Versión ligera pensada para mostrar info en la UI de búsqueda sin descargar nada.
 /// Intenta Modrinth primero (por project_id), luego CurseForge si falla.
+/// Son como mucho tres peticiones (dos en CurseForge), tenga las dependencias que tenga.
 pub fn fetch_dependency_names(
     project_id: &str,
     game_version: &str,
@@ -233,15 +525,11 @@ pub fn fetch_dependency_names(
 ) -> Vec<String> {
     // 1. Try Modrinth
     if let Some(ver) = modrinth_api::fetch_modrinth_version(project_id, game_version, loader, &ContentType::Mod) {
-        let deps = ver.required_deps();
+        let deps: Vec<UnifiedDependency> = ver.unified_deps().into_iter()
+            .filter(|d| d.kind == DependencyKind::Required)
+            .collect();
         if !deps.is_empty() {
-            return deps.iter()
-                .filter_map(|d| {
-                    modrinth_api::fetch_modrinth_project_info(&d.mod_id)
-                        .map(|(title, _slug)| title)
-                        .or_else(|| Some(d.mod_id.clone()))
-                })
-                .collect();
+            return dependency_titles(&deps);
         }
         // Version found but no deps → return empty
         return Vec::new();
@@ -251,25 +539,88 @@ pub fn fetch_dependency_names(
     if !cf_key.is_empty() {
         if let Ok(cf_id) = project_id.parse::<u32>() {
             if let Some(cf_file) = curseforge_api::fetch_curseforge_version_file(cf_id, game_version, loader, cf_key, &ContentType::Mod) {
-                let deps: Vec<String> = cf_file.dependencies.as_ref()
-                    .map(|deps| {
-                        deps.iter()
-                            .filter(|d| d.relation_type == 3) // requiredDependency
-                            .map(|d| format!("CF:{}", d.mod_id))
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                return deps;
+                let ids: Vec<u32> = cf_file.unified_deps().into_iter()
+                    .filter(|d| d.kind == DependencyKind::Required)
+                    .filter_map(|d| d.mod_id.parse().ok())
+                    .collect();
+                if ids.is_empty() {
+                    return Vec::new();
+                }
+                // Todos los nombres en una sola petición batch
+                let projects = curseforge_api::fetch_curseforge_mods(&ids, cf_key);
+                return ids.iter()
+                    .map(|id| projects.get(id).map(|p| p.name.clone()).unwrap_or_else(|| format!("CF:{}", id)))
+                    .collect();
             }
         }
     }
     return Vec::new();
 }
 
-pub fn download_mod_file(file_url: &str, output_folder: &str, filename: &str) -> Result<(), std::io::Error> {
-    let mut resp_file = DOWNLOAD_CLIENT.get(file_url).send()
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+/// Títulos de dependencias de Modrinth con las llamadas bulk: `/versions` para las que solo fijan
+/// versión y `/projects` para todas. Si un proyecto no aparece se muestra su ID.
+fn dependency_titles(deps: &[UnifiedDependency]) -> Vec<String> {
+    let version_ids: Vec<String> = deps.iter()
+        .filter(|d| d.mod_id.is_empty())
+        .filter_map(|d| d.version_id.clone())
+        .collect();
+    let versions = if version_ids.is_empty() { HashMap::new() } else { modrinth_api::fetch_modrinth_versions(&version_ids) };
+
+    let project_of = |d: &UnifiedDependency| -> String {
+        if !d.mod_id.is_empty() {
+            return d.mod_id.clone();
+        }
+        d.version_id.as_ref().and_then(|v| versions.get(v)).map(|v| v.project_id.clone())
+            .or_else(|| d.version_id.clone())
+            .unwrap_or_default()
+    };
+    let ids: Vec<String> = deps.iter().map(project_of).filter(|id| !id.is_empty()).collect();
+    let projects = modrinth_api::fetch_modrinth_projects(&ids);
+
+    ids.into_iter()
+        .map(|id| {
+            projects.get(&id)
+                .or_else(|| projects.values().find(|p| p.slug == id))
+                .map(|p| p.title.clone())
+                .unwrap_or(id)
+        })
+        .collect()
+}
+
+/// Reintentos tras el primer intento ante errores transitorios (5xx, 429, conexión cortada).
+pub const MAX_DOWNLOAD_RETRIES: u32 = 4;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(15);
+
+/// Resultado de un intento de descarga fallido: si merece la pena reintentar o no.
+enum AttemptError {
+    /// Error transitorio; `retry_after` es la espera que pide el servidor (429), si la indica.
+    Retryable { error: std::io::Error, retry_after: Option<Duration> },
+    Fatal(std::io::Error),
+}
+
+impl AttemptError {
+    fn retryable(error: std::io::Error) -> Self {
+        AttemptError::Retryable { error, retry_after: None }
+    }
+}
 
+/// Descarga `file_url` a `output_folder/filename` pasando por un `.part`. El archivo solo se renombra
+/// al nombre final si coincide con el tamaño/hashes publicados (`expected`); si no, se borra el `.part`.
+///
+/// Si ya existe un `.part` de una descarga interrumpida se reanuda con `Range`, solo si `expected` trae
+/// tamaño o hash con el que comprobar el resultado; si no, se descarta y se baja de cero. Los errores transitorios
+/// se reintentan hasta `MAX_DOWNLOAD_RETRIES` veces con backoff exponencial (avisando con
+/// `TransferUpdate::Retry`); si se agotan, el `.part` se conserva para reanudar más adelante.
+/// El progreso (`TransferUpdate::Bytes`) se emite como mucho cada `PROGRESS_INTERVAL` y al terminar.
+pub fn download_mod_file(
+    file_url: &str,
+    output_folder: &str,
+    filename: &str,
+    expected: &ExpectedFile,
+    mut on_update: impl FnMut(TransferUpdate),
+) -> Result<(), std::io::Error> {
+    let file_url = super::download_url(file_url);
     let dest_path = Path::new(output_folder).join(filename);
     let part_path = Path::new(output_folder).join(format!("{}.part", filename));
 
@@ -279,22 +630,144 @@ pub fn download_mod_file(file_url: &str, output_folder: &str, filename: &str) ->
         }
     }
 
-    // Descargar a archivo temporal .part
-    let result = {
-        let mut out_file = std::fs::File::create(&part_path)?;
-        std::io::copy(&mut resp_file, &mut out_file)
-    };
+    let mut attempt = 0;
+    loop {
+        match download_attempt(&file_url, &part_path, expected, &mut on_update) {
+            Ok(()) => break,
+            Err(AttemptError::Fatal(e)) => {
+                // Respuesta definitiva o archivo corrupto: el .part no sirve para reanudar
+                let _ = std::fs::remove_file(&part_path);
+                return Err(e);
+            }
+            Err(AttemptError::Retryable { error, retry_after }) => {
+                if attempt >= MAX_DOWNLOAD_RETRIES {
+                    return Err(error);
+                }
+                attempt += 1;
+                let delay = retry_after.unwrap_or_else(|| retry_delay(attempt));
+                println!("🔁 Reintentando {} ({}/{}) en {:.1}s: {}", filename, attempt, MAX_DOWNLOAD_RETRIES, delay.as_secs_f32(), error);
+                on_update(TransferUpdate::Retry { attempt, reason: error.to_string() });
+                std::thread::sleep(delay);
+            }
+        }
+    }
+
+    // Descarga completa y verificada: renombrar .part -> archivo final
+    std::fs::rename(&part_path, &dest_path)
+}
+
+/// Espera antes del reintento `attempt` (1, 2, ...): 0.5s, 1s, 2s... hasta `RETRY_MAX_DELAY`.
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16)).min(RETRY_MAX_DELAY)
+}
+
+/// Un intento: continúa el `.part` existente (si lo hay) y lo verifica al terminar.
+fn download_attempt(
+    file_url: &str,
+    part_path: &Path,
+    expected: &ExpectedFile,
+    on_update: &mut impl FnMut(TransferUpdate),
+) -> Result<(), AttemptError> {
+    // Sin tamaño ni hash no hay forma de saber si el .part es de este archivo: no se reanuda
+    if expected.is_empty() {
+        let _ = std::fs::remove_file(part_path);
+    }
+    let offset = std::fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+
+    // Un intento anterior llegó a bajarlo entero: no hace falta pedir nada
+    if offset > 0 && expected.size == Some(offset) && hashing::verify_file(part_path, expected).is_ok() {
+        on_update(TransferUpdate::Bytes { downloaded: offset, total: Some(offset) });
+        return Ok(());
+    }
+
+    let mut request = DOWNLOAD_CLIENT.get(file_url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut resp = request.send().map_err(|e| {
+        let error = std::io::Error::other(e.to_string());
+        if e.is_connect() || e.is_timeout() || e.is_request() { AttemptError::retryable(error) } else { AttemptError::Fatal(error) }
+    })?;
+
+    let status = resp.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // El .part no cuadra con el archivo remoto: empezar de cero
+        let _ = std::fs::remove_file(part_path);
+        return Err(AttemptError::Retryable { error: std::io::Error::other(format!("HTTP {}", status)), retry_after: Some(Duration::ZERO) });
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        let retry_after = resp.headers().get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(|secs| Duration::from_secs(secs).min(RETRY_MAX_DELAY));
+        return Err(AttemptError::Retryable { error: std::io::Error::other(format!("HTTP {}", status)), retry_after });
+    }
+    // Un 404/403 del servidor no debe acabar guardado como si fuera el jar
+    if !status.is_success() {
+        return Err(AttemptError::Fatal(std::io::Error::other(format!("HTTP {}", status))));
+    }
+
+    // Si el servidor ignora el Range (200 en vez de 206) se reescribe el .part desde el principio
+    let resumed = offset > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT;
+    let start = if resumed { offset } else { 0 };
+    if resumed {
+        println!("⏯️ Reanudando {} desde {} bytes", part_path.display(), offset);
+    }
+    let total = resp.content_length().map(|len| len + start).or(expected.size);
 
-    match result {
-        Ok(_) => {
-            // Descarga completa: renombrar .part -> archivo final
-            std::fs::rename(&part_path, &dest_path)?;
-            Ok(())
+    let out_file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)
+        .map_err(AttemptError::Fatal)?;
+    copy_with_progress(&mut resp, out_file, start, total, on_update)?;
+
+    hashing::verify_file(part_path, expected).map_err(|e| {
+        if resumed {
+            // Puede que el .part fuera de otra versión del archivo: repetir desde cero
+            let _ = std::fs::remove_file(part_path);
+            AttemptError::Retryable { error: e, retry_after: Some(Duration::ZERO) }
+        } else {
+            AttemptError::Fatal(e)
         }
-        Err(e) => {
-            // Fallo: eliminar el .part corrupto
-            let _ = std::fs::remove_file(&part_path);
-            Err(e)
+    })
+}
+
+/// Copia el cuerpo al `.part` por bloques. Los fallos de lectura (red) son reintentables;
+/// los de escritura (disco lleno, archivo bloqueado) no.
+fn copy_with_progress(
+    reader: &mut impl Read,
+    mut out_file: std::fs::File,
+    start: u64,
+    total: Option<u64>,
+    on_update: &mut impl FnMut(TransferUpdate),
+) -> Result<u64, AttemptError> {
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut written = start;
+    let mut last_report = Instant::now();
+
+    on_update(TransferUpdate::Bytes { downloaded: written, total });
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                let _ = out_file.flush();
+                return Err(AttemptError::retryable(e));
+            }
+        };
+        out_file.write_all(&buf[..n]).map_err(AttemptError::Fatal)?;
+        written += n as u64;
+
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            on_update(TransferUpdate::Bytes { downloaded: written, total });
+            last_report = Instant::now();
         }
     }
-}
\ No newline at end of file
+    out_file.flush().map_err(AttemptError::Fatal)?;
+    on_update(TransferUpdate::Bytes { downloaded: written, total });
+    Ok(written)
+}
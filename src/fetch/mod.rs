@@ -5,9 +5,83 @@ pub mod search_provider;
 pub mod single_datapack_search;
 pub mod modrinth_api;
 pub mod curseforge_api;
+pub mod update_check;
+pub mod progress;
 
-/// Devuelve la API key de CurseForge desde la variable de entorno, o cadena vacía si no está definida.
+use std::sync::OnceLock;
+
+/// Devuelve la API key de CurseForge (la de `set_endpoints` o la variable de entorno), o cadena vacía si no hay.
 /// Usar esta función evita repetir `std::env::var("CURSEFORGE_API_KEY").unwrap_or_default()` en toda la base de código.
 pub fn cf_api_key() -> String {
-    return std::env::var("CURSEFORGE_API_KEY").unwrap_or_default();
-}
\ No newline at end of file
+    return configured(|e| e.curseforge_api_key.as_ref(), "CURSEFORGE_API_KEY").unwrap_or_default();
+}
+
+// ── Endpoints configurables ──────────────────────────────────
+// Permiten apuntar a un mirror local, un proxy de caché o un servidor stub en los tests.
+
+pub const DEFAULT_MODRINTH_API_URL: &str = "https://api.modrinth.com/v2";
+pub const DEFAULT_CURSEFORGE_API_URL: &str = "https://api.curseforge.com/v1";
+
+/// Endpoints fijados desde el propio proceso (p.ej. los tests contra un servidor stub), sin tocar
+/// las variables de entorno. Lo que quede en None se sigue leyendo del entorno.
+#[derive(Debug, Clone, Default)]
+pub struct Endpoints {
+    pub modrinth_api_url: Option<String>,
+    pub curseforge_api_url: Option<String>,
+    pub download_host: Option<String>,
+    pub curseforge_api_key: Option<String>,
+}
+
+static ENDPOINTS: OnceLock<Endpoints> = OnceLock::new();
+
+/// Fija los endpoints del proceso. Solo vale la primera llamada: devuelve false si ya estaban fijados.
+pub fn set_endpoints(endpoints: Endpoints) -> bool {
+    ENDPOINTS.set(endpoints).is_ok()
+}
+
+/// El valor de `set_endpoints` si lo hay; si no, el de la variable de entorno `var`. Vacío cuenta como no definido.
+fn configured(pick: impl Fn(&Endpoints) -> Option<&String>, var: &str) -> Option<String> {
+    ENDPOINTS.get().and_then(|e| pick(e).cloned())
+        .or_else(|| std::env::var(var).ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// URL base configurada, sin la barra final. Si no está definida usa `default`.
+fn base_url(pick: impl Fn(&Endpoints) -> Option<&String>, var: &str, default: &str) -> String {
+    match configured(pick, var) {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => default.to_string(),
+    }
+}
+
+/// URL base de la API de Modrinth (`MODRINTH_API_URL`, por defecto `https://api.modrinth.com/v2`).
+pub fn modrinth_api_url() -> String {
+    base_url(|e| e.modrinth_api_url.as_ref(), "MODRINTH_API_URL", DEFAULT_MODRINTH_API_URL)
+}
+
+/// URL base de la API de CurseForge (`CURSEFORGE_API_URL`, por defecto `https://api.curseforge.com/v1`).
+pub fn curseforge_api_url() -> String {
+    base_url(|e| e.curseforge_api_url.as_ref(), "CURSEFORGE_API_URL", DEFAULT_CURSEFORGE_API_URL)
+}
+
+/// Reescribe una URL de descarga (CDN de Modrinth / CurseForge) para que apunte al host de
+/// `MODS_UPDATER_DOWNLOAD_HOST`, conservando la ruta y la query. Sin la variable, devuelve la URL tal cual.
+pub fn download_url(original: &str) -> String {
+    let host = match configured(|e| e.download_host.as_ref(), "MODS_UPDATER_DOWNLOAD_HOST") {
+        Some(h) => h.trim_end_matches('/').to_string(),
+        None => return original.to_string(),
+    };
+
+    match reqwest::Url::parse(original) {
+        Ok(url) => {
+            let mut rewritten = format!("{}{}", host, url.path());
+            if let Some(q) = url.query() {
+                rewritten.push('?');
+                rewritten.push_str(q);
+            }
+            rewritten
+        }
+        Err(_) => original.to_string(),
+    }
+}
@@ -2,14 +2,18 @@ use reqwest::blocking::Client;
 use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
 use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::hashing::ExpectedFile;
 
 #[derive(Debug, Deserialize)]
 pub struct ModrinthVersion {
     pub id: String,
+    #[serde(default)]
+    pub project_id: String,
     pub name: String,
     pub version_number: String,
     pub version_type: String,
@@ -23,23 +27,40 @@ pub struct ModrinthVersion {
 #[derive(Debug, Deserialize, Clone)]
 pub struct ModrinthDependency {
     pub project_id: Option<String>,
+    #[serde(default)]
+    pub version_id: Option<String>,
     pub dependency_type: String,
 }
 
 impl ModrinthVersion {
+    /// Archivo principal de la versión (el marcado `primary`, o el primero si ninguno lo está).
     pub fn first_file(&self) -> Option<&ModFile> {
-        self.files.first()
+        self.files.iter().find(|f| f.primary).or(self.files.first())
+    }
+
+    /// Fecha de publicación como "YYYY-MM-DD".
+    pub fn date_only(&self) -> String {
+        self.date_published.split('T').next().unwrap_or(&self.date_published).to_string()
     }
 
-    /// Devuelve las dependencias **requeridas** como Vec<UnifiedDependency>.
+    /// Devuelve las dependencias con su tipo como Vec<UnifiedDependency>.
     /// Centraliza la extracción para que no se repita en fetch_from_api.
-    pub fn required_deps(&self) -> Vec<crate::fetch::fetch_from_api::UnifiedDependency> {
+    /// Las que solo fijan `version_id` llegan con `mod_id` vacío: el proyecto sale de esa versión.
+    pub fn unified_deps(&self) -> Vec<crate::fetch::fetch_from_api::UnifiedDependency> {
         self.dependencies.as_ref()
             .map(|deps| {
                 deps.iter()
-                    .filter(|d| d.dependency_type == "required")
-                    .filter_map(|d| d.project_id.clone())
-                    .map(|id| crate::fetch::fetch_from_api::UnifiedDependency { mod_id: id })
+                    .filter_map(|d| {
+                        let kind = crate::fetch::fetch_from_api::DependencyKind::from_modrinth(&d.dependency_type)?;
+                        if d.project_id.is_none() && d.version_id.is_none() {
+                            return None;
+                        }
+                        Some(crate::fetch::fetch_from_api::UnifiedDependency {
+                            mod_id: d.project_id.clone().unwrap_or_default(),
+                            kind,
+                            version_id: d.version_id.clone(),
+                        })
+                    })
                     .collect()
             })
             .unwrap_or_default()
@@ -50,6 +71,24 @@ impl ModrinthVersion {
 pub struct ModFile {
     pub url: String,
     pub filename: String,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(default)]
+    pub hashes: HashMap<String, String>, // "sha1" / "sha512" → hex
+    #[serde(default)]
+    pub size: u64,
+}
+
+impl ModFile {
+    /// Tamaño y hashes publicados, para verificar la descarga.
+    pub fn expected(&self) -> ExpectedFile {
+        ExpectedFile {
+            size: Some(self.size).filter(|s| *s > 0),
+            sha1: self.hashes.get("sha1").cloned(),
+            sha512: self.hashes.get("sha512").cloned(),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,6 +106,19 @@ pub struct ModrinthSearchHit {
     pub author: String,
 }
 
+/// Datos de proyecto que devuelve `/project/{id}` y `/projects`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModrinthProject {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    /// "required", "optional", "unsupported" o "unknown"
+    #[serde(default)]
+    pub client_side: String,
+    #[serde(default)]
+    pub server_side: String,
+}
+
 // ── Rate Limiting ────────────────────────────────────────────
 
 struct ModrinthRateLimit {
@@ -132,7 +184,7 @@ pub fn has_capacity() -> bool {
 
 pub fn search_modrinth_project(query: &str, loader: &Option<String>, version: &Option<String>, offset: u32, limit: u32, extra_facets: &[String]) -> Vec<ModrinthSearchHit> {
     let client = &*MODRINTH_CLIENT;
-    let search_url = "https://api.modrinth.com/v2/search";
+    let search_url = format!("{}/search", crate::fetch::modrinth_api_url());
     
     let mut facets = Vec::new();
     if let Some(v) = version {
@@ -171,7 +223,7 @@ pub fn search_modrinth_project(query: &str, loader: &Option<String>, version: &O
 
     wait_for_ratelimit();
 
-    match client.get(search_url).query(&params).send() {
+    match client.get(&search_url).query(&params).send() {
         Ok(resp) => {
             update_ratelimit(resp.headers());
             if resp.status().is_success() {
@@ -195,7 +247,7 @@ pub fn fetch_modrinth_project_info(mod_id: &str) -> Option<(String, String)> {
     struct ProjectInfo { title: String, slug: String }
 
     let client = &*MODRINTH_CLIENT;
-    let api_url = format!("https://api.modrinth.com/v2/project/{}", mod_id);
+    let api_url = format!("{}/project/{}", crate::fetch::modrinth_api_url(), mod_id);
 
     wait_for_ratelimit();
 
@@ -214,7 +266,7 @@ pub fn fetch_modrinth_project_info(mod_id: &str) -> Option<(String, String)> {
 
 pub fn fetch_modrinth_version(mod_id: &str, version: &str, loader: &str, content_type: &ContentType) -> Option<ModrinthVersion> {
     let client = &*MODRINTH_CLIENT;
-    let api_url = format!("https://api.modrinth.com/v2/project/{}/version", mod_id);
+    let api_url = format!("{}/project/{}/version", crate::fetch::modrinth_api_url(), mod_id);
 
     // Datapacks use "datapack" as the loader to get .zip files from Modrinth
     let effective_loader = match content_type {
@@ -251,9 +303,33 @@ pub fn fetch_modrinth_version(mod_id: &str, version: &str, loader: &str, content
     }
 }
 
+/// Una versión concreta por su ID (p.ej. la que fija una dependencia con `version_id`).
+pub fn fetch_modrinth_version_by_id(version_id: &str) -> Option<ModrinthVersion> {
+    let client = &*MODRINTH_CLIENT;
+    let api_url = format!("{}/version/{}", crate::fetch::modrinth_api_url(), version_id);
+
+    wait_for_ratelimit();
+
+    match client.get(&api_url).send() {
+        Ok(resp) => {
+            update_ratelimit(resp.headers());
+            if resp.status().is_success() {
+                resp.json::<ModrinthVersion>().ok()
+            } else {
+                println!("❌ Error en API para la versión {}: status {}", version_id, resp.status());
+                None
+            }
+        }
+        Err(e) => {
+            println!("❌ Error consultando API de Modrinth: {}", e);
+            None
+        }
+    }
+}
+
 pub fn fetch_modrinth_project_versions(mod_id: &str, loader: &str, game_version: &str, content_type: &ContentType) -> Vec<crate::fetch::search_provider::ProjectVersion> {
     let client = &*MODRINTH_CLIENT;
-    let api_url = format!("https://api.modrinth.com/v2/project/{}/version", mod_id);
+    let api_url = format!("{}/project/{}/version", crate::fetch::modrinth_api_url(), mod_id);
 
     let effective_loader = match content_type {
         ContentType::Datapack => "datapack",
@@ -282,8 +358,7 @@ pub fn fetch_modrinth_project_versions(mod_id: &str, loader: &str, game_version:
                         "alpha" => "A".to_string(),
                         _ => "O".to_string(),
                     };
-                    // Date parsing to just "YYYY-MM-DD"
-                    let date = mv.date_published.split('T').next().unwrap_or(&mv.date_published).to_string();
+                    let date = mv.date_only();
                     crate::fetch::search_provider::ProjectVersion {
                         id: mv.id,
                         name: mv.name,
@@ -304,3 +379,134 @@ pub fn fetch_modrinth_project_versions(mod_id: &str, loader: &str, game_version:
         }
     }
 }
+
+/// Máximo de IDs por petición a `/projects` (van en la query string).
+const MAX_IDS_PER_REQUEST: usize = 100;
+
+/// Datos de varios proyectos en una sola petición (`GET /projects?ids=[...]`). Acepta IDs o slugs;
+/// el resultado va indexado por ID de proyecto.
+pub fn fetch_modrinth_projects(ids: &[String]) -> HashMap<String, ModrinthProject> {
+    let client = &*MODRINTH_CLIENT;
+    let api_url = format!("{}/projects", crate::fetch::modrinth_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in ids.chunks(MAX_IDS_PER_REQUEST) {
+        let ids_json = json!(chunk).to_string();
+
+        wait_for_ratelimit();
+
+        match client.get(&api_url).query(&[("ids", &ids_json)]).send() {
+            Ok(resp) => {
+                update_ratelimit(resp.headers());
+                if resp.status().is_success() {
+                    let projects: Vec<ModrinthProject> = resp.json().unwrap_or_default();
+                    found.extend(projects.into_iter().map(|p| (p.id.clone(), p)));
+                } else {
+                    println!("❌ Error en API (projects): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de Modrinth: {}", e);
+            }
+        }
+    }
+    found
+}
+
+/// Máximo de hashes por petición a `version_files` (la API no documenta límite, pero conviene trocear).
+const MAX_HASHES_PER_REQUEST: usize = 500;
+
+/// Identifica versiones exactas por hash de archivo con el endpoint bulk `POST /version_files`.
+/// `algorithm` es "sha1" o "sha512". Devuelve hash → versión solo para los hashes que Modrinth reconoce.
+pub fn fetch_versions_by_hashes(hashes: &[String], algorithm: &str) -> HashMap<String, ModrinthVersion> {
+    let client = &*MODRINTH_CLIENT;
+    let api_url = format!("{}/version_files", crate::fetch::modrinth_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in hashes.chunks(MAX_HASHES_PER_REQUEST) {
+        let body = json!({ "hashes": chunk, "algorithm": algorithm });
+
+        wait_for_ratelimit();
+
+        match client.post(&api_url).json(&body).send() {
+            Ok(resp) => {
+                update_ratelimit(resp.headers());
+                if resp.status().is_success() {
+                    let versions: HashMap<String, ModrinthVersion> = resp.json().unwrap_or_default();
+                    found.extend(versions);
+                } else {
+                    println!("❌ Error en API (version_files): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de Modrinth: {}", e);
+            }
+        }
+    }
+    found
+}
+
+/// Varias versiones por su ID en una sola petición (`GET /versions?ids=[...]`), indexadas por ID.
+pub fn fetch_modrinth_versions(ids: &[String]) -> HashMap<String, ModrinthVersion> {
+    let client = &*MODRINTH_CLIENT;
+    let api_url = format!("{}/versions", crate::fetch::modrinth_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in ids.chunks(MAX_IDS_PER_REQUEST) {
+        let ids_json = json!(chunk).to_string();
+
+        wait_for_ratelimit();
+
+        match client.get(&api_url).query(&[("ids", &ids_json)]).send() {
+            Ok(resp) => {
+                update_ratelimit(resp.headers());
+                if resp.status().is_success() {
+                    let versions: Vec<ModrinthVersion> = resp.json().unwrap_or_default();
+                    found.extend(versions.into_iter().map(|v| (v.id.clone(), v)));
+                } else {
+                    println!("❌ Error en API (versions): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de Modrinth: {}", e);
+            }
+        }
+    }
+    found
+}
+
+/// Última versión compatible con `game_version` + `loader` del proyecto de cada archivo, con el
+/// endpoint bulk `POST /version_files/update`. Devuelve hash → versión más reciente; los hashes
+/// que Modrinth no conoce, o sin versión para ese destino, no aparecen.
+pub fn fetch_latest_versions_by_hashes(hashes: &[String], algorithm: &str, game_version: &str, loader: &str) -> HashMap<String, ModrinthVersion> {
+    let client = &*MODRINTH_CLIENT;
+    let api_url = format!("{}/version_files/update", crate::fetch::modrinth_api_url());
+    let mut found = HashMap::new();
+
+    for chunk in hashes.chunks(MAX_HASHES_PER_REQUEST) {
+        let body = json!({
+            "hashes": chunk,
+            "algorithm": algorithm,
+            "loaders": [loader.to_lowercase()],
+            "game_versions": [game_version],
+        });
+
+        wait_for_ratelimit();
+
+        match client.post(&api_url).json(&body).send() {
+            Ok(resp) => {
+                update_ratelimit(resp.headers());
+                if resp.status().is_success() {
+                    let versions: HashMap<String, ModrinthVersion> = resp.json().unwrap_or_default();
+                    found.extend(versions);
+                } else {
+                    println!("❌ Error en API (version_files/update): status {}", resp.status());
+                }
+            }
+            Err(e) => {
+                println!("❌ Error consultando API de Modrinth: {}", e);
+            }
+        }
+    }
+    found
+}
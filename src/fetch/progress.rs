@@ -0,0 +1,123 @@
+//! Progreso agregado de un lote de descargas: bytes, velocidad media y ETA de todo el pool de workers.
+//! Se alimenta con los `DownloadEvent` en el lado que los consume (UI o CLI).
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Intervalo mínimo entre dos eventos `Progress` del mismo archivo.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Lo que `download_mod_file` va contando de una descarga.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferUpdate {
+    /// Bytes en disco (incluidos los de un `.part` reanudado); `total` es None si no se conoce el tamaño.
+    Bytes { downloaded: u64, total: Option<u64> },
+    /// Error transitorio: se va a reintentar (`attempt` empieza en 1).
+    Retry { attempt: u32, reason: String },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct FileProgress {
+    downloaded: u64,
+    total: Option<u64>,
+    finished: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct TransferStats {
+    started_at: Option<Instant>,
+    files: HashMap<String, FileProgress>,
+}
+
+impl TransferStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Un archivo empieza a descargarse. Si el lote anterior ya había terminado, empieza uno nuevo.
+    pub fn start(&mut self, key: &str) {
+        if !self.files.is_empty() && self.files.values().all(|f| f.finished) {
+            self.reset();
+        }
+        self.started_at.get_or_insert_with(Instant::now);
+        self.files.insert(key.to_string(), FileProgress::default());
+    }
+
+    pub fn record(&mut self, key: &str, downloaded: u64, total: Option<u64>) {
+        self.started_at.get_or_insert_with(Instant::now);
+        let file = self.files.entry(key.to_string()).or_default();
+        file.downloaded = downloaded;
+        file.total = total.or(file.total);
+    }
+
+    /// Archivo terminado (bien o con error): deja de contar como pendiente.
+    pub fn finish(&mut self, key: &str) {
+        if let Some(file) = self.files.get_mut(key) {
+            file.finished = true;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.started_at = None;
+        self.files.clear();
+    }
+
+    pub fn downloaded_bytes(&self) -> u64 {
+        self.files.values().map(|f| f.downloaded).sum()
+    }
+
+    /// Bytes por segundo desde el primer archivo del lote.
+    pub fn throughput(&self) -> f64 {
+        let Some(start) = self.started_at else { return 0.0 };
+        let secs = start.elapsed().as_secs_f64();
+        if secs <= 0.0 { 0.0 } else { self.downloaded_bytes() as f64 / secs }
+    }
+
+    /// Bytes que faltan para terminar `total_files` archivos. Los que aún no han empezado
+    /// (o no mandan Content-Length) se estiman con el tamaño medio de los conocidos.
+    pub fn remaining_bytes(&self, total_files: usize) -> u64 {
+        let known: Vec<u64> = self.files.values().filter_map(|f| f.total).collect();
+        let avg = if known.is_empty() { 0 } else { known.iter().sum::<u64>() / known.len() as u64 };
+
+        let in_flight: u64 = self.files.values()
+            .filter(|f| !f.finished)
+            .map(|f| f.total.unwrap_or(avg).saturating_sub(f.downloaded))
+            .sum();
+        let not_started = total_files.saturating_sub(self.files.len()) as u64;
+        in_flight + not_started * avg
+    }
+
+    pub fn eta(&self, total_files: usize) -> Option<Duration> {
+        let speed = self.throughput();
+        if speed <= 0.0 {
+            return None;
+        }
+        Some(Duration::from_secs_f64(self.remaining_bytes(total_files) as f64 / speed))
+    }
+
+    /// Resumen de una línea: "3.2 MB · 1.1 MB/s · ETA 0:42".
+    pub fn summary(&self, total_files: usize) -> String {
+        let eta = self.eta(total_files).map(format_eta).unwrap_or_else(|| "--:--".to_string());
+        format!("{} · {}/s · ETA {}", format_bytes(self.downloaded_bytes()), format_bytes(self.throughput() as u64), eta)
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+pub fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,111 @@
+//! Comprobación de actualizaciones: busca el archivo compatible más reciente de cada mod para
+//! una versión de MC + loader y lo compara con el jar instalado.
+
+use std::collections::HashMap;
+use crossbeam_channel::{Receiver, Sender};
+use crate::common::spawn_worker_pool;
+use crate::common::version_range::Version;
+use crate::fetch::async_download::best_known_project_id;
+use crate::fetch::fetch_from_api::{self, ModDownloadInfo};
+use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::{ModInfo, UpdateState};
+
+#[derive(Debug, Clone)]
+pub struct UpdateCheckJob {
+    pub modinfo: ModInfo,
+    pub game_version: String,
+    pub loader: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateCheckResult {
+    pub key: String,
+    pub state: UpdateState,
+    /// Archivo compatible más reciente (None si `Unavailable`)
+    pub latest: Option<ModDownloadInfo>,
+}
+
+/// Busca la última versión compatible de un mod y la compara con la instalada.
+pub fn check_mod_update(mi: &ModInfo, game_version: &str, loader: &str, cf_key: &str) -> UpdateCheckResult {
+    let project_id = best_known_project_id(mi);
+    let latest = fetch_from_api::find_mod_download(&mi.name, project_id.as_deref(), game_version, loader, cf_key, &ContentType::Mod);
+
+    let state = match &latest {
+        Some(info) => compare_with_local(mi, info),
+        None => UpdateState::Unavailable,
+    };
+    UpdateCheckResult { key: mi.key.clone(), state, latest }
+}
+
+/// Comprueba en bloque los jobs que Modrinth reconoce por hash o CurseForge por ID (ver
+/// `find_mod_downloads_in_bulk`). Devuelve sus resultados y los jobs que quedan para comprobar uno a uno.
+pub fn check_updates_by_hash(jobs: Vec<UpdateCheckJob>) -> (Vec<UpdateCheckResult>, Vec<UpdateCheckJob>) {
+    let mut targets: Vec<(String, String)> = jobs.iter()
+        .filter(|j| j.modinfo.hashes.is_some() || j.modinfo.confirmed_project_id.is_some())
+        .map(|j| (j.game_version.clone(), j.loader.clone()))
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    let cf_key = crate::fetch::cf_api_key();
+    let mut found: HashMap<(String, String, String), ModDownloadInfo> = HashMap::new();
+    for (game_version, loader) in targets {
+        let mods: Vec<ModInfo> = jobs.iter()
+            .filter(|j| j.game_version == game_version && j.loader == loader)
+            .map(|j| j.modinfo.clone())
+            .collect();
+        for (key, info) in fetch_from_api::find_mod_downloads_in_bulk(&mods, &game_version, &loader, &cf_key) {
+            found.insert((key, game_version.clone(), loader.clone()), info);
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut pending = Vec::new();
+    for job in jobs {
+        match found.remove(&(job.modinfo.key.clone(), job.game_version.clone(), job.loader.clone())) {
+            Some(latest) => results.push(UpdateCheckResult {
+                key: job.modinfo.key.clone(),
+                state: compare_with_local(&job.modinfo, &latest),
+                latest: Some(latest),
+            }),
+            None => pending.push(job),
+        }
+    }
+    (results, pending)
+}
+
+/// Decide si el jar local es ya el archivo remoto más reciente.
+/// Por orden de fiabilidad: hash SHA-1, nombre de archivo y, como último recurso, número de versión.
+pub fn compare_with_local(mi: &ModInfo, latest: &ModDownloadInfo) -> UpdateState {
+    if let (Some(local), Some(remote_sha1)) = (&mi.hashes, &latest.expected.sha1) {
+        return if local.sha1.eq_ignore_ascii_case(remote_sha1) { UpdateState::UpToDate } else { UpdateState::Outdated };
+    }
+
+    if latest.filename == mi.key {
+        return UpdateState::UpToDate;
+    }
+
+    if let Some(local_version) = mi.version_local.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        // "mc1.20.1-0.5.3" contiene la versión "0.5.3" como segmento; el jar puede decir
+        // "0.5.3+mc1.20.1", así que se comparan como versiones y no como texto
+        let local = Version::parse(local_version);
+        let same = latest.version_remote == local_version
+            || latest.version_remote
+                .split(['-', '+', ' ', '_'])
+                .any(|segment| segment == local_version || Version::parse(segment) == local);
+        if same {
+            return UpdateState::UpToDate;
+        }
+    }
+    UpdateState::Outdated
+}
+
+/// Lanza el pool de workers de comprobación. Igual que la descarga, cada lote debería pasar antes
+/// por la identificación por hash para que el project_id sea fiable.
+pub fn spawn_update_check_workers(n: usize, rx: Receiver<UpdateCheckJob>, tx: Sender<UpdateCheckResult>) {
+    spawn_worker_pool(n, rx, move |job: UpdateCheckJob| {
+        let cf_key = crate::fetch::cf_api_key();
+        let result = check_mod_update(&job.modinfo, &job.game_version, &job.loader, &cf_key);
+        let _ = tx.send(result);
+    });
+}
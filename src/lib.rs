@@ -2,6 +2,7 @@ pub mod common;
 pub mod local_mods_ops;
 pub mod local_datapacks_ops;
 pub mod profiles;
+pub mod pack_formats;
 pub mod fetch;
 pub mod ui;
 pub mod paths_vars;
@@ -11,26 +11,16 @@ pub struct DatapackReadJob {
 
 #[derive(Debug, Clone)]
 pub enum DatapackReadEvent {
-    Done { world_name: String, info: DatapackInfo },
+    Done { world_name: String, info: Box<DatapackInfo> },
     Error { world_name: String, path: PathBuf, msg: String },
 }
 
 pub fn spawn_datapack_read_workers(n: usize, rx: Receiver<DatapackReadJob>, tx: Sender<DatapackReadEvent>) {
     spawn_worker_pool(n, rx, move |job: DatapackReadJob| {
-        match super::scanner::read_single_datapack(&job.file_path) {
-            Ok(mut info) => {
-                // Attach file metadata
-                if let Ok(meta) = std::fs::metadata(&job.file_path) {
-                    info.file_size_bytes = Some(meta.len());
-                    info.file_mtime_secs = Some(
-                        meta.modified()
-                            .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-                            .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs()
-                    );
-                }
-                let _ = tx.send(DatapackReadEvent::Done { world_name: job.world_name, info });
+        // La caché (por mundo + archivo, invalidada por tamaño/mtime) evita reabrir los zips sin cambios
+        match super::scanner::read_datapack_cached(&job.world_name, &job.file_path) {
+            Ok(info) => {
+                let _ = tx.send(DatapackReadEvent::Done { world_name: job.world_name, info: Box::new(info) });
             }
             Err(msg) => {
                 let _ = tx.send(DatapackReadEvent::Error {
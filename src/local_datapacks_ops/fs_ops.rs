@@ -0,0 +1,132 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+use zip::write::SimpleFileOptions;
+use crate::pack_formats::safe_relative_path;
+
+// ── Comprimir / descomprimir datapacks ───────────────────────
+// Solo se escribe dentro de la carpeta datapacks/ del mundo, junto al pack original.
+// Las carpetas de datapack son del usuario: nunca se borran, solo los zips.
+
+/// Resultado de convertir un datapack entre zip y carpeta.
+#[derive(Debug, Clone)]
+pub struct RepackOutcome {
+    /// Ruta del pack nuevo (el `.zip` o la carpeta)
+    pub new_path: PathBuf,
+    /// Si el original se quitó. Si no, Minecraft cargará los dos.
+    pub source_removed: bool,
+}
+
+/// Empaqueta una carpeta de datapack en `<carpeta>.zip`, al lado. La carpeta se conserva.
+pub fn zip_datapack_folder(folder: &Path) -> Result<RepackOutcome, String> {
+    if !folder.join("pack.mcmeta").is_file() {
+        return Err(format!("'{}' no tiene pack.mcmeta", folder.display()));
+    }
+    let name = folder.file_name().ok_or("Ruta de datapack no válida")?.to_string_lossy().to_string();
+    let output = folder.with_file_name(format!("{}.zip", name));
+    if output.exists() {
+        return Err(format!("Ya existe '{}'", output.display()));
+    }
+
+    let mut files = Vec::new();
+    collect_files(folder, folder, &mut files)?;
+    files.sort();
+
+    let tmp = output.with_extension("tmp");
+    let result = (|| -> Result<(), String> {
+        let file = File::create(&tmp).map_err(|e| format!("No se pudo crear '{}': {}", tmp.display(), e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (entry_name, source) in &files {
+            let mut input = File::open(source).map_err(|e| format!("No se pudo leer '{}': {}", source.display(), e))?;
+            zip.start_file(entry_name.as_str(), options).map_err(|e| e.to_string())?;
+            std::io::copy(&mut input, &mut zip).map_err(|e| format!("Error añadiendo '{}': {}", entry_name, e))?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => {
+            fs::rename(&tmp, &output).map_err(|e| format!("No se pudo escribir '{}': {}", output.display(), e))?;
+            Ok(RepackOutcome { new_path: output, source_removed: false })
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Archivos bajo `dir` como (ruta dentro del zip con '/', ruta en disco). Los enlaces simbólicos se ignoran.
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("No se pudo leer '{}': {}", dir.display(), e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if file_type.is_file()
+            && let Ok(rel) = path.strip_prefix(root)
+        {
+            let name = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            out.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Extrae un datapack `.zip` en una carpeta con su nombre y, si todo fue bien, borra el zip.
+pub fn unzip_datapack(zip_path: &Path) -> Result<RepackOutcome, String> {
+    if zip_path.extension().and_then(|s| s.to_str()) != Some("zip") || !zip_path.is_file() {
+        return Err(format!("'{}' no es un .zip", zip_path.display()));
+    }
+    let stem = zip_path.file_stem().ok_or("Ruta de datapack no válida")?.to_string_lossy().to_string();
+    let dest = zip_path.with_file_name(&stem);
+    // Se extrae a una carpeta temporal propia y se renombra al final, para no dejar packs a medias
+    let tmp = zip_path.with_file_name(format!("{}.unzipping", stem));
+    if dest.exists() || tmp.exists() {
+        return Err(format!("Ya existe '{}'", dest.display()));
+    }
+
+    {
+        let file = File::open(zip_path).map_err(|e| format!("No se pudo abrir '{}': {}", zip_path.display(), e))?;
+        let mut archive = ZipArchive::new(file).map_err(|e| format!("Zip corrupto: {}", e))?;
+        if archive.by_name("pack.mcmeta").is_err() {
+            return Err("El zip no tiene pack.mcmeta en la raíz".to_string());
+        }
+        fs::create_dir(&tmp).map_err(|e| format!("No se pudo crear '{}': {}", tmp.display(), e))?;
+        if let Err(e) = extract_all(&mut archive, &tmp) {
+            let _ = fs::remove_dir_all(&tmp);
+            return Err(e);
+        }
+    }
+    if let Err(e) = fs::rename(&tmp, &dest) {
+        let _ = fs::remove_dir_all(&tmp);
+        return Err(format!("No se pudo crear '{}': {}", dest.display(), e));
+    }
+
+    // El zip puede estar bloqueado (juego abierto, antivirus): la carpeta ya está, solo se avisa
+    let source_removed = fs::remove_file(zip_path).is_ok();
+    Ok(RepackOutcome { new_path: dest, source_removed })
+}
+
+fn extract_all(archive: &mut ZipArchive<File>, folder: &Path) -> Result<(), String> {
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| format!("Zip corrupto: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel) = safe_relative_path(entry.name()) else {
+            println!("⚠️ Ruta insegura ignorada: {}", entry.name());
+            continue;
+        };
+        let dest = folder.join(&rel);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("No se pudo crear '{}': {}", parent.display(), e))?;
+        }
+        let mut out = File::create(&dest).map_err(|e| format!("No se pudo escribir '{}': {}", dest.display(), e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("Error extrayendo '{}': {}", rel.display(), e))?;
+    }
+    Ok(())
+}
@@ -1,6 +1,8 @@
 pub mod models;
+pub mod pack_format_table;
 pub mod scanner;
 pub mod async_reader;
+pub mod fs_ops;
 
 pub use models::*;
 pub use scanner::*;
@@ -1,15 +1,24 @@
 use serde::{Serialize, Deserialize};
+use super::pack_format_table::{FormatRange, PackKind, PackVersion};
 
 /// Información de un datapack escaneado localmente.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DatapackInfo {
-    pub key: String,                          // filename del .zip
+    pub key: String,                          // filename del .zip o nombre de la carpeta
     pub name: String,                         // mejor nombre encontrado
     pub detected_project_id: Option<String>,  // slug (id → namespace → filename)
     pub confirmed_project_id: Option<String>, // confirmado tras descarga exitosa
-    pub pack_format: Option<u32>,             // del pack.mcmeta
-    pub supported_formats: Option<(u32, u32)>, // (min, max) si existe
-    pub mc_version: Option<String>,           // deducida de pack_format
+    pub pack_format: Option<PackVersion>,     // del pack.mcmeta
+    pub supported_formats: Option<FormatRange>, // supported_formats o min_format/max_format
+    pub mc_version: Option<String>,           // resumen de mc_versions ("1.20.5 – 1.21.1")
+    #[serde(default)]
+    pub mc_versions: Vec<String>,             // releases soportadas, overlays incluidas
+    #[serde(default)]
+    pub overlays: Vec<PackOverlay>,
+    #[serde(default)]
+    pub kind: PackKind,
+    #[serde(default)]
+    pub is_folder: bool,                      // datapack descomprimido (carpeta con pack.mcmeta)
     pub version_local: Option<String>,        // extraída del filename
     pub version_remote: Option<String>,
     pub selected: bool,
@@ -18,3 +27,17 @@ pub struct DatapackInfo {
     #[serde(default)]
     pub file_mtime_secs: Option<u64>,
 }
+
+/// Entrada de `overlays.entries` en pack.mcmeta: un directorio que se aplica solo en esos formatos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackOverlay {
+    pub directory: String,
+    pub formats: FormatRange,
+}
+
+/// Fila de la tabla DATAPACKS de la caché. La ruta permite a `prune_db` descartar los packs borrados.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDatapack {
+    pub path: std::path::PathBuf,
+    pub info: DatapackInfo,
+}
@@ -0,0 +1,274 @@
+use once_cell::sync::Lazy;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::PathBuf;
+
+// ── Tabla pack_format → versiones de MC ──────────────────────
+// Embebida desde pack_format_table.json. El usuario puede añadir o corregir formatos
+// con un archivo del mismo nombre en la carpeta de configuración de la app.
+
+const EMBEDDED_TABLE: &str = include_str!("pack_format_table.json");
+const USER_TABLE_FILENAME: &str = "pack_format_table.json";
+
+pub static PACK_FORMAT_TABLE: Lazy<PackFormatTable> = Lazy::new(|| {
+    let mut table = PackFormatTable::embedded();
+    if let Some(path) = user_table_path()
+        && path.exists()
+    {
+        match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|json| PackFormatTable::from_json(&json)) {
+            Ok(user) => table.merge(user),
+            Err(e) => println!("⚠️ Ignorando tabla de pack_format de {}: {}", path.display(), e),
+        }
+    }
+    table
+});
+
+/// Archivo con el que el usuario amplía la tabla embebida (mismo formato JSON).
+pub fn user_table_path() -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push("mods_updater");
+    path.push(USER_TABLE_FILENAME);
+    Some(path)
+}
+
+/// Datapack (`data/`) o resource pack (`assets/`): cada uno numera sus formatos por separado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PackKind {
+    #[default]
+    Data,
+    Resource,
+}
+
+/// Formato de pack. Desde 1.21.9 lleva minor (`[88, 0]`, `94.1`); None = solo el número entero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PackVersion {
+    pub major: u32,
+    pub minor: Option<u32>,
+}
+
+impl PackVersion {
+    pub fn new(major: u32, minor: Option<u32>) -> Self {
+        Self { major, minor }
+    }
+
+    /// Acepta `48`, `94.1`, `[94, 1]` y `"94.1"`, como aparecen en pack.mcmeta.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) => Self::parse(&n.to_string()),
+            Value::String(s) => Self::parse(s),
+            Value::Array(parts) => {
+                let major = u32::try_from(parts.first()?.as_u64()?).ok()?;
+                let minor = match parts.get(1) {
+                    Some(minor) => Some(u32::try_from(minor.as_u64()?).ok()?),
+                    None => None,
+                };
+                Some(Self::new(major, minor))
+            }
+            _ => None,
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let (major, minor) = match s.trim().split_once('.') {
+            Some((major, minor)) => (major, Some(minor.parse().ok()?)),
+            None => (s.trim(), None),
+        };
+        Some(Self::new(major.parse().ok()?, minor))
+    }
+
+    /// Posición como límite inferior: sin minor equivale a `.0`.
+    fn lower(&self) -> (u32, u32) {
+        (self.major, self.minor.unwrap_or(0))
+    }
+
+    /// Posición como límite superior: sin minor abarca todas las minor de ese formato.
+    fn upper(&self) -> (u32, u32) {
+        (self.major, self.minor.unwrap_or(u32::MAX))
+    }
+}
+
+impl fmt::Display for PackVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.minor {
+            Some(minor) => write!(f, "{}.{}", self.major, minor),
+            None => write!(f, "{}", self.major),
+        }
+    }
+}
+
+/// Rango inclusivo de formatos que declara un pack (o una de sus overlays).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatRange {
+    pub min: PackVersion,
+    pub max: PackVersion,
+}
+
+impl FormatRange {
+    pub fn single(format: PackVersion) -> Self {
+        Self { min: format, max: format }
+    }
+
+    /// `supported_formats` / `formats` anteriores a 1.21.9: `{min_inclusive, max_inclusive}`, `[min, max]` o un entero.
+    pub fn from_supported(value: &Value) -> Option<Self> {
+        let (min, max) = match value {
+            Value::Object(obj) => (obj.get("min_inclusive")?, obj.get("max_inclusive")?),
+            Value::Array(arr) if arr.len() == 2 => (&arr[0], &arr[1]),
+            Value::Array(arr) if arr.len() == 1 => (&arr[0], &arr[0]),
+            other => (other, other),
+        };
+        Some(Self { min: PackVersion::from_value(min)?, max: PackVersion::from_value(max)? })
+    }
+
+    /// `min_format` / `max_format` de 1.21.9+. Si falta uno, el otro hace de ambos límites.
+    pub fn from_min_max(min: Option<&Value>, max: Option<&Value>) -> Option<Self> {
+        let min = min.and_then(PackVersion::from_value);
+        let max = max.and_then(PackVersion::from_value);
+        Some(Self { min: min.or(max)?, max: max.or(min)? })
+    }
+
+    pub fn contains(&self, format: &PackVersion) -> bool {
+        self.min.lower() <= format.lower() && format.lower() <= self.max.upper()
+    }
+
+    pub fn is_single(&self) -> bool {
+        self.min == self.max
+    }
+}
+
+impl fmt::Display for FormatRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_single() {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawEntry {
+    format: Value,
+    releases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct RawTable {
+    #[serde(default)]
+    data: Vec<RawEntry>,
+    #[serde(default)]
+    resource: Vec<RawEntry>,
+}
+
+/// Un formato y las releases de MC que lo usan.
+#[derive(Debug, Clone)]
+pub struct FormatEntry {
+    pub format: PackVersion,
+    pub releases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PackFormatTable {
+    data: Vec<FormatEntry>,
+    resource: Vec<FormatEntry>,
+}
+
+impl PackFormatTable {
+    /// La tabla que viene con la app.
+    pub fn embedded() -> Self {
+        Self::from_json(EMBEDDED_TABLE).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let raw: RawTable = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(Self { data: parse_entries(raw.data)?, resource: parse_entries(raw.resource)? })
+    }
+
+    /// Los formatos de `other` reemplazan a los iguales de esta tabla; los nuevos se añaden.
+    pub fn merge(&mut self, other: PackFormatTable) {
+        for (own, theirs) in [(&mut self.data, other.data), (&mut self.resource, other.resource)] {
+            for entry in theirs {
+                own.retain(|e| e.format.lower() != entry.format.lower());
+                own.push(entry);
+            }
+            own.sort_by_key(|e| e.format.lower());
+        }
+    }
+
+    pub fn entries(&self, kind: PackKind) -> &[FormatEntry] {
+        match kind {
+            PackKind::Data => &self.data,
+            PackKind::Resource => &self.resource,
+        }
+    }
+
+    /// Releases de MC cuyo formato cae en alguno de los rangos, en orden y sin repetir.
+    pub fn releases_in(&self, kind: PackKind, ranges: &[FormatRange]) -> Vec<String> {
+        self.release_runs(kind, ranges).into_iter().flatten().collect()
+    }
+
+    /// Como `releases_in`, pero partido en tramos de entradas consecutivas de la tabla: un pack con
+    /// huecos (p. ej. un overlay para formatos antiguos) da un tramo por cada bloque sin hueco.
+    pub fn release_runs(&self, kind: PackKind, ranges: &[FormatRange]) -> Vec<Vec<String>> {
+        let mut runs: Vec<Vec<String>> = Vec::new();
+        let mut seen: Vec<&String> = Vec::new();
+        let mut in_run = false;
+        for entry in self.entries(kind) {
+            if !ranges.iter().any(|r| r.contains(&entry.format)) {
+                in_run = false;
+                continue;
+            }
+            if !in_run {
+                runs.push(Vec::new());
+                in_run = true;
+            }
+            for release in &entry.releases {
+                if !seen.contains(&release) {
+                    seen.push(release);
+                    if let Some(run) = runs.last_mut() {
+                        run.push(release.clone());
+                    }
+                }
+            }
+        }
+        runs.retain(|run| !run.is_empty());
+        runs
+    }
+
+    /// ¿Hay algún rango por encima del formato más nuevo de la tabla? (pack para una versión que aún no conocemos)
+    pub fn exceeds_known(&self, kind: PackKind, ranges: &[FormatRange]) -> bool {
+        let Some(newest) = self.entries(kind).last() else { return false };
+        ranges.iter().any(|r| r.max.lower() > newest.format.lower())
+    }
+}
+
+fn parse_entries(raw: Vec<RawEntry>) -> Result<Vec<FormatEntry>, String> {
+    let mut entries = raw.into_iter()
+        .map(|e| match PackVersion::from_value(&e.format) {
+            Some(format) => Ok(FormatEntry { format, releases: e.releases }),
+            None => Err(format!("Formato no válido: {}", e.format)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.format.lower());
+    Ok(entries)
+}
+
+/// Resumen legible de los tramos de releases: "1.20.5 – 1.21.1", la única que haya, o varios
+/// tramos separados por comas ("1.21 – 1.21.3, 1.21.9 – 1.21.11").
+pub fn summarize_releases(runs: &[Vec<String>], open_ended: bool) -> Option<String> {
+    let parts: Vec<String> = runs.iter()
+        .filter_map(|run| {
+            let first = run.first()?;
+            let last = run.last()?;
+            Some(if first == last { first.clone() } else { format!("{} – {}", first, last) })
+        })
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    let mut summary = parts.join(", ");
+    if open_ended {
+        summary.push('+');
+    }
+    Some(summary)
+}
@@ -1,5 +1,7 @@
 use crate::paths_vars::PATHS;
-use super::models::DatapackInfo;
+use crate::local_mods_ops::cache;
+use super::models::{DatapackInfo, PackOverlay};
+use super::pack_format_table::{summarize_releases, FormatRange, PackKind, PackVersion, PACK_FORMAT_TABLE};
 use indexmap::IndexMap;
 use std::fs::{self, File};
 use std::io::Read;
@@ -7,102 +9,64 @@ use std::path::Path;
 use zip::ZipArchive;
 use std::time::SystemTime;
 use serde::Deserialize;
+use serde_json::Value;
 
 // ── Deserialización de pack.mcmeta ───────────────────────────
+// Los formatos se leen como JSON crudo: según la versión son enteros, floats ("94.1"),
+// arrays `[major, minor]` u objetos `{min_inclusive, max_inclusive}`.
 
 #[derive(Debug, Deserialize)]
 struct PackMcMeta {
     pack: PackInfo,
+    #[serde(default)]
+    overlays: Option<OverlaysSection>,
 }
 
 #[derive(Debug, Deserialize)]
 struct PackInfo {
-    #[serde(default, deserialize_with = "deserialize_pack_format")]
-    pack_format: Option<u32>,
-    #[serde(default, deserialize_with = "deserialize_supported_formats")]
-    supported_formats: Option<SupportedFormats>,
+    #[serde(default)]
+    pack_format: Option<Value>,
+    #[serde(default)]
+    supported_formats: Option<Value>,
+    #[serde(default)]
+    min_format: Option<Value>,
+    #[serde(default)]
+    max_format: Option<Value>,
 }
 
-/// Gracefully deserialize supported_formats, returning None if the format is unrecognized.
-fn deserialize_supported_formats<'de, D>(deserializer: D) -> Result<Option<SupportedFormats>, D::Error>
-where D: serde::Deserializer<'de> {
-    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
-    match value {
-        None => Ok(None),
-        Some(v) => Ok(serde_json::from_value::<SupportedFormats>(v).ok()),
+impl PackInfo {
+    /// Rango declarado: min_format/max_format (1.21.9+) o supported_formats (1.20.2+).
+    fn declared_range(&self) -> Option<FormatRange> {
+        FormatRange::from_min_max(self.min_format.as_ref(), self.max_format.as_ref())
+            .or_else(|| self.supported_formats.as_ref().and_then(FormatRange::from_supported))
     }
 }
 
-/// Accepts pack_format as integer, float (truncated), or missing.
-fn deserialize_pack_format<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
-where D: serde::Deserializer<'de> {
-    use serde::de;
-    struct PackFormatVisitor;
-    impl<'de> de::Visitor<'de> for PackFormatVisitor {
-        type Value = Option<u32>;
-        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-            f.write_str("an integer or float for pack_format")
-        }
-        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
-            Ok(Some(v as u32))
-        }
-        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
-            Ok(Some(v as u32))
-        }
-        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
-            Ok(Some(v as u32))
-        }
-    }
-    deserializer.deserialize_any(PackFormatVisitor)
+#[derive(Debug, Deserialize)]
+struct OverlaysSection {
+    #[serde(default)]
+    entries: Vec<OverlayEntry>,
 }
 
 #[derive(Debug, Deserialize)]
-#[serde(untagged)]
-enum SupportedFormats {
-    Range { min_inclusive: u32, max_inclusive: u32 },
-    Array(Vec<u32>),
-    Single(u32),
+struct OverlayEntry {
+    directory: String,
+    #[serde(default)]
+    formats: Option<Value>,
+    #[serde(default)]
+    min_format: Option<Value>,
+    #[serde(default)]
+    max_format: Option<Value>,
 }
 
-impl SupportedFormats {
-    fn as_range(&self) -> (u32, u32) {
-        return match self {
-            SupportedFormats::Range { min_inclusive, max_inclusive } => (*min_inclusive, *max_inclusive),
-            SupportedFormats::Array(arr) => {
-                let min = arr.iter().copied().min().unwrap_or(0);
-                let max = arr.iter().copied().max().unwrap_or(0);
-                (min, max)
-            }
-            SupportedFormats::Single(v) => (*v, *v),
-        };
+impl OverlayEntry {
+    fn to_overlay(&self) -> Option<PackOverlay> {
+        let formats = FormatRange::from_min_max(self.min_format.as_ref(), self.max_format.as_ref())
+            .or_else(|| self.formats.as_ref().and_then(FormatRange::from_supported))?;
+        Some(PackOverlay { directory: self.directory.clone(), formats })
     }
 }
 
-// ── Tabla pack_format → MC version (solo releases) ──────────
-
-/// Devuelve la versión de MC correspondiente a un pack_format de datapack.
-/// Solo cubre releases estables — los números intermedios son de snapshots.
-pub fn pack_format_to_mc(format: u32) -> Option<&'static str> {
-    return match format {
-        4  => Some("1.13"),
-        5  => Some("1.15"),
-        6  => Some("1.16.5"),
-        7  => Some("1.17.1"),
-        8  => Some("1.18.1"),
-        9  => Some("1.18.2"),
-        10 => Some("1.19"),
-        12 => Some("1.19.4"),
-        15 => Some("1.20.1"),
-        18 => Some("1.20.2"),
-        26 => Some("1.20.4"),
-        41 => Some("1.20.6"),
-        48 => Some("1.21.1"),
-        57 => Some("1.21.3"),
-        61 => Some("1.21.4"),
-        _  => None,
-    };
-}
-
 // ── Utilidades ───────────────────────────────────────────────
 
 fn get_file_mtime(metadata: &fs::Metadata) -> u64 {
@@ -165,26 +129,73 @@ fn clean_name_from_filename(filename: &str) -> String {
 
 // ── Parser de datapack ───────────────────────────────────────
 
-pub fn read_single_datapack(path: &Path) -> Result<DatapackInfo, String> {
+/// pack.mcmeta y nombres de las entradas de un datapack `.zip`.
+fn read_zip_contents(path: &Path) -> Result<(String, Vec<String>), String> {
     let file = File::open(path).map_err(|_| "No se pudo abrir archivo".to_string())?;
     let mut zip = ZipArchive::new(file).map_err(|_| "No es un ZIP válido".to_string())?;
 
-    // 1. Leer pack.mcmeta
     let mut mcmeta_str = String::new();
-    let mut found_mcmeta = false;
+    let mut names = Vec::with_capacity(zip.len());
     for i in 0..zip.len() {
         if let Ok(mut entry) = zip.by_index(i) {
             let name = entry.name().replace('\\', "/");
-            if name == "pack.mcmeta" {
-                if entry.read_to_string(&mut mcmeta_str).is_ok() {
-                    found_mcmeta = true;
-                    break;
-                }
+            if name == "pack.mcmeta" && mcmeta_str.is_empty() {
+                let _ = entry.read_to_string(&mut mcmeta_str);
             }
+            names.push(name);
+        }
+    }
+    Ok((mcmeta_str, names))
+}
+
+/// Lo mismo para un datapack descomprimido. Para los namespaces basta con el primer nivel de data/.
+fn read_folder_contents(path: &Path) -> Result<(String, Vec<String>), String> {
+    let mcmeta_str = fs::read_to_string(path.join("pack.mcmeta")).unwrap_or_default();
+    let mut names = Vec::new();
+    for top in ["data", "assets"] {
+        let Ok(entries) = fs::read_dir(path.join(top)) else { continue };
+        for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()) {
+            names.push(format!("{}/{}/", top, entry.file_name().to_string_lossy()));
         }
     }
+    Ok((mcmeta_str, names))
+}
+
+/// ¿Es un datapack que Minecraft cargaría? Un `.zip` o una carpeta con pack.mcmeta.
+pub fn is_datapack_path(path: &Path) -> bool {
+    if path.is_dir() {
+        path.join("pack.mcmeta").is_file()
+    } else {
+        path.extension().and_then(|s| s.to_str()) == Some("zip")
+    }
+}
+
+/// Tamaño y mtime para invalidar la caché. En las carpetas, la suma de tamaños y el mtime más
+/// reciente de todo el árbol: editar cualquier archivo del pack cuenta como cambio.
+pub fn pack_stamp(path: &Path) -> (u64, u64) {
+    // La carpeta del pack puede ser un enlace (p.ej. al repo donde se desarrolla); dentro no se siguen
+    fs::metadata(path).map(|meta| tree_stamp(path, &meta)).unwrap_or((0, 0))
+}
+
+fn tree_stamp(path: &Path, meta: &fs::Metadata) -> (u64, u64) {
+    if !meta.is_dir() {
+        return (meta.len(), get_file_mtime(meta));
+    }
+    let mut stamp = (0, get_file_mtime(meta));
+    for entry in fs::read_dir(path).into_iter().flatten().filter_map(|e| e.ok()) {
+        let Ok(child) = fs::symlink_metadata(entry.path()) else { continue };
+        let (size, mtime) = tree_stamp(&entry.path(), &child);
+        stamp = (stamp.0 + size, stamp.1.max(mtime));
+    }
+    stamp
+}
 
-    if !found_mcmeta || mcmeta_str.is_empty() {
+pub fn read_single_datapack(path: &Path) -> Result<DatapackInfo, String> {
+    // 1. Leer pack.mcmeta (del zip o de la carpeta)
+    let is_folder = path.is_dir();
+    let (mcmeta_str, entry_names) = if is_folder { read_folder_contents(path)? } else { read_zip_contents(path)? };
+
+    if mcmeta_str.is_empty() {
         return Err("No se encontró pack.mcmeta".to_string());
     }
 
@@ -202,15 +213,15 @@ pub fn read_single_datapack(path: &Path) -> Result<DatapackInfo, String> {
     // 3. Detectar namespaces en data/ (excluir "minecraft")
     let mut namespaces: Vec<String> = Vec::new();
     let mut seen = std::collections::HashSet::new();
-    for i in 0..zip.len() {
-        if let Ok(entry) = zip.by_index(i) {
-            let name = entry.name().replace('\\', "/");
-            if name.starts_with("data/") {
-                let parts: Vec<&str> = name.split('/').collect();
-                if parts.len() >= 2 && parts[1] != "minecraft" && !parts[1].is_empty() {
-                    if seen.insert(parts[1].to_string()) {
-                        namespaces.push(parts[1].to_string());
-                    }
+    let (mut has_data, mut has_assets) = (false, false);
+    for name in &entry_names {
+        has_data |= name.starts_with("data/");
+        has_assets |= name.starts_with("assets/");
+        if name.starts_with("data/") {
+            let parts: Vec<&str> = name.split('/').collect();
+            if parts.len() >= 2 && parts[1] != "minecraft" && !parts[1].is_empty() {
+                if seen.insert(parts[1].to_string()) {
+                    namespaces.push(parts[1].to_string());
                 }
             }
         }
@@ -237,28 +248,65 @@ pub fn read_single_datapack(path: &Path) -> Result<DatapackInfo, String> {
     let name = clean_name_from_filename(&filename);
     let version_local = extract_version_from_filename(&filename);
 
-    // 6. MC version desde pack_format
-    let mc_version = pack_meta.pack.pack_format
-        .and_then(|pf| pack_format_to_mc(pf))
-        .map(|s| s.to_string());
+    // 6. Formatos declarados (base + overlays) → versiones de MC según la tabla
+    // Sin data/ pero con assets/ es un resource pack: su numeración es otra
+    let kind = if has_assets && !has_data { PackKind::Resource } else { PackKind::Data };
+    let pack_format = pack_meta.pack.pack_format.as_ref().and_then(PackVersion::from_value);
+    let supported_formats = pack_meta.pack.declared_range();
+    let overlays: Vec<PackOverlay> = pack_meta.overlays.iter()
+        .flat_map(|o| &o.entries)
+        .filter_map(OverlayEntry::to_overlay)
+        .collect();
 
-    // 7. Supported formats
-    let supported_formats = pack_meta.pack.supported_formats.map(|sf| sf.as_range());
 
-    return Ok(DatapackInfo {
+    let mut info = DatapackInfo {
         key: filename,
         name,
         detected_project_id,
         confirmed_project_id: None,
-        pack_format: pack_meta.pack.pack_format,
+        pack_format,
         supported_formats,
-        mc_version,
+        overlays,
+        kind,
+        is_folder,
         version_local,
         version_remote: None,
         selected: true,
-        file_size_bytes: None,
-        file_mtime_secs: None,
-    });
+        ..Default::default()
+    };
+    resolve_mc_versions(&mut info);
+    return Ok(info);
+}
+
+/// Rellena mc_versions/mc_version con la tabla actual. Se repite en cada acierto de caché
+/// para que los cambios en la tabla del usuario se vean sin volver a leer los zips.
+fn resolve_mc_versions(info: &mut DatapackInfo) {
+    let mut ranges: Vec<FormatRange> = info.supported_formats.into_iter().collect();
+    ranges.extend(info.pack_format.map(FormatRange::single));
+    ranges.extend(info.overlays.iter().map(|o| o.formats));
+    let runs = PACK_FORMAT_TABLE.release_runs(info.kind, &ranges);
+    info.mc_version = summarize_releases(&runs, PACK_FORMAT_TABLE.exceeds_known(info.kind, &ranges));
+    info.mc_versions = runs.into_iter().flatten().collect();
+}
+
+/// Como `read_single_datapack`, pero sin abrir el pack si la caché tiene el mismo tamaño y mtime.
+/// Lo leído se guarda en la caché bajo `world_name`.
+pub fn read_datapack_cached(world_name: &str, path: &Path) -> Result<DatapackInfo, String> {
+    let (file_size, file_mtime) = pack_stamp(path);
+    let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    if let Some(mut cached) = cache::get_datapack(world_name, &filename)
+        && cached.file_size_bytes == Some(file_size) && cached.file_mtime_secs == Some(file_mtime)
+    {
+        resolve_mc_versions(&mut cached);
+        return Ok(cached);
+    }
+
+    let mut info = read_single_datapack(path)?;
+    info.file_size_bytes = Some(file_size);
+    info.file_mtime_secs = Some(file_mtime);
+    cache::upsert_datapack(world_name, path, &info);
+    Ok(info)
 }
 
 // ── Scanner de mundos y datapacks ────────────────────────────
@@ -297,27 +345,21 @@ pub fn read_datapacks_in_world(world_name: &str) -> IndexMap<String, DatapackInf
 
         for entry in entries_vec {
             let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+            if is_datapack_path(&path) {
                 let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
 
-                let (file_size, file_mtime) = if let Ok(meta) = fs::metadata(&path) {
-                    (meta.len(), get_file_mtime(&meta))
-                } else {
-                    (0, 0)
-                };
-
-                if let Ok(mut info) = read_single_datapack(&path) {
-                    info.file_size_bytes = Some(file_size);
-                    info.file_mtime_secs = Some(file_mtime);
+                if let Ok(info) = read_datapack_cached(world_name, &path) {
                     packs_map.insert(filename, info);
                 } else {
                     // Datapack sin metadata válido — registrar con info mínima
+                    let (file_size, file_mtime) = pack_stamp(&path);
                     let name = clean_name_from_filename(&filename);
                     packs_map.insert(filename.clone(), DatapackInfo {
                         key: filename,
                         name,
                         file_size_bytes: Some(file_size),
                         file_mtime_secs: Some(file_mtime),
+                        is_folder: path.is_dir(),
                         selected: true,
                         ..Default::default()
                     });
@@ -10,7 +10,7 @@ pub struct ReadJob {
 
 #[derive(Debug, Clone)]
 pub enum ReadEvent {
-    Done { info: ModInfo },
+    Done { info: Box<ModInfo> },
     Error { path: PathBuf, msg: String },
 }
 
@@ -20,7 +20,7 @@ pub fn spawn_read_workers(n: usize, rx: Receiver<ReadJob>, tx: Sender<ReadEvent>
             Ok(info) => {
                 let filename = job.file_path.file_name().unwrap_or_default().to_string_lossy().to_string();
                 crate::local_mods_ops::cache::upsert_mod(&filename, &info);
-                let _ = tx.send(ReadEvent::Done { info });
+                let _ = tx.send(ReadEvent::Done { info: Box::new(info) });
             }
             Err(e) => {
                 let _ = tx.send(ReadEvent::Error { path: job.file_path, msg: e });
@@ -1,15 +1,19 @@
-use redb::{Database, TableDefinition, ReadableTable};
-use std::path::PathBuf;
+use redb::{Database, TableDefinition, ReadableTable, Table};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::sync::OnceLock;
 use crate::paths_vars::PATHS;
 use crate::local_mods_ops::models::{ModInfo, CachedFile, CachedProject};
+use crate::local_datapacks_ops::models::{CachedDatapack, DatapackInfo};
 
 // Define tables
 // FILES: filename (String) -> serialized CachedFile (JSON String)
 const TABLE_FILES: TableDefinition<&str, &str> = TableDefinition::new("files");
 // PROJECTS: project_id (String) -> serialized CachedProject (JSON String)
 const TABLE_PROJECTS: TableDefinition<&str, &str> = TableDefinition::new("projects");
+// DATAPACKS: "mundo/archivo" (String) -> serialized CachedDatapack (JSON String)
+const TABLE_DATAPACKS: TableDefinition<&str, &str> = TableDefinition::new("datapacks");
 
 // Global Database Instance (Redb es Send+Sync con MVCC, no necesita Mutex)
 static DB: OnceLock<Database> = OnceLock::new();
@@ -49,6 +53,7 @@ pub fn init_with_path(db_path: PathBuf) -> bool {
             };
             let _ = write_txn.open_table(TABLE_FILES);
             let _ = write_txn.open_table(TABLE_PROJECTS);
+            let _ = write_txn.open_table(TABLE_DATAPACKS);
             let _ = write_txn.commit();
             
             let _ = DB.set(db);
@@ -66,7 +71,31 @@ pub fn get_mod(filename: &str) -> Option<ModInfo> {
     let read_txn = db.begin_read().ok()?;
     let table_files = read_txn.open_table(TABLE_FILES).ok()?;
     let table_projects = read_txn.open_table(TABLE_PROJECTS).ok()?;
-    
+    read_mod(&table_files, &table_projects, filename)
+}
+
+/// Como `get_mod` para muchos archivos a la vez, en una sola transacción de lectura.
+/// Solo devuelve los que están en la caché.
+pub fn get_mods<'a>(filenames: impl IntoIterator<Item = &'a str>) -> HashMap<String, ModInfo> {
+    let mut found = HashMap::new();
+    let Some(db) = db() else { return found };
+    let Ok(read_txn) = db.begin_read() else { return found };
+    let (Ok(table_files), Ok(table_projects)) = (read_txn.open_table(TABLE_FILES), read_txn.open_table(TABLE_PROJECTS)) else {
+        return found;
+    };
+    for filename in filenames {
+        if let Some(info) = read_mod(&table_files, &table_projects, filename) {
+            found.insert(filename.to_string(), info);
+        }
+    }
+    found
+}
+
+fn read_mod(
+    table_files: &impl ReadableTable<&'static str, &'static str>,
+    table_projects: &impl ReadableTable<&'static str, &'static str>,
+    filename: &str,
+) -> Option<ModInfo> {
     // 1. Get File Info
     let access = table_files.get(filename).ok()??;
     let file_json = access.value();
@@ -89,62 +118,85 @@ pub fn get_mod(filename: &str) -> Option<ModInfo> {
         file_size_bytes: cached_file.file_size_bytes,
         file_mtime_secs: cached_file.file_mtime_secs,
         depends: cached_file.depends,
+        breaks: cached_file.breaks,
+        loaders: cached_file.loaders,
+        bundled: cached_file.bundled,
         has_local_icon: proj.has_local_icon,
+        hashes: cached_file.hashes,
+        remote_date: proj.remote_date,
+        update_state: None,
+        pinned: None,
     })
 }
 
 pub fn upsert_mod(filename: &str, info: &ModInfo) {
+    upsert_mods([(filename, info)]);
+}
+
+/// Como `upsert_mod` para muchos archivos, en una sola transacción de escritura.
+pub fn upsert_mods<'a>(mods: impl IntoIterator<Item = (&'a str, &'a ModInfo)>) {
     let Some(db) = db() else { return };
     let Ok(write_txn) = db.begin_write() else { return };
     {
-        let mut table_files = write_txn.open_table(TABLE_FILES).unwrap();
-        let mut table_projects = write_txn.open_table(TABLE_PROJECTS).unwrap();
-
-        let project_id = info.confirmed_project_id.clone()
-            .or(info.detected_project_id.clone())
-            .unwrap_or(info.name.clone());
-
-        let mut project_to_save = CachedProject {
-            name: info.name.clone(),
-            detected_project_id: info.detected_project_id.clone(),
-            confirmed_project_id: info.confirmed_project_id.clone(),
-            version_remote: info.version_remote.clone(),
-            has_local_icon: info.has_local_icon,
-        };
+        let Ok(mut table_files) = write_txn.open_table(TABLE_FILES) else { return };
+        let Ok(mut table_projects) = write_txn.open_table(TABLE_PROJECTS) else { return };
+        for (filename, info) in mods {
+            write_mod(&mut table_files, &mut table_projects, filename, info);
+        }
+    }
+    let _ = write_txn.commit();
+}
 
-        // Preservar confirmed_id y version_remote existentes si el nuevo info no los tiene
-        if let Ok(Some(existing_access)) = table_projects.get(project_id.as_str()) {
-            if let Ok(existing) = serde_json::from_str::<CachedProject>(existing_access.value()) {
-                if project_to_save.confirmed_project_id.is_none() {
-                    project_to_save.confirmed_project_id = existing.confirmed_project_id;
-                }
-                if project_to_save.version_remote.is_none() {
-                    project_to_save.version_remote = existing.version_remote;
-                }
-                // Si ya había icono y ahora no lo comprobamos, lo mantenemos (o si el nuevo dice true, machaca)
-                if !project_to_save.has_local_icon {
-                    project_to_save.has_local_icon = existing.has_local_icon;
-                }
+fn write_mod(table_files: &mut Table<&str, &str>, table_projects: &mut Table<&str, &str>, filename: &str, info: &ModInfo) {
+    let project_id = info.confirmed_project_id.clone()
+        .or(info.detected_project_id.clone())
+        .unwrap_or(info.name.clone());
+
+    let mut project_to_save = CachedProject {
+        name: info.name.clone(),
+        detected_project_id: info.detected_project_id.clone(),
+        confirmed_project_id: info.confirmed_project_id.clone(),
+        version_remote: info.version_remote.clone(),
+        has_local_icon: info.has_local_icon,
+        remote_date: info.remote_date.clone(),
+    };
+
+    // Preservar confirmed_id y version_remote existentes si el nuevo info no los tiene
+    if let Ok(Some(existing_access)) = table_projects.get(project_id.as_str()) {
+        if let Ok(existing) = serde_json::from_str::<CachedProject>(existing_access.value()) {
+            if project_to_save.confirmed_project_id.is_none() {
+                project_to_save.confirmed_project_id = existing.confirmed_project_id;
+            }
+            if project_to_save.version_remote.is_none() {
+                project_to_save.version_remote = existing.version_remote;
+                project_to_save.remote_date = existing.remote_date;
+            }
+            // Si ya había icono y ahora no lo comprobamos, lo mantenemos (o si el nuevo dice true, machaca)
+            if !project_to_save.has_local_icon {
+                project_to_save.has_local_icon = existing.has_local_icon;
             }
         }
+    }
 
-        if let Ok(json_proj) = serde_json::to_string(&project_to_save) {
-            let _ = table_projects.insert(project_id.as_str(), json_proj.as_str());
-        }
+    if let Ok(json_proj) = serde_json::to_string(&project_to_save) {
+        let _ = table_projects.insert(project_id.as_str(), json_proj.as_str());
+    }
 
-        let file_to_save = CachedFile {
-            file_size_bytes: info.file_size_bytes,
-            file_mtime_secs: info.file_mtime_secs,
-            version_local: info.version_local.clone(),
-            depends: info.depends.clone(),
-            project_ref: project_id.clone(),
-        };
+    let file_to_save = CachedFile {
+        file_size_bytes: info.file_size_bytes,
+        file_mtime_secs: info.file_mtime_secs,
+        version_local: info.version_local.clone(),
+        depends: info.depends.clone(),
+        breaks: info.breaks.clone(),
+        loaders: info.loaders.clone(),
+        bundled: info.bundled.clone(),
+        project_ref: project_id.clone(),
+        hashes: info.hashes.clone(),
+    };
 
-        if let Ok(json_file) = serde_json::to_string(&file_to_save) {
-            let _ = table_files.insert(filename, json_file.as_str());
-        }
+    if let Ok(json_file) = serde_json::to_string(&file_to_save) {
+        let _ = table_files.insert(filename, json_file.as_str());
     }
-    let _ = write_txn.commit();
 }
 
 pub fn update_remote_info(filename: &str, project_id: Option<String>, version_remote: Option<String>) {
@@ -189,7 +241,7 @@ pub fn update_remote_info_with_deps(filename: &str, project_id: Option<String>,
         if let Some(deps) = dependencies {
             if let Some(mut file) = existing_file {
                 let mut dep_map = std::collections::HashMap::new();
-                for d in deps {
+                for d in deps.iter().filter(|d| d.kind == crate::fetch::fetch_from_api::DependencyKind::Required) {
                     dep_map.insert(d.mod_id.clone(), "required".to_string());
                 }
                 file.depends = Some(dep_map);
@@ -202,6 +254,54 @@ pub fn update_remote_info_with_deps(filename: &str, project_id: Option<String>,
     let _ = write_txn.commit();
 }
 
+/// Fija el confirmed_project_id del proyecto al que apunta `filename` (p.ej. tras identificarlo por hash),
+/// sin tocar version_remote.
+pub fn set_confirmed_project_id(filename: &str, project_id: &str) {
+    update_project_of_file(filename, |proj| {
+        proj.confirmed_project_id = Some(project_id.to_string());
+    });
+}
+
+/// Guarda el resultado de la comprobación de actualizaciones: proyecto, versión remota real y su fecha.
+pub fn update_remote_check(filename: &str, project_id: Option<String>, version_remote: Option<String>, remote_date: Option<String>) {
+    update_project_of_file(filename, |proj| {
+        if project_id.is_some() {
+            proj.confirmed_project_id = project_id;
+        }
+        proj.version_remote = version_remote;
+        proj.remote_date = remote_date;
+    });
+}
+
+/// Aplica `update` al CachedProject referenciado por el archivo `filename`, en una sola transacción.
+fn update_project_of_file(filename: &str, update: impl FnOnce(&mut CachedProject)) {
+    let Some(db) = db() else { return };
+    let Ok(write_txn) = db.begin_write() else { return };
+    {
+        let Ok(table_files) = write_txn.open_table(TABLE_FILES) else { return };
+        let Ok(mut table_projects) = write_txn.open_table(TABLE_PROJECTS) else { return };
+
+        let project_ref = match table_files.get(filename) {
+            Ok(Some(access)) => serde_json::from_str::<CachedFile>(access.value()).ok().map(|f| f.project_ref),
+            _ => None,
+        };
+        let Some(project_ref) = project_ref else { return };
+
+        let new_json = match table_projects.get(project_ref.as_str()) {
+            Ok(Some(access)) => serde_json::from_str::<CachedProject>(access.value()).ok()
+                .and_then(|mut proj| {
+                    update(&mut proj);
+                    serde_json::to_string(&proj).ok()
+                }),
+            _ => None,
+        };
+        if let Some(json) = new_json {
+            let _ = table_projects.insert(project_ref.as_str(), json.as_str());
+        }
+    }
+    let _ = write_txn.commit();
+}
+
 /// Busca el confirmed_project_id directamente en la tabla PROJECTS por detected_project_id.
 /// Esto evita depender del filename (que cambia entre versiones del mod).
 pub fn get_confirmed_id(detected_id: &str) -> Option<String> {
@@ -213,6 +313,54 @@ pub fn get_confirmed_id(detected_id: &str) -> Option<String> {
     proj.confirmed_project_id
 }
 
+// ── Datapacks ────────────────────────────────────────────────
+
+/// Los datapacks se guardan por mundo: el mismo zip puede estar en varios con distinto mtime.
+fn datapack_key(world_name: &str, filename: &str) -> String {
+    format!("{}/{}", world_name, filename)
+}
+
+pub fn get_datapack(world_name: &str, filename: &str) -> Option<DatapackInfo> {
+    let db = db()?;
+    let read_txn = db.begin_read().ok()?;
+    let table = read_txn.open_table(TABLE_DATAPACKS).ok()?;
+    let access = table.get(datapack_key(world_name, filename).as_str()).ok()??;
+    let cached: CachedDatapack = serde_json::from_str(access.value()).ok()?;
+    Some(cached.info)
+}
+
+pub fn upsert_datapack(world_name: &str, path: &Path, info: &DatapackInfo) {
+    let Some(db) = db() else { return };
+    let Ok(write_txn) = db.begin_write() else { return };
+    {
+        let Ok(mut table) = write_txn.open_table(TABLE_DATAPACKS) else { return };
+        let cached = CachedDatapack { path: path.to_path_buf(), info: info.clone() };
+        if let Ok(json) = serde_json::to_string(&cached) {
+            let _ = table.insert(datapack_key(world_name, &info.key).as_str(), json.as_str());
+        }
+    }
+    let _ = write_txn.commit();
+}
+
+/// Quita de DATAPACKS los packs cuyo archivo ya no existe (mundo o pack borrados).
+fn prune_datapacks(write_txn: &redb::WriteTransaction) -> usize {
+    let Ok(mut table) = write_txn.open_table(TABLE_DATAPACKS) else { return 0 };
+    let mut to_remove = Vec::new();
+    if let Ok(iter) = table.iter() {
+        for (key, value) in iter.flatten() {
+            let exists = serde_json::from_str::<CachedDatapack>(value.value()).is_ok_and(|c| c.path.exists());
+            if !exists {
+                to_remove.push(key.value().to_string());
+            }
+        }
+    }
+    for key in &to_remove {
+        let _ = table.remove(key.as_str());
+    }
+    to_remove.len()
+}
+
+/// Borra los jars que no están en `valid_filenames` y los datapacks cuyo archivo ya no existe.
 pub fn prune_db(valid_filenames: &std::collections::HashSet<String>) -> usize {
     let Some(db) = db() else { return 0 };
     let Ok(write_txn) = db.begin_write() else { return 0 };
@@ -241,6 +389,7 @@ pub fn prune_db(valid_filenames: &std::collections::HashSet<String>) -> usize {
             let _ = table.remove(k.as_str());
         }
     }
+    let count = count + prune_datapacks(&write_txn);
     let _ = write_txn.commit();
     count
 }
@@ -112,17 +112,21 @@ pub fn read_active_marker() -> Option<String> {
     return None;
 }
 
-/// Elimina archivos `.part` huérfanos de descargas interrumpidas.
+/// Antigüedad a partir de la cual un `.part` ya no se reanuda y se borra.
+pub const PART_FILE_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Elimina archivos `.part` abandonados (más antiguos que `PART_FILE_MAX_AGE`).
+/// Los recientes se conservan: la siguiente descarga del mismo archivo los reanuda con `Range`.
 /// Escanea recursivamente la carpeta de modpacks.
 pub fn cleanup_partial_downloads() {
     let dir = &PATHS.modpacks_folder;
     if !dir.exists() {
         return;
     }
-    cleanup_part_files_recursive(dir);
+    cleanup_part_files_recursive(dir, PART_FILE_MAX_AGE);
 }
 
-fn cleanup_part_files_recursive(dir: &Path) {
+pub fn cleanup_part_files_recursive(dir: &Path, max_age: std::time::Duration) {
     let entries = match fs::read_dir(dir) {
         Ok(e) => e,
         Err(_) => return,
@@ -131,9 +135,16 @@ fn cleanup_part_files_recursive(dir: &Path) {
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() {
-            cleanup_part_files_recursive(&path);
+            cleanup_part_files_recursive(&path, max_age);
         } else if path.extension().and_then(|e| e.to_str()) == Some("part") {
-            println!("🧹 Eliminando descarga parcial: {}", path.display());
+            let age = entry.metadata().ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.elapsed().ok());
+            // Si no se puede saber la edad se conserva: como mucho se reanuda y se verifica
+            if age.is_none_or(|a| a < max_age) {
+                continue;
+            }
+            println!("🧹 Eliminando descarga parcial abandonada: {}", path.display());
             let _ = fs::remove_file(&path);
         }
     }
@@ -0,0 +1,201 @@
+//! Hashes de los jars locales para identificarlos de forma exacta en Modrinth y CurseForge.
+//! - SHA-1 / SHA-512: claves del endpoint `version_files` de Modrinth.
+//! - Fingerprint (MurmurHash2): clave del endpoint `fingerprints` de CurseForge.
+//! - Verificación de archivos contra el tamaño y los hashes que publica cada plataforma.
+
+use serde::{Serialize, Deserialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct FileHashes {
+    pub sha1: String,
+    pub sha512: String,
+    /// Fingerprint de CurseForge (MurmurHash2 sobre el archivo sin espacios en blanco).
+    pub cf_fingerprint: u32,
+}
+
+/// Calcula todos los hashes de un archivo leyéndolo una sola vez.
+pub fn hash_file(path: &Path) -> std::io::Result<FileHashes> {
+    let bytes = std::fs::read(path)?;
+    Ok(hash_bytes(&bytes))
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> FileHashes {
+    FileHashes {
+        sha1: to_hex(&Sha1::digest(bytes)),
+        sha512: to_hex(&Sha512::digest(bytes)),
+        cf_fingerprint: curseforge_fingerprint(bytes),
+    }
+}
+
+/// Tamaño y hashes que publica la plataforma para un archivo remoto. Los campos ausentes no se comprueban.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExpectedFile {
+    pub size: Option<u64>,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+    /// Solo lo publican los packs de packwiz (su hash por defecto)
+    pub sha256: Option<String>,
+    /// Fingerprint de CurseForge (el `murmur2` de packwiz)
+    pub cf_fingerprint: Option<u32>,
+}
+
+impl ExpectedFile {
+    pub fn is_empty(&self) -> bool {
+        self.size.is_none() && !self.has_hash()
+    }
+
+    /// ¿Hay algún hash con el que comprobar el contenido (no solo el tamaño)?
+    pub fn has_hash(&self) -> bool {
+        self.sha1.is_some() || self.sha512.is_some() || self.sha256.is_some() || self.cf_fingerprint.is_some()
+    }
+}
+
+/// Comprueba un archivo contra lo publicado. Devuelve `InvalidData` con el motivo si no coincide.
+/// Sirve tanto para el `.part` recién descargado como para jars ya instalados.
+pub fn verify_file(path: &Path, expected: &ExpectedFile) -> std::io::Result<()> {
+    if expected.is_empty() {
+        return Ok(());
+    }
+
+    // El tamaño es gratis: descarta descargas truncadas sin leer el archivo
+    if let Some(size) = expected.size {
+        let actual = std::fs::metadata(path)?.len();
+        if actual != size {
+            return Err(mismatch(format!("tamaño {} bytes, se esperaban {}", actual, size)));
+        }
+    }
+
+    if !expected.has_hash() {
+        return Ok(());
+    }
+    check_hashes(std::fs::File::open(path)?, expected)
+}
+
+/// Como `verify_file` para un contenido ya en memoria (metafiles e índices de packwiz).
+pub fn verify_bytes(bytes: &[u8], expected: &ExpectedFile) -> std::io::Result<()> {
+    if let Some(size) = expected.size
+        && bytes.len() as u64 != size
+    {
+        return Err(mismatch(format!("tamaño {} bytes, se esperaban {}", bytes.len(), size)));
+    }
+    check_hashes(bytes, expected)
+}
+
+fn check_hashes(mut file: impl Read, expected: &ExpectedFile) -> std::io::Result<()> {
+    let mut sha1 = Sha1::new();
+    let mut sha512 = Sha512::new();
+    let mut sha256 = Sha256::new();
+    // MurmurHash2 no es incremental: se acumulan los bytes sin espacios en blanco
+    let mut normalized = Vec::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        if expected.sha1.is_some() {
+            sha1.update(&buf[..n]);
+        }
+        if expected.sha512.is_some() {
+            sha512.update(&buf[..n]);
+        }
+        if expected.sha256.is_some() {
+            sha256.update(&buf[..n]);
+        }
+        if expected.cf_fingerprint.is_some() {
+            normalized.extend(buf[..n].iter().copied().filter(|b| !is_fingerprint_whitespace(*b)));
+        }
+    }
+
+    if let Some(want) = &expected.sha1 {
+        let got = to_hex(&sha1.finalize());
+        if !got.eq_ignore_ascii_case(want) {
+            return Err(mismatch(format!("SHA-1 {} no coincide con {}", got, want)));
+        }
+    }
+    if let Some(want) = &expected.sha512 {
+        let got = to_hex(&sha512.finalize());
+        if !got.eq_ignore_ascii_case(want) {
+            return Err(mismatch(format!("SHA-512 {} no coincide con el publicado", got)));
+        }
+    }
+    if let Some(want) = &expected.sha256 {
+        let got = to_hex(&sha256.finalize());
+        if !got.eq_ignore_ascii_case(want) {
+            return Err(mismatch(format!("SHA-256 {} no coincide con {}", got, want)));
+        }
+    }
+    if let Some(want) = expected.cf_fingerprint {
+        let got = murmur2(&normalized, 1);
+        if got != want {
+            return Err(mismatch(format!("fingerprint {} no coincide con {}", got, want)));
+        }
+    }
+    Ok(())
+}
+
+/// SHA-256 en hex (hash por defecto de los índices de packwiz).
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+fn mismatch(msg: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Archivo corrupto: {}", msg))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fingerprint de CurseForge: MurmurHash2 (32 bits, semilla 1) ignorando tab, LF, CR y espacio.
+pub fn curseforge_fingerprint(bytes: &[u8]) -> u32 {
+    let normalized: Vec<u8> = bytes.iter()
+        .copied()
+        .filter(|b| !is_fingerprint_whitespace(*b))
+        .collect();
+    murmur2(&normalized, 1)
+}
+
+/// Tab, LF, CR y espacio: CurseForge los quita antes de calcular el fingerprint.
+fn is_fingerprint_whitespace(b: u8) -> bool {
+    matches!(b, 9 | 10 | 13 | 32)
+}
+
+fn murmur2(data: &[u8], seed: u32) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let mut h: u32 = seed ^ (data.len() as u32);
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    let tail = chunks.remainder();
+    if tail.len() >= 3 {
+        h ^= (tail[2] as u32) << 16;
+    }
+    if tail.len() >= 2 {
+        h ^= (tail[1] as u32) << 8;
+    }
+    if !tail.is_empty() {
+        h ^= tail[0] as u32;
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
@@ -0,0 +1,26 @@
+use std::collections::BTreeMap;
+use super::models::ModInfo;
+
+/// ¿Puede cargar `loader` este jar? Quilt carga también los mods de Fabric. Un jar sin loaders
+/// detectados (caché antigua, metadata rara) se da por compatible para no avisar en falso.
+pub fn supports_loader(info: &ModInfo, loader: &str) -> bool {
+    if info.loaders.is_empty() {
+        return true;
+    }
+    info.loaders.iter().any(|l| {
+        l.eq_ignore_ascii_case(loader) || (loader.eq_ignore_ascii_case("Quilt") && l == "Fabric")
+    })
+}
+
+/// Loader al que apunta la mayoría de jars (los multi-loader cuentan para cada uno de los suyos).
+/// En empate gana el primero por orden alfabético; None si ningún jar declara loader.
+pub fn majority_loader<'a>(mods: impl IntoIterator<Item = &'a ModInfo>) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for info in mods {
+        for loader in &info.loaders {
+            *counts.entry(loader.as_str()).or_default() += 1;
+        }
+    }
+    let max = counts.values().copied().max()?;
+    counts.into_iter().find(|(_, n)| *n == max).map(|(l, _)| l.to_string())
+}
@@ -4,6 +4,9 @@ pub mod fs_ops;
 pub mod async_reader;
 pub mod cache;
 pub mod parsers;
+pub mod hashing;
+pub mod loaders;
+pub mod validation;
 
 pub use models::*;
 pub use scanner::*;
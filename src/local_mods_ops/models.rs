@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use super::hashing::{ExpectedFile, FileHashes};
 
 // Información básica de un mod (Utilizada en la UI y lógica general)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,7 +18,51 @@ pub struct ModInfo {
     #[serde(default)]
     pub depends: Option<std::collections::HashMap<String, String>>,
     #[serde(default)]
+    pub breaks: Option<std::collections::HashMap<String, String>>, // Mods incompatibles (modId → rango)
+    #[serde(default)]
+    pub loaders: Vec<String>, // Loaders para los que está hecho el jar según su metadata ("Forge", "NeoForge"...)
+    #[serde(default)]
+    pub bundled: Option<Vec<BundledJar>>, // Jars incluidos dentro (jar-in-jar); None = aún sin inspeccionar
+    #[serde(default)]
     pub has_local_icon: bool,
+    #[serde(default)]
+    pub hashes: Option<FileHashes>,
+    #[serde(default)]
+    pub remote_date: Option<String>, // Fecha de publicación de version_remote ("YYYY-MM-DD")
+    #[serde(skip)]
+    pub update_state: Option<UpdateState>, // Resultado de la última comprobación (solo en memoria)
+    #[serde(default)]
+    pub pinned: Option<PinnedFile>, // Archivo exacto de un pack importado (.mrpack, manifest...)
+}
+
+/// Jar incluido dentro de otro (`jars` de fabric.mod.json / quilt.mod.json,
+/// META-INF/jarjar/metadata.json de Forge y NeoForge). Cuenta como mod instalado.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct BundledJar {
+    pub path: String, // Ruta dentro del jar contenedor
+    pub mod_id: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Archivo exacto fijado por un modpack importado. Mientras se instale para la misma versión de MC
+/// que el pack, se descarga tal cual en lugar de buscar la última versión compatible.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct PinnedFile {
+    pub url: String,
+    pub filename: String,
+    #[serde(default)]
+    pub version_id: Option<String>,
+    #[serde(default)]
+    pub expected: ExpectedFile,
+}
+
+/// Estado de un mod tras la comprobación de actualizaciones para una versión de MC + loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateState {
+    UpToDate,
+    Outdated,
+    /// No hay ningún archivo compatible con la versión/loader elegidos
+    Unavailable,
 }
 
 impl ModInfo {
@@ -77,6 +122,8 @@ pub struct CachedProject {
     pub version_remote: Option<String>,
     #[serde(default)]
     pub has_local_icon: bool,
+    #[serde(default)]
+    pub remote_date: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,5 +132,13 @@ pub struct CachedFile {
     pub file_mtime_secs: Option<u64>,
     pub version_local: Option<String>,
     pub depends: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub breaks: Option<std::collections::HashMap<String, String>>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub bundled: Option<Vec<BundledJar>>,
     pub project_ref: String, // Reference to project_id (usually detected_project_id or fallback)
+    #[serde(default)]
+    pub hashes: Option<FileHashes>,
 }
@@ -72,6 +72,8 @@ pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
         file_size_bytes: None,
         file_mtime_secs: None,
         depends,
+        loaders: vec!["Fabric".to_string()],
         has_local_icon,
+        ..Default::default()
     })
 }
@@ -1,11 +1,17 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::Path;
 use zip::ZipArchive;
 use std::fs::File;
 use crate::local_mods_ops::ModInfo;
 use super::read_zip_entry;
 
-// ── Deserialización de META-INF/mods.toml (Forge / NeoForge) ─
+/// Forge (y NeoForge hasta 1.20.1).
+const FORGE_MODS_TOML: &str = "META-INF/mods.toml";
+/// NeoForge 1.20.2+.
+const NEOFORGE_MODS_TOML: &str = "META-INF/neoforge.mods.toml";
+
+// ── Deserialización de mods.toml / neoforge.mods.toml ────────
 
 #[derive(Debug, Deserialize)]
 struct ForgeModToml {
@@ -26,11 +32,75 @@ struct ForgeModEntry {
     logo_file: Option<String>,
 }
 
+/// Tipo de una dependencia: `type` de NeoForge ("required", "optional", "incompatible", "discouraged")
+/// o, en Forge, `mandatory` (sin él se considera obligatoria).
+fn dependency_type(dep: &toml::value::Table) -> String {
+    dep.get("type")
+        .and_then(|v| v.as_str())
+        .map(|t| t.to_lowercase())
+        .unwrap_or_else(|| {
+            let mandatory = dep.get("mandatory").and_then(|v| v.as_bool()).unwrap_or(true);
+            if mandatory { "required".to_string() } else { "optional".to_string() }
+        })
+}
+
+/// Dependencias obligatorias e incompatibles (modId → versionRange) de `[[dependencies.<modId>]]`.
+fn split_dependencies(deps: Option<toml::Value>) -> (HashMap<String, String>, HashMap<String, String>) {
+    let mut required = HashMap::new();
+    let mut incompatible = HashMap::new();
+    let Some(table) = deps.as_ref().and_then(|d| d.as_table()) else { return (required, incompatible) };
+
+    // Las claves son los modId de los mods del jar, los valores son arrays de deps
+    for dep_array in table.values() {
+        for dep_table in dep_array.as_array().into_iter().flatten().filter_map(|d| d.as_table()) {
+            let Some(dep_mod_id) = dep_table.get("modId").and_then(|v| v.as_str()) else { continue };
+            let version_range = dep_table.get("versionRange")
+                .and_then(|v| v.as_str())
+                .unwrap_or("*")
+                .to_string();
+            match dependency_type(dep_table).as_str() {
+                "required" => { required.insert(dep_mod_id.to_string(), version_range); }
+                "incompatible" => { incompatible.insert(dep_mod_id.to_string(), version_range); }
+                _ => {} // optional / discouraged
+            }
+        }
+    }
+    (required, incompatible)
+}
+
+/// Loader: neoforge.mods.toml es solo de NeoForge; mods.toml es de Forge salvo que dependa de
+/// "neoforge" (NeoForge 1.20.1 aún usaba mods.toml)
+fn loaders_from(neoforge_toml: Option<&str>, forge_toml: Option<&str>) -> Vec<String> {
+    let mods_toml_loader = forge_toml
+        .and_then(|s| toml::from_str::<ForgeModToml>(s).ok())
+        .map(|t| if split_dependencies(t.dependencies).0.contains_key("neoforge") { "NeoForge" } else { "Forge" });
+    let mut loaders = Vec::new();
+    if neoforge_toml.is_some() {
+        loaders.push("NeoForge".to_string());
+    }
+    if let Some(loader) = mods_toml_loader
+        && !loaders.iter().any(|l| l == loader)
+    {
+        loaders.push(loader.to_string());
+    }
+    loaders
+}
+
+/// Loaders que declaran los mods.toml del jar, aunque la metadata principal sea de otro loader.
+pub fn detect_loaders(zip: &mut ZipArchive<File>) -> Vec<String> {
+    let neoforge_toml = read_zip_entry(zip, NEOFORGE_MODS_TOML);
+    let forge_toml = read_zip_entry(zip, FORGE_MODS_TOML);
+    loaders_from(neoforge_toml.as_deref(), forge_toml.as_deref())
+}
+
 // ── Parser ───────────────────────────────────────────────────
 
 pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
-    let toml_str = read_zip_entry(zip, "META-INF/mods.toml")?;
-    let mod_toml: ForgeModToml = toml::from_str(&toml_str).ok()?;
+    let neoforge_toml = read_zip_entry(zip, NEOFORGE_MODS_TOML);
+    let forge_toml = read_zip_entry(zip, FORGE_MODS_TOML);
+    // Los jars multi-loader pueden traer los dos: el de NeoForge es el más reciente
+    let toml_str = neoforge_toml.as_ref().or(forge_toml.as_ref())?;
+    let mod_toml: ForgeModToml = toml::from_str(toml_str).ok()?;
     let entry = mod_toml.mods.first()?;
 
     let key = path.file_name().and_then(|s| s.to_str())
@@ -39,27 +109,9 @@ pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
 
     let name = entry.display_name.clone().unwrap_or_else(|| entry.mod_id.clone());
 
-    // Extraer dependencias de [[dependencies.modId]]
-    let depends = mod_toml.dependencies.and_then(|deps| {
-        let table = deps.as_table()?;
-        // Las claves son los modId del mod principal, los valores son arrays de deps
-        let mut all_deps = std::collections::HashMap::new();
-        for (_mod_id, dep_array) in table {
-            if let Some(arr) = dep_array.as_array() {
-                for dep in arr {
-                    if let Some(dep_table) = dep.as_table() {
-                        let dep_mod_id = dep_table.get("modId")?.as_str()?.to_string();
-                        let version_range = dep_table.get("versionRange")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("*")
-                            .to_string();
-                        all_deps.insert(dep_mod_id, version_range);
-                    }
-                }
-            }
-        }
-        if all_deps.is_empty() { None } else { Some(all_deps) }
-    });
+    let (required, incompatible) = split_dependencies(mod_toml.dependencies);
+
+    let loaders = loaders_from(neoforge_toml.as_deref(), forge_toml.as_deref());
 
     // Limpiar versión (Forge usa ${file.jarVersion} como placeholder)
     let version = entry.version.clone().filter(|v| !v.contains("${"));
@@ -88,7 +140,10 @@ pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
         selected: true,
         file_size_bytes: None,
         file_mtime_secs: None,
-        depends,
+        depends: if required.is_empty() { None } else { Some(required) },
+        breaks: if incompatible.is_empty() { None } else { Some(incompatible) },
+        loaders,
         has_local_icon,
+        ..Default::default()
     })
 }
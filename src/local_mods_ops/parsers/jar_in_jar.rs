@@ -0,0 +1,105 @@
+use std::io::{Cursor, Read, Seek};
+use zip::ZipArchive;
+use crate::local_mods_ops::BundledJar;
+use super::{read_zip_entry, read_zip_entry_bytes};
+
+// ── Jar-in-jar ───────────────────────────────────────────────
+// Fabric y Quilt listan los jars incluidos en `jars`; Forge y NeoForge en META-INF/jarjar/metadata.json.
+
+const JARJAR_METADATA: &str = "META-INF/jarjar/metadata.json";
+/// Fabric API trae sus módulos un nivel más abajo; más allá no suele haber nada útil
+const MAX_DEPTH: usize = 3;
+
+/// Jars incluidos dentro de `zip` (y los de dentro de esos), con el ID y la versión de su metadata.
+pub fn scan_bundled<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Vec<BundledJar> {
+    let mut bundled = Vec::new();
+    scan_into(zip, 0, &mut bundled);
+    bundled
+}
+
+fn scan_into<R: Read + Seek>(zip: &mut ZipArchive<R>, depth: usize, out: &mut Vec<BundledJar>) {
+    if depth >= MAX_DEPTH {
+        return;
+    }
+    for (path, fallback) in nested_paths(zip) {
+        if out.iter().any(|b| b.path == path) {
+            continue;
+        }
+        let mut nested = read_zip_entry_bytes(zip, &path).and_then(|bytes| ZipArchive::new(Cursor::new(bytes)).ok());
+        // Librerías sin metadata de mod: la de jarjar (artifact + versión) es lo único que hay
+        let (mod_id, version) = nested.as_mut().and_then(identity).or(fallback).unzip();
+        out.push(BundledJar { path, mod_id, version: version.flatten() });
+        if let Some(nested) = nested.as_mut() {
+            scan_into(nested, depth + 1, out);
+        }
+    }
+}
+
+/// ID y versión de un jar incluido.
+type Identity = (String, Option<String>);
+
+/// Rutas de los jars incluidos según la metadata del contenedor. Las de jarjar traen además
+/// el artifact y la versión, por si el jar de dentro no tiene metadata propia.
+fn nested_paths<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Vec<(String, Option<Identity>)> {
+    let mut paths = Vec::new();
+
+    if let Some(json) = read_zip_entry(zip, "fabric.mod.json")
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
+    {
+        for jar in value["jars"].as_array().into_iter().flatten() {
+            if let Some(file) = jar["file"].as_str() {
+                paths.push((file.to_string(), None));
+            }
+        }
+    }
+
+    if let Some(json) = read_zip_entry(zip, "quilt.mod.json")
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
+    {
+        for jar in value["quilt_loader"]["jars"].as_array().into_iter().flatten() {
+            if let Some(file) = jar.as_str() {
+                paths.push((file.to_string(), None));
+            }
+        }
+    }
+
+    if let Some(json) = read_zip_entry(zip, JARJAR_METADATA)
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
+    {
+        for jar in value["jars"].as_array().into_iter().flatten() {
+            let Some(path) = jar["path"].as_str() else { continue };
+            let fallback = jar["identifier"]["artifact"].as_str().map(|artifact| {
+                (artifact.to_string(), jar["version"]["artifactVersion"].as_str().map(str::to_string))
+            });
+            paths.push((path.to_string(), fallback));
+        }
+    }
+
+    paths
+}
+
+/// ID y versión de un jar incluido, leyendo su fabric.mod.json, quilt.mod.json o mods.toml.
+fn identity<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Option<Identity> {
+    if let Some(json) = read_zip_entry(zip, "fabric.mod.json")
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
+        && let Some(id) = value["id"].as_str()
+    {
+        return Some((id.to_string(), value["version"].as_str().map(str::to_string)));
+    }
+
+    if let Some(json) = read_zip_entry(zip, "quilt.mod.json")
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(&json)
+        && let Some(id) = value["quilt_loader"]["id"].as_str()
+    {
+        return Some((id.to_string(), value["quilt_loader"]["version"].as_str().map(str::to_string)));
+    }
+
+    let toml_str = read_zip_entry(zip, "META-INF/neoforge.mods.toml")
+        .or_else(|| read_zip_entry(zip, "META-INF/mods.toml"))?;
+    let value: toml::Value = toml::from_str(&toml_str).ok()?;
+    let first = value.get("mods")?.as_array()?.first()?;
+    let id = first.get("modId")?.as_str()?.to_string();
+    // Forge usa ${file.jarVersion} como placeholder
+    let version = first.get("version").and_then(|v| v.as_str()).filter(|v| !v.contains("${")).map(str::to_string);
+    Some((id, version))
+}
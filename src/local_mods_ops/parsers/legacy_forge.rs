@@ -0,0 +1,98 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use zip::ZipArchive;
+use std::fs::File;
+use crate::local_mods_ops::ModInfo;
+use super::read_zip_entry;
+
+// ── Deserialización de mcmod.info (Forge 1.12.2 y anteriores) ─
+
+/// mcmod.info es un array de mods o, en `modListVersion: 2`, un objeto con `modList`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum McModInfo {
+    List(Vec<McModEntry>),
+    V2 {
+        #[serde(rename = "modList")]
+        mod_list: Vec<McModEntry>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct McModEntry {
+    #[serde(alias = "modId")]
+    modid: String,
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default, rename = "requiredMods")]
+    required_mods: Vec<String>,
+    #[serde(rename = "logoFile")]
+    logo_file: Option<String>,
+}
+
+/// "Forge@[10.13.4,)" → ("Forge", "[10.13.4,)"); sin '@' cualquier versión vale.
+fn split_requirement(req: &str) -> Option<(String, String)> {
+    let req = req.trim();
+    if req.is_empty() {
+        return None;
+    }
+    match req.split_once('@') {
+        Some((id, range)) if !range.trim().is_empty() => Some((id.trim().to_string(), range.trim().to_string())),
+        Some((id, _)) => Some((id.trim().to_string(), "*".to_string())),
+        None => Some((req.to_string(), "*".to_string())),
+    }
+}
+
+// ── Parser ───────────────────────────────────────────────────
+
+pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
+    let raw = read_zip_entry(zip, "mcmod.info")?;
+    // Muchos mcmod.info llevan saltos de línea dentro de las descripciones, que no son JSON válido
+    let json_str: String = raw.chars().map(|c| if matches!(c, '\n' | '\r' | '\t') { ' ' } else { c }).collect();
+    let entries = match serde_json::from_str::<McModInfo>(&json_str).ok()? {
+        McModInfo::List(list) => list,
+        McModInfo::V2 { mod_list } => mod_list,
+    };
+    let entry = entries.into_iter().next()?;
+
+    let name = entry.name.clone().filter(|n| !n.trim().is_empty()).unwrap_or_else(|| entry.modid.clone());
+    let key = path.file_name().and_then(|s| s.to_str()).unwrap_or(&name).to_string();
+
+    let depends: HashMap<String, String> = entry.required_mods.iter()
+        .filter_map(|req| split_requirement(req))
+        .collect();
+
+    // Placeholders sin sustituir por el build ("${version}", "@VERSION@")
+    let version = entry.version.clone().filter(|v| !v.contains("${") && !v.contains('@') && !v.trim().is_empty());
+
+    let mut has_local_icon = false;
+    let icon_paths = [
+        entry.logo_file.as_deref().map(|p| p.trim_start_matches('/')),
+        Some("pack.png"),
+        Some("logo.png"),
+    ];
+
+    for path in icon_paths.into_iter().flatten() {
+        if super::extract_and_save_icon(zip, path, &entry.modid) {
+            has_local_icon = true;
+            break;
+        }
+    }
+
+    Some(ModInfo {
+        key,
+        name,
+        detected_project_id: Some(entry.modid),
+        confirmed_project_id: None,
+        version_local: version,
+        version_remote: None,
+        selected: true,
+        file_size_bytes: None,
+        file_mtime_secs: None,
+        depends: if depends.is_empty() { None } else { Some(depends) },
+        loaders: vec!["Forge".to_string()],
+        has_local_icon,
+        ..Default::default()
+    })
+}
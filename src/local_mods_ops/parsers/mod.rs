@@ -1,7 +1,10 @@
 pub mod fabric_quilt;
 pub mod forge_neoforge;
+pub mod jar_in_jar;
+pub mod legacy_forge;
+pub mod quilt;
 
-use std::io::Read;
+use std::io::{Read, Seek};
 use std::path::Path;
 use zip::ZipArchive;
 use std::fs::File;
@@ -10,7 +13,7 @@ use crate::local_mods_ops::ModInfo;
 /// Lee el contenido de una entrada del ZIP por nombre de archivo.
 /// Hace una búsqueda exacta por ruta completa O por nombre de archivo (basename).
 /// Normaliza separadores para compatibilidad Windows/Unix.
-pub fn read_zip_entry(zip: &mut ZipArchive<File>, target: &str) -> Option<String> {
+pub fn read_zip_entry<R: Read + Seek>(zip: &mut ZipArchive<R>, target: &str) -> Option<String> {
     for i in 0..zip.len() {
         if let Ok(mut entry) = zip.by_index(i) {
             let entry_name = entry.name().replace('\\', "/");
@@ -28,7 +31,7 @@ pub fn read_zip_entry(zip: &mut ZipArchive<File>, target: &str) -> Option<String
 }
 
 /// Lee el contenido binario de una entrada del ZIP.
-pub fn read_zip_entry_bytes(zip: &mut ZipArchive<File>, target: &str) -> Option<Vec<u8>> {
+pub fn read_zip_entry_bytes<R: Read + Seek>(zip: &mut ZipArchive<R>, target: &str) -> Option<Vec<u8>> {
     for i in 0..zip.len() {
         if let Ok(mut entry) = zip.by_index(i) {
             let entry_name = entry.name().replace('\\', "/");
@@ -67,8 +70,36 @@ pub fn extract_and_save_icon(zip: &mut ZipArchive<File>, icon_path: &str, projec
 /// Intenta parsear el JAR con todos los parsers disponibles, en orden.
 /// Para añadir un nuevo loader: crear archivo, añadir `pub mod`, y una línea aquí.
 pub fn try_all(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
-    fabric_quilt::try_parse(zip, path)
+    let mut info = fabric_quilt::try_parse(zip, path)
+        .or_else(|| quilt::try_parse(zip, path))
         .or_else(|| forge_neoforge::try_parse(zip, path))
+        .or_else(|| legacy_forge::try_parse(zip, path))?;
     // Futuro:
     // .or_else(|| liteloader::try_parse(zip, path))
+
+    // Jars multi-loader: el parser que gana solo ve su metadata, añadir los loaders del resto
+    for loader in detect_loaders(zip) {
+        if !info.loaders.contains(&loader) {
+            info.loaders.push(loader);
+        }
+    }
+    Some(info)
+}
+
+/// Loaders para los que trae metadata el jar, en el mismo orden que `try_all`.
+fn detect_loaders(zip: &mut ZipArchive<File>) -> Vec<String> {
+    let mut loaders = Vec::new();
+    if read_zip_entry(zip, "fabric.mod.json").is_some() {
+        loaders.push("Fabric".to_string());
+    }
+    if read_zip_entry(zip, "quilt.mod.json").is_some() {
+        loaders.push("Quilt".to_string());
+    }
+    let toml_loaders = forge_neoforge::detect_loaders(zip);
+    // mcmod.info suelto solo cuenta si no hay mods.toml (los jars modernos lo arrastran a veces)
+    if toml_loaders.is_empty() && read_zip_entry(zip, "mcmod.info").is_some() {
+        loaders.push("Forge".to_string());
+    }
+    loaders.extend(toml_loaders);
+    loaders
 }
@@ -0,0 +1,152 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use zip::ZipArchive;
+use std::fs::File;
+use crate::local_mods_ops::ModInfo;
+use super::read_zip_entry;
+
+// ── Deserialización de quilt.mod.json ────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct QuiltModJson {
+    pub quilt_loader: QuiltLoader,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuiltLoader {
+    pub id: String,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub metadata: QuiltMetadata,
+    #[serde(default)]
+    pub depends: Vec<QuiltDependency>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct QuiltMetadata {
+    pub name: Option<String>,
+    pub icon: Option<serde_json::Value>,
+}
+
+/// Una dependencia es un ID suelto ("quilt_loader"), un objeto con `versions` o un array de
+/// ellas, que se cumple con cualquiera.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum QuiltDependency {
+    Id(String),
+    Object {
+        id: String,
+        #[serde(default)]
+        versions: Option<serde_json::Value>,
+        #[serde(default)]
+        optional: bool,
+    },
+    Group(Vec<QuiltDependency>),
+}
+
+impl QuiltDependency {
+    /// (ID, rango) de una dependencia obligatoria. Un grupo del mismo mod se une con " || "; si mezcla
+    /// mods distintos (o alguno es opcional) no se puede expresar como un rango y se ignora.
+    fn required(self) -> Option<(String, String)> {
+        match self {
+            QuiltDependency::Id(id) => Some((id, "*".to_string())),
+            QuiltDependency::Object { optional: true, .. } => None,
+            QuiltDependency::Object { id, versions, .. } => {
+                let range = versions.as_ref().and_then(versions_to_range).unwrap_or_else(|| "*".to_string());
+                Some((id, range))
+            }
+            QuiltDependency::Group(alternatives) => {
+                let alternatives: Vec<(String, String)> = alternatives.into_iter()
+                    .map(QuiltDependency::required)
+                    .collect::<Option<_>>()?;
+                let (id, _) = alternatives.first()?;
+                if alternatives.iter().any(|(other, _)| other != id) {
+                    return None;
+                }
+                let id = id.clone();
+                if alternatives.iter().any(|(_, range)| range == "*") {
+                    return Some((id, "*".to_string()));
+                }
+                let ranges: Vec<String> = alternatives.into_iter().map(|(_, range)| range).collect();
+                Some((id, ranges.join(" || ")))
+            }
+        }
+    }
+}
+
+/// `versions` de Quilt → rango en la sintaxis de fabric.mod.json: una cadena tal cual, un array o
+/// `{"any": [...]}` como alternativas (" || ") y `{"all": [...]}` como condiciones a la vez (" ").
+fn versions_to_range(versions: &serde_json::Value) -> Option<String> {
+    let join = |arr: &[serde_json::Value], sep: &str| {
+        let parts: Vec<String> = arr.iter().filter_map(versions_to_range).collect();
+        if parts.is_empty() { None } else { Some(parts.join(sep)) }
+    };
+    match versions {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(arr) => join(arr, " || "),
+        serde_json::Value::Object(obj) => {
+            if let Some(serde_json::Value::Array(any)) = obj.get("any") {
+                join(any, " || ")
+            } else if let Some(serde_json::Value::Array(all)) = obj.get("all") {
+                join(all, " ")
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// ── Parser ───────────────────────────────────────────────────
+
+pub fn try_parse(zip: &mut ZipArchive<File>, path: &Path) -> Option<ModInfo> {
+    let json_str = read_zip_entry(zip, "quilt.mod.json")?;
+    let mod_json: QuiltModJson = serde_json::from_str(&json_str).ok()?;
+    let loader = mod_json.quilt_loader;
+
+    let name = loader.metadata.name.clone().unwrap_or_else(|| loader.id.clone());
+    let key = path.file_name().and_then(|s| s.to_str()).unwrap_or(&name).to_string();
+
+    // Solo las obligatorias, igual que `depends` de fabric.mod.json
+    let depends: HashMap<String, String> = loader.depends.into_iter()
+        .filter_map(QuiltDependency::required)
+        .collect();
+
+    let mut has_local_icon = false;
+    let mut possible_icons = vec!["pack.png".to_string(), "assets/icon.png".to_string()];
+    if let Some(icon_val) = &loader.metadata.icon {
+        if let Some(s) = icon_val.as_str() {
+            possible_icons.insert(0, s.to_string());
+        } else if let Some(obj) = icon_val.as_object() {
+            for (_, path) in obj {
+                if let Some(s) = path.as_str() {
+                    possible_icons.insert(0, s.to_string());
+                }
+            }
+        }
+    }
+
+    for path in possible_icons {
+        if super::extract_and_save_icon(zip, &path, &loader.id) {
+            has_local_icon = true;
+            break;
+        }
+    }
+
+    Some(ModInfo {
+        key,
+        name,
+        detected_project_id: Some(loader.id),
+        confirmed_project_id: None,
+        version_local: loader.version,
+        version_remote: None,
+        selected: true,
+        file_size_bytes: None,
+        file_mtime_secs: None,
+        depends: if depends.is_empty() { None } else { Some(depends) },
+        loaders: vec!["Quilt".to_string()],
+        has_local_icon,
+        ..Default::default()
+    })
+}
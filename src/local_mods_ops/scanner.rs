@@ -1,6 +1,7 @@
 use indexmap::IndexMap;
 use std::fs::{self, File};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
 use zip::ZipArchive;
 use std::time::SystemTime;
 use crate::paths_vars::PATHS;
@@ -15,62 +16,82 @@ pub fn get_file_mtime(metadata: &fs::Metadata) -> u64 {
         .as_secs()
 }
 
+/// Escanea los jars de una carpeta en cuatro pasos: stat de todos, caché de todos en una sola
+/// lectura, parseo en paralelo (rayon) solo de los que cambiaron y una sola escritura en la caché.
 pub fn read_mods_in_folder(mods_folder: &str) -> IndexMap<String, ModInfo> {
-    let mut mods_map: IndexMap<String, ModInfo> = IndexMap::new();
-
-    if let Ok(entries) = fs::read_dir(mods_folder) {
-        let mut entries_vec: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-        entries_vec.sort_by_key(|e| e.file_name());
+    // 1. Stat de todos los jars, en orden de nombre
+    let mut jars: Vec<(String, PathBuf, u64, u64)> = fs::read_dir(mods_folder)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("jar"))
+        .map(|path| {
+            let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let (file_size, file_mtime) = fs::metadata(&path)
+                .map(|meta| (meta.len(), get_file_mtime(&meta)))
+                .unwrap_or((0, 0));
+            (filename, path, file_size, file_mtime)
+        })
+        .collect();
+    jars.sort_by(|a, b| a.0.cmp(&b.0));
 
-        for entry in entries_vec {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("jar") {
-                let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                
-                let (file_size, file_mtime) = if let Ok(meta) = fs::metadata(&path) {
-                    (meta.len(), get_file_mtime(&meta))
-                } else {
-                    (0, 0)
-                };
+    // 2. Filas de caché de todos en una transacción
+    let cached = super::cache::get_mods(jars.iter().map(|(filename, ..)| filename.as_str()));
 
-                let cached = crate::local_mods_ops::cache::get_mod(&filename);
-                let mut use_cache = false;
-                if let Some(ref c) = cached {
-                    if c.file_size_bytes == Some(file_size) && c.file_mtime_secs == Some(file_mtime) {
-                        mods_map.insert(c.key.clone(), c.clone());
-                        use_cache = true;
-                    }
+    // 3. Solo se parsean los que no están o cambiaron, en paralelo: (archivo, info, hay que guardarlo)
+    let results: Vec<(String, ModInfo, bool)> = jars.par_iter()
+        .filter_map(|(filename, path, file_size, file_mtime)| {
+            let old = cached.get(filename);
+            // Entradas sin loaders o sin jars incluidos inspeccionados son de antes: se vuelven a parsear
+            if let Some(c) = old
+                && c.file_size_bytes == Some(*file_size) && c.file_mtime_secs == Some(*file_mtime)
+                && !c.loaders.is_empty() && c.bundled.is_some()
+            {
+                let mut c = c.clone();
+                // Entradas de caché anteriores al hashing: calcular y guardar una sola vez
+                let missing_hashes = c.hashes.is_none();
+                if missing_hashes {
+                    c.hashes = super::hashing::hash_file(path).ok();
                 }
+                return Some((filename.clone(), c, missing_hashes));
+            }
 
-                if !use_cache {
-                    if let Ok(mut mod_info) = read_single_mod(&path) {
-                        mod_info.file_size_bytes = Some(file_size);
-                        mod_info.file_mtime_secs = Some(file_mtime);
-                        
-                        if let Some(old) = &cached {
-                            if old.key == mod_info.key {
-                                mod_info.confirmed_project_id = old.confirmed_project_id.clone();
-                                mod_info.version_remote = old.version_remote.clone();
-                                mod_info.selected = old.selected;
-                            }
-                        }
+            let mut mod_info = read_single_mod(path).ok()?;
+            mod_info.file_size_bytes = Some(*file_size);
+            mod_info.file_mtime_secs = Some(*file_mtime);
+            if let Some(old) = old
+                && old.key == mod_info.key
+            {
+                mod_info.confirmed_project_id = old.confirmed_project_id.clone();
+                mod_info.version_remote = old.version_remote.clone();
+                mod_info.selected = old.selected;
+            }
+            Some((filename.clone(), mod_info, true))
+        })
+        .collect();
 
-                        mods_map.insert(mod_info.key.clone(), mod_info.clone());
-                        crate::local_mods_ops::cache::upsert_mod(&filename, &mod_info); 
-                    } 
-                }
-            }        
-        }
-    } 
-    return mods_map;
+    // 4. Todas las altas/cambios en una sola transacción de escritura
+    super::cache::upsert_mods(results.iter()
+        .filter(|(_, _, changed)| *changed)
+        .map(|(filename, info, _)| (filename.as_str(), info)));
+
+    results.into_iter().map(|(_, info, _)| (info.key.clone(), info)).collect()
 }
 
 pub fn read_single_mod(path: &Path) -> Result<ModInfo, String> {
     let file = File::open(path).map_err(|_| "No se pudo abrir archivo".to_string())?;
     let mut zip = ZipArchive::new(file).map_err(|_| "No es un ZIP válido".to_string())?;
 
-    parsers::try_all(&mut zip, path)
-        .ok_or_else(|| "No se encontró metadata del mod (ni fabric.mod.json ni META-INF/mods.toml)".to_string())
+    let mut mod_info = parsers::try_all(&mut zip, path)
+        .ok_or_else(|| "No se encontró metadata del mod (fabric.mod.json, quilt.mod.json, mods.toml, neoforge.mods.toml ni mcmod.info)".to_string())?;
+
+    // Librerías incluidas (jar-in-jar): cuentan como instaladas al validar y al resolver dependencias
+    mod_info.bundled = Some(parsers::jar_in_jar::scan_bundled(&mut zip));
+
+    // Hash para identificar el mod de forma exacta en Modrinth/CurseForge
+    mod_info.hashes = super::hashing::hash_file(path).ok();
+    Ok(mod_info)
 }
 
 pub fn get_minecraft_versions(manifest_path: &str) -> Vec<String> {
@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use crate::common::version_range::{compare_versions, VersionRange};
+use super::models::ModInfo;
+
+// Validación offline de un modpack: solo usa la metadata ya leída de los jars (`depends`), sin red.
+
+/// IDs que aporta el propio juego/loader: no son jars del modpack.
+/// `fml` y `mod_minecraftforge` son los nombres de Forge en mods antiguos (mcmod.info).
+const PLATFORM_IDS: &[&str] = &["minecraft", "java", "fabricloader", "fabric-loader", "quilt_loader", "forge", "fml", "mod_minecraftforge", "neoforge"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencyIssue {
+    /// `mod_key` necesita `dependency` y no hay ningún jar que lo aporte.
+    Missing { mod_key: String, dependency: String, range: String },
+    /// La dependencia está, pero su versión (`found`) no cumple `range`.
+    VersionMismatch { mod_key: String, dependency: String, range: String, found: String },
+    /// Varios jars declaran el mismo ID de mod.
+    Duplicate { mod_id: String, keys: Vec<String> },
+    /// Varios jars incluyen (jar-in-jar) el mismo mod con versiones distintas: (jar contenedor, versión).
+    ConflictingBundles { mod_id: String, copies: Vec<(String, String)> },
+}
+
+impl DependencyIssue {
+    /// Texto para el panel del explorador.
+    pub fn describe(&self) -> String {
+        match self {
+            DependencyIssue::Missing { mod_key, dependency, range } => {
+                format!("{} necesita {} {} y no está en el modpack", mod_key, dependency, range)
+            }
+            DependencyIssue::VersionMismatch { mod_key, dependency, range, found } => {
+                format!("{} necesita {} {} (hay {})", mod_key, dependency, range, found)
+            }
+            DependencyIssue::Duplicate { mod_id, keys } => {
+                format!("'{}' está repetido: {}", mod_id, keys.join(", "))
+            }
+            DependencyIssue::ConflictingBundles { mod_id, copies } => {
+                let copies: Vec<String> = copies.iter().map(|(key, version)| format!("{} ({})", key, version)).collect();
+                format!("'{}' va incluido con versiones distintas: {}", mod_id, copies.join(", "))
+            }
+        }
+    }
+}
+
+/// Contexto contra el que se validan las entradas de plataforma (`minecraft`, `java`, loader).
+#[derive(Debug, Clone)]
+pub struct ValidationTarget {
+    pub mc_version: String,
+    pub loader: String,
+    /// Versión del loader si se conoce; sin ella no se comprueban `fabricloader`/`forge`/...
+    pub loader_version: Option<String>,
+}
+
+/// Un mod presente en el modpack: un jar suelto o uno incluido dentro de otro.
+struct Provided<'a> {
+    key: &'a str, // Jar del modpack (el contenedor si va incluido)
+    version: Option<&'a str>,
+    bundled: bool,
+}
+
+/// Valida las dependencias obligatorias de los jars de un modpack: faltantes, rangos que no se
+/// cumplen (incluidos minecraft/java/loader), IDs duplicados y librerías incluidas en versiones
+/// distintas. Los jars incluidos (jar-in-jar) cuentan como instalados. Los rangos que no se
+/// entienden no se reportan, para no dar falsos positivos.
+pub fn validate_modpack<'a>(mods: impl IntoIterator<Item = &'a ModInfo>, target: &ValidationTarget) -> Vec<DependencyIssue> {
+    let mods: Vec<&ModInfo> = mods.into_iter().collect();
+    let mut issues = Vec::new();
+
+    // ID de mod (en minúsculas) → jars que lo aportan, sueltos o incluidos
+    let mut by_id: BTreeMap<String, Vec<Provided>> = BTreeMap::new();
+    for m in &mods {
+        if let Some(id) = &m.detected_project_id {
+            by_id.entry(id.to_lowercase()).or_default().push(Provided {
+                key: &m.key, version: m.version_local.as_deref(), bundled: false,
+            });
+        }
+        for jar in m.bundled.iter().flatten() {
+            if let Some(id) = &jar.mod_id {
+                by_id.entry(id.to_lowercase()).or_default().push(Provided {
+                    key: &m.key, version: jar.version.as_deref(), bundled: true,
+                });
+            }
+        }
+    }
+    for (id, provided) in &by_id {
+        let mut keys: Vec<String> = provided.iter().filter(|p| !p.bundled).map(|p| p.key.to_string()).collect();
+        let loose = keys.len();
+        if loose > 1 {
+            keys.sort();
+            issues.push(DependencyIssue::Duplicate { mod_id: id.clone(), keys });
+        }
+        // Una copia suelta manda sobre las incluidas; si no la hay, el loader elige entre ellas
+        if loose == 0 {
+            let mut copies: Vec<(String, String)> = provided.iter()
+                .filter_map(|p| p.version.map(|v| (p.key.to_string(), v.to_string())))
+                .collect();
+            copies.sort();
+            copies.dedup();
+            if copies.iter().any(|(_, v)| compare_versions(v, &copies[0].1) != Ordering::Equal) {
+                issues.push(DependencyIssue::ConflictingBundles { mod_id: id.clone(), copies });
+            }
+        }
+    }
+
+    let java = java_for_minecraft(&target.mc_version).to_string();
+    let platform: HashMap<&str, Option<&str>> = HashMap::from([
+        ("minecraft", Some(target.mc_version.as_str())),
+        ("java", Some(java.as_str())),
+        (loader_dependency_id(&target.loader), target.loader_version.as_deref()),
+    ]);
+
+    let mut sorted = mods.clone();
+    sorted.sort_by(|a, b| a.key.cmp(&b.key));
+    for m in sorted {
+        let Some(depends) = &m.depends else { continue };
+        // Forge/NeoForge declaran rangos Maven: "47.1.0" suelto es una recomendación, no "=47.1.0"
+        let maven = !m.loaders.is_empty() && m.loaders.iter().all(|l| matches!(l.to_lowercase().as_str(), "forge" | "neoforge"));
+        let mut deps: Vec<(&String, &String)> = depends.iter().collect();
+        deps.sort();
+        for (dep, range) in deps {
+            let dep_id = dep.to_lowercase();
+            let found: Option<String> = if PLATFORM_IDS.contains(&dep_id.as_str()) {
+                // Loaders que no son el elegido ya los avisa la comprobación de loaders
+                match platform.get(normalize_platform_id(&dep_id)) {
+                    Some(Some(v)) => Some(v.to_string()),
+                    _ => continue,
+                }
+            } else if let Some((provided, exact)) = provider(&by_id, &dep_id) {
+                // La copia suelta es la que se carga; si solo va incluida, la primera con versión
+                let version = provided.iter().find(|p| !p.bundled && p.version.is_some())
+                    .or_else(|| provided.iter().find(|p| p.version.is_some()))
+                    .and_then(|p| p.version);
+                match version {
+                    Some(v) if exact => Some(v.to_string()),
+                    _ => continue, // Sin versión local (o la aporta otro mod) no hay nada que comparar
+                }
+            } else {
+                None
+            };
+
+            match found {
+                None => issues.push(DependencyIssue::Missing {
+                    mod_key: m.key.clone(), dependency: dep.clone(), range: range.clone(),
+                }),
+                Some(v) => {
+                    let parsed = if maven { VersionRange::parse_maven(range) } else { VersionRange::parse_fabric(range) };
+                    if parsed.is_some_and(|r| !r.matches(&v)) {
+                        issues.push(DependencyIssue::VersionMismatch {
+                            mod_key: m.key.clone(), dependency: dep.clone(), range: range.clone(), found: v,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Jars que aportan `dep_id` y si la versión de esos jars es la de la dependencia. Fabric API se
+/// llamaba "fabric" y trae dentro sus módulos ("fabric-networking-api-v1"...), que los mods suelen
+/// pedir sueltos con la versión del módulo, no la de Fabric API.
+fn provider<'m, 'a>(by_id: &'m BTreeMap<String, Vec<Provided<'a>>>, dep_id: &str) -> Option<(&'m Vec<Provided<'a>>, bool)> {
+    if let Some(jars) = by_id.get(dep_id) {
+        return Some((jars, true));
+    }
+    let fabric_api = by_id.get("fabric-api").or_else(|| by_id.get("fabric"));
+    match dep_id {
+        "fabric" | "fabric-api" => fabric_api.or_else(|| by_id.get("quilted_fabric_api")).map(|j| (j, true)),
+        _ if dep_id.starts_with("fabric-") => fabric_api.map(|j| (j, false)),
+        _ => None,
+    }
+}
+
+fn normalize_platform_id(dep_id: &str) -> &str {
+    match dep_id {
+        "fabric-loader" => "fabricloader",
+        "fml" | "mod_minecraftforge" => "forge",
+        _ => dep_id,
+    }
+}
+
+/// ID con el que los mods declaran la dependencia del loader elegido.
+fn loader_dependency_id(loader: &str) -> &'static str {
+    match loader.to_lowercase().as_str() {
+        "fabric" => "fabricloader",
+        "quilt" => "quilt_loader",
+        "neoforge" => "neoforge",
+        "forge" => "forge",
+        _ => "",
+    }
+}
+
+/// Java mínimo que usa cada versión de Minecraft (según el manifiesto de Mojang).
+pub fn java_for_minecraft(mc_version: &str) -> u32 {
+    let minor = |v: &str| -> Option<(u32, u32)> {
+        let mut parts = v.split('.').skip(1).map(|p| p.parse::<u32>().ok());
+        Some((parts.next()??, parts.next().flatten().unwrap_or(0)))
+    };
+    match minor(mc_version) {
+        Some((m, p)) if m > 20 || (m == 20 && p >= 5) => 21,
+        Some((m, _)) if m >= 18 => 17,
+        Some((17, _)) => 16,
+        _ => 8,
+    }
+}
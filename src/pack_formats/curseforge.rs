@@ -0,0 +1,324 @@
+//! Modpacks de CurseForge: un zip con `manifest.json` (pares `projectID`/`fileID` que se resuelven
+//! contra la API) y la carpeta `overrides/` que se copia tal cual.
+//! Algunos autores desactivan la distribución por terceros: esos archivos no tienen `downloadUrl`
+//! y se devuelven como descargas manuales en lugar de descartarlos.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{export_scratch_dir, extract_overrides, unique_pack_name, write_pack_zip, ExportOptions, ExportReport, ImportedPack, ManualDownload, OVERRIDES_DIR};
+use crate::fetch::async_download::best_known_project_id;
+use crate::fetch::curseforge_api;
+use crate::fetch::fetch_from_api::{self, ModDownloadInfo};
+use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::hashing;
+use crate::local_mods_ops::{ModInfo, PinnedFile};
+use crate::profiles::{Profile, ProfilesDatabase};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Prefijo del `id` de `modLoaders` ("forge-47.2.0") → nombre del loader en la app.
+pub const LOADER_PREFIXES: [(&str, &str); 4] = [
+    ("forge", "Forge"),
+    ("neoforge", "NeoForge"),
+    ("fabric", "Fabric"),
+    ("quilt", "Quilt"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CfManifest {
+    pub minecraft: CfMinecraft,
+    pub manifest_type: String,
+    pub manifest_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    pub files: Vec<CfManifestFile>,
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+fn default_overrides() -> String {
+    OVERRIDES_DIR.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CfMinecraft {
+    pub version: String,
+    #[serde(default)]
+    pub mod_loaders: Vec<CfModLoader>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CfModLoader {
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CfManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: u32,
+    #[serde(rename = "fileID")]
+    pub file_id: u32,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl CfManifest {
+    /// Loader de la app y su versión según el `modLoaders` principal.
+    pub fn loader(&self) -> Option<(String, String)> {
+        let entry = self.minecraft.mod_loaders.iter().find(|l| l.primary)
+            .or_else(|| self.minecraft.mod_loaders.first())?;
+        let (prefix, version) = entry.id.split_once('-')?;
+        LOADER_PREFIXES.iter()
+            .find(|(p, _)| p.eq_ignore_ascii_case(prefix))
+            .map(|(_, loader)| (loader.to_string(), version.to_string()))
+    }
+}
+
+/// Prefijo de `modLoaders` para un loader de la app ("NeoForge" → "neoforge").
+pub fn loader_prefix(loader: &str) -> Option<&'static str> {
+    LOADER_PREFIXES.iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(loader))
+        .map(|(prefix, _)| *prefix)
+}
+
+/// Página del proyecto en CurseForge, para las descargas manuales.
+pub fn project_page(project_id: u32) -> String {
+    format!("https://www.curseforge.com/projects/{}", project_id)
+}
+
+/// Lee solo el `manifest.json` de un zip de CurseForge.
+pub fn read_manifest(path: &Path) -> Result<CfManifest, String> {
+    let file = fs::File::open(path).map_err(|e| format!("No se pudo abrir '{}': {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("'{}' no es un zip válido: {}", path.display(), e))?;
+    let entry = archive.by_name(MANIFEST_FILE).map_err(|_| format!("'{}' no contiene {}", path.display(), MANIFEST_FILE))?;
+    serde_json::from_reader(entry).map_err(|e| format!("{} inválido: {}", MANIFEST_FILE, e))
+}
+
+/// ¿Es un zip de modpack de CurseForge? (tiene `manifest.json` en la raíz)
+pub fn is_curseforge_pack(path: &Path) -> bool {
+    fs::File::open(path).ok()
+        .and_then(|f| zip::ZipArchive::new(f).ok())
+        .is_some_and(|mut archive| archive.by_name(MANIFEST_FILE).is_ok())
+}
+
+/// Importa un zip de CurseForge: crea `modpacks/<nombre>`, extrae los overrides y resuelve cada
+/// `projectID`/`fileID` contra la API. Los archivos sin `downloadUrl` quedan en el perfil (sin fijar)
+/// y en `manual_downloads`. El perfil no se guarda: eso lo decide quien llama.
+pub fn import_curseforge(path: &Path, db: &ProfilesDatabase, modpacks_root: &Path) -> Result<ImportedPack, String> {
+    let manifest = read_manifest(path)?;
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(format!("Tipo de manifest no soportado: '{}'", manifest.manifest_type));
+    }
+    let cf_key = crate::fetch::cf_api_key();
+    if cf_key.is_empty() {
+        return Err("Hace falta una API key de CurseForge (CURSEFORGE_API_KEY) para importar este pack".to_string());
+    }
+
+    let name = unique_pack_name(db, modpacks_root, &manifest.name);
+    let folder = modpacks_root.join(&name);
+    fs::create_dir_all(&folder).map_err(|e| format!("No se pudo crear '{}': {}", folder.display(), e))?;
+
+    let mut profile = Profile::new(name, None);
+    profile.game_version = Some(manifest.minecraft.version.clone());
+    if let Some((loader, loader_version)) = manifest.loader() {
+        profile.loader = Some(loader);
+        profile.loader_version = Some(loader_version);
+    }
+
+    let mut downloads = Vec::new();
+    let mut manual_downloads = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Todos los archivos del manifest en una sola petición batch
+    let file_ids: Vec<u32> = manifest.files.iter().filter(|e| e.required).map(|e| e.file_id).collect();
+    let cf_files = curseforge_api::fetch_curseforge_files(&file_ids, &cf_key);
+
+    for entry in &manifest.files {
+        if !entry.required {
+            warnings.push(format!("Archivo opcional no incluido: {}/{}", entry.project_id, entry.file_id));
+            continue;
+        }
+        let Some(cf) = cf_files.get(&entry.file_id) else {
+            manual_downloads.push(ManualDownload {
+                name: format!("{}/{}", entry.project_id, entry.file_id),
+                url: project_page(entry.project_id),
+                folder: folder.clone(),
+            });
+            continue;
+        };
+        let Some(filename) = Path::new(&cf.file_name).file_name().map(|f| f.to_string_lossy().to_string()) else {
+            warnings.push(format!("Nombre de archivo inválido: {}", cf.file_name));
+            continue;
+        };
+        // El manifest no dice dónde va cada archivo: los jars son mods, el resto se deja en overrides/ para revisarlo
+        let is_mod = filename.to_lowercase().ends_with(".jar");
+        let dest_folder = if is_mod { folder.clone() } else { folder.join(OVERRIDES_DIR) };
+
+        let mut mi = ModInfo {
+            key: filename.clone(),
+            name: filename.trim_end_matches(".jar").to_string(),
+            detected_project_id: Some(entry.project_id.to_string()),
+            confirmed_project_id: Some(entry.project_id.to_string()),
+            selected: true,
+            ..Default::default()
+        };
+
+        match cf.download_url.clone() {
+            Some(url) => {
+                mi.pinned = Some(PinnedFile {
+                    url,
+                    filename: filename.clone(),
+                    version_id: Some(entry.file_id.to_string()),
+                    expected: cf.expected(),
+                });
+                if let Some(info) = ModDownloadInfo::from_pinned(&mi) {
+                    downloads.push((dest_folder, info));
+                }
+            }
+            None => manual_downloads.push(ManualDownload {
+                name: filename.clone(),
+                url: project_page(entry.project_id),
+                folder: dest_folder,
+            }),
+        }
+        if is_mod {
+            profile.mods.insert(filename, mi);
+        }
+    }
+
+    let file = fs::File::open(path).map_err(|e| format!("No se pudo abrir '{}': {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Zip corrupto: {}", e))?;
+    let prefix = format!("{}/", manifest.overrides.trim_matches('/'));
+    let overrides_extracted = extract_overrides(&mut archive, &prefix, &folder, &mut warnings)?;
+
+    println!(
+        "📦 Importado '{}': {} archivos a descargar, {} manuales, {} overrides",
+        profile.name, downloads.len(), manual_downloads.len(), overrides_extracted
+    );
+    Ok(ImportedPack { profile, folder, downloads, manual_downloads, overrides_extracted, warnings })
+}
+
+// ── Exportación ──────────────────────────────────────────────
+
+/// Archivo de CurseForge fijado en el perfil (pack importado de CurseForge): proyecto + archivo.
+fn pinned_cf_file(mi: &ModInfo) -> Option<CfManifestFile> {
+    let pin = mi.pinned.as_ref()?;
+    let project_id = mi.confirmed_project_id.as_deref()?.parse().ok()?;
+    let file_id = pin.version_id.as_deref()?.parse().ok()?;
+    Some(CfManifestFile { project_id, file_id, required: true })
+}
+
+/// Exporta un perfil como zip de CurseForge (`manifest.json` + `overrides/`) en `output`.
+/// Cada mod se resuelve, por orden: archivo fijado de CurseForge (si el perfil es de esta versión de MC),
+/// jar local identificado por fingerprint, y el archivo compatible más reciente del proyecto en CurseForge.
+/// Lo que solo está en Modrinth o solo en disco se incluye en `overrides/mods`.
+pub fn export_curseforge(profile: &Profile, opts: &ExportOptions, output: &Path) -> Result<ExportReport, String> {
+    let prefix = loader_prefix(&opts.loader)
+        .ok_or_else(|| format!("Loader no soportado por CurseForge: '{}'", opts.loader))?;
+    if opts.loader_version.trim().is_empty() {
+        return Err(format!("Falta la versión de {} para modLoaders", opts.loader));
+    }
+    let cf_key = crate::fetch::cf_api_key();
+    if cf_key.is_empty() {
+        return Err("Hace falta una API key de CurseForge (CURSEFORGE_API_KEY) para exportar a CurseForge".to_string());
+    }
+
+    let pins_valid = profile.game_version.as_deref() == Some(opts.game_version.as_str());
+    let scratch = export_scratch_dir("tetanos_cf_export");
+
+    let mut report = ExportReport::default();
+    let mut files: Vec<(String, CfManifestFile)> = Vec::new(); // nombre para el informe + entrada
+    let mut bundled: Vec<(String, PathBuf)> = Vec::new();
+    let mut locals: Vec<(String, PathBuf, u32)> = Vec::new(); // filename, ruta, fingerprint
+
+    for (key, mi) in &profile.mods {
+        if pins_valid && let Some(file) = pinned_cf_file(mi) {
+            files.push((key.clone(), file));
+            continue;
+        }
+
+        if let Some(path) = opts.local_folder.as_ref().map(|f| f.join(key)).filter(|p| p.is_file()) {
+            match hashing::hash_file(&path) {
+                Ok(h) => locals.push((key.clone(), path, h.cf_fingerprint)),
+                Err(_) => bundled.push((key.clone(), path)),
+            }
+            continue;
+        }
+
+        // Un ID numérico es de CurseForge; si no, se busca el proyecto por nombre
+        let project_id = best_known_project_id(mi);
+        let cf_id = project_id.as_deref().and_then(|id| id.parse::<u32>().ok())
+            .or_else(|| curseforge_api::fetch_curseforge_project_id(&mi.name, &cf_key));
+        let cf_file = cf_id.and_then(|id| {
+            curseforge_api::fetch_curseforge_version_file(id, &opts.game_version, &opts.loader, &cf_key, &ContentType::Mod)
+                .map(|f| CfManifestFile { project_id: id, file_id: f.id, required: true })
+        });
+        if let Some(file) = cf_file {
+            files.push((mi.name.clone(), file));
+            continue;
+        }
+
+        // Solo en Modrinth: se descarga (verificado) para incluirlo en el zip
+        let Some(info) = fetch_from_api::find_mod_download(&mi.name, project_id.as_deref(), &opts.game_version, &opts.loader, &cf_key, &ContentType::Mod) else {
+            report.missing.push(mi.name.clone());
+            continue;
+        };
+        match fetch_from_api::download_mod_file(&info.url, &scratch.to_string_lossy(), &info.filename, &info.expected, |_| {}) {
+            Ok(()) => bundled.push((info.filename.clone(), scratch.join(&info.filename))),
+            Err(e) => {
+                println!("❌ No se pudo descargar '{}' para el pack: {}", info.filename, e);
+                report.missing.push(mi.name.clone());
+            }
+        }
+    }
+
+    // Jars locales: una sola petición de fingerprints
+    let fingerprints: Vec<u32> = locals.iter().map(|(_, _, fp)| *fp).collect();
+    let matches = if fingerprints.is_empty() { Default::default() } else { curseforge_api::fetch_fingerprint_matches(&fingerprints, &cf_key) };
+    for (filename, path, fingerprint) in locals {
+        match matches.get(&fingerprint) {
+            Some(m) => files.push((filename, CfManifestFile { project_id: m.id, file_id: m.file.id, required: true })),
+            None => bundled.push((filename, path)),
+        }
+    }
+
+    report.indexed = files.iter().map(|(name, _)| name.clone()).collect();
+    report.bundled = bundled.iter().map(|(name, _)| name.clone()).collect();
+    let manifest = CfManifest {
+        minecraft: CfMinecraft {
+            version: opts.game_version.clone(),
+            mod_loaders: vec![CfModLoader { id: format!("{}-{}", prefix, opts.loader_version.trim()), primary: true }],
+        },
+        manifest_type: "minecraftModpack".to_string(),
+        manifest_version: 1,
+        name: profile.name.clone(),
+        version: opts.version_id.clone(),
+        author: String::new(),
+        files: files.into_iter().map(|(_, f)| f).collect(),
+        overrides: OVERRIDES_DIR.to_string(),
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+
+    let result = write_pack_zip(output, MANIFEST_FILE, &json, &bundled);
+    if scratch.exists() {
+        // Carpeta temporal creada por esta exportación
+        let _ = fs::remove_dir_all(&scratch);
+    }
+    result?;
+
+    println!("📦 Exportado '{}': {} en el manifest, {} incluidos, {} sin resolver", profile.name, report.indexed.len(), report.bundled.len(), report.missing.len());
+    Ok(report)
+}
@@ -0,0 +1,258 @@
+//! Importación y exportación de modpacks en formatos de otros launchers.
+//!
+//! Todo lo que se escribe queda dentro de `modpacks/<nombre>`: los jars en la raíz (la carpeta que
+//! se enlaza como `mods`) y el resto de overrides (config, resourcepacks...) en
+//! `modpacks/<nombre>/overrides/`, para revisarlos a mano. Nunca se tocan config/ ni saves/ del juego.
+
+pub mod curseforge;
+pub mod mrpack;
+pub mod packwiz;
+
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::fetch::async_download::DownloadJob;
+use crate::fetch::fetch_from_api::ModDownloadInfo;
+use crate::fetch::search_provider::ContentType;
+use crate::profiles::{Profile, ProfilesDatabase};
+
+/// Subcarpeta del modpack donde se guardan los overrides que no son mods.
+pub const OVERRIDES_DIR: &str = "overrides";
+
+/// Resultado de importar un pack: el perfil a guardar y lo que queda por descargar.
+#[derive(Debug, Clone)]
+pub struct ImportedPack {
+    pub profile: Profile,
+    /// Carpeta `modpacks/<nombre>` creada para el pack
+    pub folder: PathBuf,
+    /// Archivos a descargar: carpeta de destino + info ya resuelta (URL y hashes del pack)
+    pub downloads: Vec<(PathBuf, ModDownloadInfo)>,
+    /// Archivos que hay que descargar a mano (sin URL de descarga para terceros)
+    pub manual_downloads: Vec<ManualDownload>,
+    pub overrides_extracted: usize,
+    /// Entradas ignoradas (rutas inseguras, hosts no permitidos, solo servidor...)
+    pub warnings: Vec<String>,
+}
+
+impl ImportedPack {
+    /// Jobs para el pool de descargas. Van con `pre_resolved`, así que no pasan por ninguna búsqueda.
+    pub fn download_jobs(&self) -> Vec<DownloadJob> {
+        let game_version = self.profile.game_version.clone().unwrap_or_default();
+        let loader = self.profile.loader.clone().unwrap_or_default();
+
+        self.downloads.iter()
+            .map(|(folder, info)| DownloadJob {
+                key: info.filename.clone(),
+                modinfo: self.profile.mods.get(&info.filename).cloned().unwrap_or_default(),
+                output_folder: folder.to_string_lossy().to_string(),
+                selected_version: game_version.clone(),
+                selected_loader: loader.clone(),
+                content_type: ContentType::Mod,
+                replaces_filename: None,
+                raw_game_version: game_version.clone(),
+                pre_resolved: Some(info.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Archivo del pack que no se puede descargar automáticamente.
+#[derive(Debug, Clone)]
+pub struct ManualDownload {
+    pub name: String,
+    /// Página desde la que descargarlo
+    pub url: String,
+    /// Carpeta donde hay que dejarlo
+    pub folder: PathBuf,
+}
+
+/// Para qué versión de MC y loader se exporta un perfil, y dónde buscar sus jars.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub game_version: String,
+    pub loader: String,
+    pub loader_version: String,
+    /// Versión del propio pack (`versionId`)
+    pub version_id: String,
+    /// Carpeta con los jars del perfil (normalmente `modpacks/<perfil>`). Los jars que no se pueden
+    /// referenciar desde el manifest se incluyen desde aquí en `overrides/mods`.
+    pub local_folder: Option<PathBuf>,
+}
+
+/// Resumen de una exportación.
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// Archivos referenciados por el manifest (el launcher los descarga)
+    pub indexed: Vec<String>,
+    /// Jars incluidos dentro del zip en `overrides/mods` (solo en CurseForge o solo locales)
+    pub bundled: Vec<String>,
+    /// Mods que no se pudieron resolver ni encontrar en disco
+    pub missing: Vec<String>,
+}
+
+/// Carpeta temporal propia de una exportación. El contador evita que dos exportaciones a la vez
+/// en el mismo proceso compartan carpeta (y que una borre los jars de la otra al terminar).
+pub fn export_scratch_dir(prefix: &str) -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("{}_{}_{}", prefix, std::process::id(), n))
+}
+
+/// Escribe el zip de un pack: el manifest en la raíz y los jars incluidos bajo `overrides/mods/`.
+/// Se escribe a un `.tmp` y se renombra, para no dejar un zip a medias si algo falla.
+pub fn write_pack_zip(output: &Path, manifest_name: &str, manifest: &[u8], bundled: &[(String, PathBuf)]) -> Result<(), String> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("No se pudo crear '{}': {}", parent.display(), e))?;
+    }
+    let tmp = output.with_extension("tmp");
+    let result = (|| -> Result<(), String> {
+        let file = fs::File::create(&tmp).map_err(|e| format!("No se pudo crear '{}': {}", tmp.display(), e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let deflated = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        // Los jars ya van comprimidos
+        let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+        zip.start_file(manifest_name, deflated).map_err(|e| e.to_string())?;
+        zip.write_all(manifest).map_err(|e| e.to_string())?;
+
+        for (name, source) in bundled {
+            let mut jar = fs::File::open(source).map_err(|e| format!("No se pudo leer '{}': {}", source.display(), e))?;
+            zip.start_file(format!("{}/mods/{}", OVERRIDES_DIR, name), stored).map_err(|e| e.to_string())?;
+            std::io::copy(&mut jar, &mut zip).map_err(|e| format!("Error añadiendo '{}': {}", name, e))?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => fs::rename(&tmp, output).map_err(|e| format!("No se pudo escribir '{}': {}", output.display(), e)),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Formatos a los que se puede exportar un perfil.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackFormat {
+    Mrpack,
+    CurseForge,
+    /// Carpeta packwiz (`pack.toml` + un `.pw.toml` por mod), pensada para versionarla en git
+    Packwiz,
+}
+
+impl PackFormat {
+    pub const ALL: [PackFormat; 3] = [PackFormat::Mrpack, PackFormat::CurseForge, PackFormat::Packwiz];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PackFormat::Mrpack => "Modrinth (.mrpack)",
+            PackFormat::CurseForge => "CurseForge (.zip)",
+            PackFormat::Packwiz => "packwiz (carpeta)",
+        }
+    }
+
+    /// Nombre del archivo (o carpeta, en packwiz) exportado para un perfil.
+    pub fn file_name(&self, profile_name: &str, game_version: &str) -> String {
+        match self {
+            PackFormat::Mrpack => format!("{}-{}.mrpack", profile_name, game_version),
+            PackFormat::CurseForge => format!("{}-{}-curseforge.zip", profile_name, game_version),
+            PackFormat::Packwiz => format!("{}-{}-packwiz", profile_name, game_version),
+        }
+    }
+}
+
+/// Exporta un perfil en el formato indicado.
+pub fn export_pack(profile: &Profile, format: PackFormat, opts: &ExportOptions, output: &Path) -> Result<ExportReport, String> {
+    match format {
+        PackFormat::Mrpack => mrpack::export_mrpack(profile, opts, output),
+        PackFormat::CurseForge => curseforge::export_curseforge(profile, opts, output),
+        PackFormat::Packwiz => packwiz::export_packwiz(profile, opts, output),
+    }
+}
+
+/// Importa un pack detectando su formato: carpeta packwiz (o su `pack.toml`), o por la extensión.
+pub fn import_pack(path: &Path, db: &ProfilesDatabase, modpacks_root: &Path) -> Result<ImportedPack, String> {
+    if packwiz::pack_root(path).is_some() {
+        return packwiz::import_packwiz(path, db, modpacks_root);
+    }
+    match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+        Some("mrpack") => mrpack::import_mrpack(path, db, modpacks_root),
+        Some("zip") if curseforge::is_curseforge_pack(path) => curseforge::import_curseforge(path, db, modpacks_root),
+        _ => Err(format!("Formato de pack no soportado: '{}'", path.display())),
+    }
+}
+
+/// Convierte una ruta relativa de un pack en una ruta segura: sin `..`, sin raíz ni unidad.
+pub fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if clean.as_os_str().is_empty() { None } else { Some(clean) }
+}
+
+/// Nombre libre para el perfil y su carpeta: sin caracteres problemáticos y con sufijo " (2)", " (3)"...
+/// si ya existe un perfil o una carpeta con ese nombre.
+pub fn unique_pack_name(db: &ProfilesDatabase, modpacks_root: &Path, wanted: &str) -> String {
+    let base: String = wanted.chars()
+        .map(|c| if matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control() { '_' } else { c })
+        .collect();
+    let base = base.trim().trim_matches('.').to_string();
+    let base = if base.is_empty() { "modpack".to_string() } else { base };
+
+    let taken = |name: &str| db.get_profile(name).is_some() || modpacks_root.join(name).exists();
+    if !taken(&base) {
+        return base;
+    }
+    (2..).map(|n| format!("{} ({})", base, n)).find(|name| !taken(name)).unwrap_or(base)
+}
+
+/// Carpeta de destino de un archivo del pack: `mods/...` a la raíz del modpack, el resto a `overrides/`.
+pub fn destination_for(folder: &Path, rel: &Path) -> PathBuf {
+    match rel.strip_prefix("mods") {
+        Ok(inside_mods) if !inside_mods.as_os_str().is_empty() => folder.join(inside_mods),
+        _ => folder.join(OVERRIDES_DIR).join(rel),
+    }
+}
+
+/// Extrae las entradas bajo `prefix` (p.ej. "overrides/") en el modpack. Devuelve cuántos archivos se escribieron.
+pub fn extract_overrides<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    prefix: &str,
+    folder: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<usize, String> {
+    let mut extracted = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| format!("Zip corrupto: {}", e))?;
+        let Some(rel) = entry.name().strip_prefix(prefix).map(str::to_string) else { continue };
+        if entry.is_dir() || rel.is_empty() {
+            continue;
+        }
+        let Some(rel_path) = safe_relative_path(&rel) else {
+            warnings.push(format!("Ruta insegura ignorada: {}", entry.name()));
+            continue;
+        };
+
+        let dest = destination_for(folder, &rel_path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("No se pudo crear '{}': {}", parent.display(), e))?;
+        }
+        let mut out = fs::File::create(&dest).map_err(|e| format!("No se pudo escribir '{}': {}", dest.display(), e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("Error extrayendo '{}': {}", rel, e))?;
+        extracted += 1;
+    }
+    Ok(extracted)
+}
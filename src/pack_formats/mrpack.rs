@@ -0,0 +1,327 @@
+//! Formato `.mrpack` de Modrinth: un zip con `modrinth.index.json` (archivos a descargar con sus
+//! hashes) y carpetas `overrides/` / `client-overrides/` que se copian tal cual.
+//! Solo se pueden referenciar archivos de hosts permitidos (el CDN de Modrinth, GitHub...): al exportar,
+//! lo que está solo en CurseForge o solo en disco se incluye dentro del zip en `overrides/mods`.
+//! Especificación: https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{destination_for, export_scratch_dir, extract_overrides, safe_relative_path, unique_pack_name, write_pack_zip, ExportOptions, ExportReport, ImportedPack};
+use crate::fetch::async_download::best_known_project_id;
+use crate::fetch::fetch_from_api::{self, ModDownloadInfo};
+use crate::fetch::modrinth_api;
+use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::hashing::{self, ExpectedFile};
+use crate::local_mods_ops::{ModInfo, PinnedFile};
+use crate::profiles::{Profile, ProfilesDatabase};
+
+pub const INDEX_FILE: &str = "modrinth.index.json";
+
+/// Hosts desde los que el formato permite descargar archivos.
+pub const ALLOWED_DOWNLOAD_HOSTS: [&str; 4] = ["cdn.modrinth.com", "github.com", "raw.githubusercontent.com", "gitlab.com"];
+
+/// Clave del bloque `dependencies` → nombre del loader en la app.
+pub const LOADER_DEPENDENCIES: [(&str, &str); 4] = [
+    ("fabric-loader", "Fabric"),
+    ("quilt-loader", "Quilt"),
+    ("forge", "Forge"),
+    ("neoforge", "NeoForge"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub files: Vec<MrpackFile>,
+    /// "minecraft" y el loader ("fabric-loader", "forge"...) → versión
+    pub dependencies: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    pub path: String,
+    /// "sha1" y "sha512" → hex
+    pub hashes: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<MrpackEnv>,
+    pub downloads: Vec<String>,
+    pub file_size: u64,
+}
+
+/// Lado en el que se necesita un archivo: "required", "optional" o "unsupported".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MrpackEnv {
+    pub client: String,
+    pub server: String,
+}
+
+impl MrpackIndex {
+    /// Loader de la app y su versión según el bloque `dependencies`.
+    pub fn loader(&self) -> Option<(String, String)> {
+        LOADER_DEPENDENCIES.iter()
+            .find_map(|(key, loader)| self.dependencies.get(*key).map(|v| (loader.to_string(), v.clone())))
+    }
+}
+
+/// Clave del bloque `dependencies` para un loader de la app ("Fabric" → "fabric-loader").
+pub fn loader_dependency_key(loader: &str) -> Option<&'static str> {
+    LOADER_DEPENDENCIES.iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(loader))
+        .map(|(key, _)| *key)
+}
+
+/// IDs de proyecto y versión a partir de una URL del CDN (`/data/<proyecto>/versions/<versión>/<archivo>`).
+pub fn ids_from_cdn_url(url: &str) -> Option<(String, String)> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if parsed.host_str() != Some("cdn.modrinth.com") {
+        return None;
+    }
+    let segments: Vec<&str> = parsed.path_segments()?.collect();
+    match segments.as_slice() {
+        ["data", project, "versions", version, ..] => Some((project.to_string(), version.to_string())),
+        _ => None,
+    }
+}
+
+fn allowed_download(url: &str) -> bool {
+    reqwest::Url::parse(url).ok()
+        .and_then(|u| u.host_str().map(|h| ALLOWED_DOWNLOAD_HOSTS.contains(&h)))
+        .unwrap_or(false)
+}
+
+/// Lee solo el índice de un `.mrpack`.
+pub fn read_index(path: &Path) -> Result<MrpackIndex, String> {
+    let file = fs::File::open(path).map_err(|e| format!("No se pudo abrir '{}': {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("'{}' no es un zip válido: {}", path.display(), e))?;
+    let entry = archive.by_name(INDEX_FILE).map_err(|_| format!("'{}' no contiene {}", path.display(), INDEX_FILE))?;
+    serde_json::from_reader(entry).map_err(|e| format!("{} inválido: {}", INDEX_FILE, e))
+}
+
+/// Importa un `.mrpack`: crea `modpacks/<nombre>`, extrae los overrides y devuelve el perfil
+/// (con cada archivo fijado a su versión) y las descargas pendientes, que se verifican por hash.
+/// El perfil no se guarda: eso lo decide quien llama (`ProfilesDatabase::add_profile` + `save_profiles`).
+pub fn import_mrpack(path: &Path, db: &ProfilesDatabase, modpacks_root: &Path) -> Result<ImportedPack, String> {
+    let index = read_index(path)?;
+    if index.game != "minecraft" {
+        return Err(format!("Juego no soportado en el .mrpack: '{}'", index.game));
+    }
+
+    let name = unique_pack_name(db, modpacks_root, &index.name);
+    let folder = modpacks_root.join(&name);
+    fs::create_dir_all(&folder).map_err(|e| format!("No se pudo crear '{}': {}", folder.display(), e))?;
+
+    let mut profile = Profile::new(name, index.summary.clone());
+    profile.game_version = index.dependencies.get("minecraft").cloned();
+    if let Some((loader, loader_version)) = index.loader() {
+        profile.loader = Some(loader);
+        profile.loader_version = Some(loader_version);
+    }
+
+    let mut downloads = Vec::new();
+    let mut warnings = Vec::new();
+
+    for file in &index.files {
+        if file.env.as_ref().is_some_and(|env| env.client == "unsupported") {
+            continue; // Solo servidor
+        }
+        let Some(rel) = safe_relative_path(&file.path) else {
+            warnings.push(format!("Ruta insegura ignorada: {}", file.path));
+            continue;
+        };
+        let Some(url) = file.downloads.iter().find(|u| allowed_download(u)) else {
+            warnings.push(format!("Sin URL de descarga permitida: {}", file.path));
+            continue;
+        };
+        let dest = destination_for(&folder, &rel);
+        let (Some(dest_folder), Some(filename)) = (dest.parent(), dest.file_name()) else { continue };
+        let filename = filename.to_string_lossy().to_string();
+
+        let ids = ids_from_cdn_url(url);
+        let pinned = PinnedFile {
+            url: url.clone(),
+            filename: filename.clone(),
+            version_id: ids.as_ref().map(|(_, version)| version.clone()),
+            expected: ExpectedFile {
+                size: Some(file.file_size).filter(|s| *s > 0),
+                sha1: file.hashes.get("sha1").cloned(),
+                sha512: file.hashes.get("sha512").cloned(),
+                ..Default::default()
+            },
+        };
+        let mi = ModInfo {
+            key: filename.clone(),
+            name: filename.trim_end_matches(".jar").to_string(),
+            detected_project_id: ids.as_ref().map(|(project, _)| project.clone()),
+            confirmed_project_id: ids.map(|(project, _)| project),
+            selected: true,
+            pinned: Some(pinned),
+            ..Default::default()
+        };
+        let Some(info) = ModDownloadInfo::from_pinned(&mi) else { continue };
+
+        // Solo los jars de mods/ forman parte del perfil; el resto se descarga a overrides/
+        if rel.starts_with("mods") {
+            profile.mods.insert(filename, mi);
+        }
+        downloads.push((dest_folder.to_path_buf(), info));
+    }
+
+    // Los client-overrides van después para que tengan prioridad sobre los comunes
+    let file = fs::File::open(path).map_err(|e| format!("No se pudo abrir '{}': {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Zip corrupto: {}", e))?;
+    let mut overrides_extracted = 0;
+    for prefix in ["overrides/", "client-overrides/"] {
+        overrides_extracted += extract_overrides(&mut archive, prefix, &folder, &mut warnings)?;
+    }
+
+    println!("📦 Importado '{}': {} archivos a descargar, {} overrides", profile.name, downloads.len(), overrides_extracted);
+    Ok(ImportedPack { profile, folder, downloads, manual_downloads: Vec::new(), overrides_extracted, warnings })
+}
+
+// ── Exportación ──────────────────────────────────────────────
+
+/// Entrada del índice para un archivo descargable, si cumple lo que exige el formato
+/// (host permitido, SHA-1, SHA-512 y tamaño).
+fn index_entry(filename: &str, url: &str, expected: &ExpectedFile) -> Option<MrpackFile> {
+    if !allowed_download(url) {
+        return None;
+    }
+    let mut hashes = IndexMap::new();
+    hashes.insert("sha1".to_string(), expected.sha1.clone()?);
+    hashes.insert("sha512".to_string(), expected.sha512.clone()?);
+    Some(MrpackFile {
+        path: format!("mods/{}", filename),
+        hashes,
+        env: None,
+        downloads: vec![url.to_string()],
+        file_size: expected.size?,
+    })
+}
+
+/// `client_side`/`server_side` de Modrinth → valor de `env` ("unknown" cuenta como requerido).
+fn env_side(side: &str) -> String {
+    match side {
+        "optional" | "unsupported" => side.to_string(),
+        _ => "required".to_string(),
+    }
+}
+
+/// Exporta un perfil como `.mrpack` en `output`.
+/// Cada mod se resuelve, por orden: archivo fijado (si el perfil es de esta versión de MC), jar local
+/// identificado por SHA-1 en Modrinth, y búsqueda normal. Lo que no se puede referenciar desde un host
+/// permitido (CurseForge, jars locales desconocidos) se incluye en `overrides/mods`.
+pub fn export_mrpack(profile: &Profile, opts: &ExportOptions, output: &Path) -> Result<ExportReport, String> {
+    let loader_key = loader_dependency_key(&opts.loader)
+        .ok_or_else(|| format!("Loader no soportado por .mrpack: '{}'", opts.loader))?;
+    if opts.loader_version.trim().is_empty() {
+        return Err(format!("Falta la versión de {} para el bloque dependencies", opts.loader));
+    }
+
+    let pins_valid = profile.game_version.as_deref() == Some(opts.game_version.as_str());
+    let cf_key = crate::fetch::cf_api_key();
+    let scratch = export_scratch_dir("tetanos_mrpack_export");
+
+    let mut report = ExportReport::default();
+    let mut files: Vec<(MrpackFile, Option<String>)> = Vec::new(); // archivo + project_id (para env)
+    let mut bundled: Vec<(String, PathBuf)> = Vec::new();
+    let mut locals: Vec<(String, PathBuf, String)> = Vec::new(); // filename, ruta, sha1
+
+    for (key, mi) in &profile.mods {
+        if pins_valid
+            && let Some(pin) = &mi.pinned
+            && let Some(file) = index_entry(&pin.filename, &pin.url, &pin.expected)
+        {
+            files.push((file, mi.confirmed_project_id.clone()));
+            continue;
+        }
+
+        if let Some(path) = opts.local_folder.as_ref().map(|f| f.join(key)).filter(|p| p.is_file()) {
+            match hashing::hash_file(&path) {
+                Ok(h) => locals.push((key.clone(), path, h.sha1)),
+                Err(_) => bundled.push((key.clone(), path)),
+            }
+            continue;
+        }
+
+        let project_id = best_known_project_id(mi);
+        let Some(info) = fetch_from_api::find_mod_download(&mi.name, project_id.as_deref(), &opts.game_version, &opts.loader, &cf_key, &ContentType::Mod) else {
+            report.missing.push(mi.name.clone());
+            continue;
+        };
+        if let Some(file) = index_entry(&info.filename, &info.url, &info.expected) {
+            files.push((file, Some(info.project_id)));
+            continue;
+        }
+        // Solo en CurseForge: se descarga (verificado) para incluirlo en el zip
+        match fetch_from_api::download_mod_file(&info.url, &scratch.to_string_lossy(), &info.filename, &info.expected, |_| {}) {
+            Ok(()) => bundled.push((info.filename.clone(), scratch.join(&info.filename))),
+            Err(e) => {
+                println!("❌ No se pudo descargar '{}' para el pack: {}", info.filename, e);
+                report.missing.push(mi.name.clone());
+            }
+        }
+    }
+
+    // Jars locales: una sola petición bulk a Modrinth por SHA-1
+    let sha1s: Vec<String> = locals.iter().map(|(_, _, sha1)| sha1.clone()).collect();
+    let versions = if sha1s.is_empty() { Default::default() } else { modrinth_api::fetch_versions_by_hashes(&sha1s, "sha1") };
+    for (filename, path, sha1) in locals {
+        let entry = versions.get(&sha1).and_then(|version| {
+            version.files.iter()
+                .find(|f| f.hashes.get("sha1") == Some(&sha1))
+                .and_then(|f| index_entry(&f.filename, &f.url, &f.expected()))
+                .map(|file| (file, Some(version.project_id.clone())))
+        });
+        match entry {
+            Some(entry) => files.push(entry),
+            None => bundled.push((filename, path)),
+        }
+    }
+
+    // Lados cliente/servidor de cada proyecto, en bloque
+    let ids: Vec<String> = files.iter().filter_map(|(_, id)| id.clone()).filter(|id| !id.is_empty()).collect();
+    let projects = if ids.is_empty() { Default::default() } else { modrinth_api::fetch_modrinth_projects(&ids) };
+    for (file, id) in &mut files {
+        if let Some(id) = id
+            && let Some(project) = projects.values().find(|p| &p.id == id || &p.slug == id)
+        {
+            file.env = Some(MrpackEnv { client: env_side(&project.client_side), server: env_side(&project.server_side) });
+        }
+    }
+
+    let mut dependencies = IndexMap::new();
+    dependencies.insert("minecraft".to_string(), opts.game_version.clone());
+    dependencies.insert(loader_key.to_string(), opts.loader_version.trim().to_string());
+
+    report.indexed = files.iter().map(|(f, _)| f.path.clone()).collect();
+    report.bundled = bundled.iter().map(|(name, _)| name.clone()).collect();
+    let index = MrpackIndex {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: opts.version_id.clone(),
+        name: profile.name.clone(),
+        summary: profile.description.clone(),
+        files: files.into_iter().map(|(f, _)| f).collect(),
+        dependencies,
+    };
+    let json = serde_json::to_vec_pretty(&index).map_err(|e| e.to_string())?;
+
+    let result = write_pack_zip(output, INDEX_FILE, &json, &bundled);
+    if scratch.exists() {
+        // Carpeta temporal creada por esta exportación
+        let _ = fs::remove_dir_all(&scratch);
+    }
+    result?;
+
+    println!("📦 Exportado '{}': {} en el índice, {} incluidos, {} sin resolver", profile.name, report.indexed.len(), report.bundled.len(), report.missing.len());
+    Ok(report)
+}
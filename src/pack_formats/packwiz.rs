@@ -0,0 +1,587 @@
+//! Formato packwiz: una carpeta (normalmente versionada en git) con `pack.toml`, `index.toml` y un
+//! `.pw.toml` por mod con su URL, hash y de dónde actualizarlo (`[update.modrinth]` / `[update.curseforge]`).
+//! El resto de archivos del índice (configs, jars sueltos...) se copian tal cual.
+//! Especificación: https://packwiz.infra.link/reference/pack-format/
+
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::curseforge::project_page;
+use super::mrpack::ids_from_cdn_url;
+use super::{destination_for, safe_relative_path, unique_pack_name, ExportOptions, ExportReport, ImportedPack, ManualDownload};
+use crate::fetch::async_download::best_known_project_id;
+use crate::fetch::curseforge_api;
+use crate::fetch::fetch_from_api::{self, ModDownloadInfo};
+use crate::fetch::modrinth_api;
+use crate::fetch::search_provider::ContentType;
+use crate::local_mods_ops::hashing::{self, ExpectedFile};
+use crate::local_mods_ops::{ModInfo, PinnedFile};
+use crate::profiles::{Profile, ProfilesDatabase};
+
+pub const PACK_FILE: &str = "pack.toml";
+pub const INDEX_FILE: &str = "index.toml";
+pub const METAFILE_SUFFIX: &str = ".pw.toml";
+pub const PACK_FORMAT: &str = "packwiz:1.1.0";
+/// Hash de `index.toml` y de sus entradas al exportar.
+const INDEX_HASH_FORMAT: &str = "sha256";
+/// Modo de descarga de los mods de CurseForge: el launcher pide la URL a la API con `[update.curseforge]`.
+const CURSEFORGE_MODE: &str = "metadata:curseforge";
+
+/// Clave de `[versions]` → nombre del loader en la app.
+pub const LOADER_VERSIONS: [(&str, &str); 4] = [
+    ("fabric", "Fabric"),
+    ("quilt", "Quilt"),
+    ("forge", "Forge"),
+    ("neoforge", "NeoForge"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackToml {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub pack_format: String,
+    pub index: PackIndexRef,
+    /// "minecraft" y el loader ("fabric", "forge"...) → versión
+    pub versions: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackIndexRef {
+    pub file: String,
+    pub hash_format: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexToml {
+    pub hash_format: String,
+    #[serde(default)]
+    pub files: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexEntry {
+    pub file: String,
+    pub hash: String,
+    /// Formato del hash de esta entrada si no es el de `index.toml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModToml {
+    pub name: String,
+    pub filename: String,
+    /// "client", "server" o "both"
+    #[serde(default = "default_side")]
+    pub side: String,
+    pub download: ModDownload,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<ModUpdate>,
+}
+
+fn default_side() -> String {
+    "both".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModDownload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub hash_format: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseForgeUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    pub mod_id: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseForgeUpdate {
+    pub file_id: u32,
+    pub project_id: u32,
+}
+
+impl PackToml {
+    /// Loader de la app y su versión según `[versions]`.
+    pub fn loader(&self) -> Option<(String, String)> {
+        LOADER_VERSIONS.iter()
+            .find_map(|(key, loader)| self.versions.get(*key).map(|v| (loader.to_string(), v.clone())))
+    }
+}
+
+impl ModToml {
+    /// ID del proyecto (Modrinth primero) y de la versión/archivo fijados.
+    pub fn ids(&self) -> Option<(String, String)> {
+        let update = self.update.as_ref()?;
+        update.modrinth.as_ref().map(|m| (m.mod_id.clone(), m.version.clone()))
+            .or_else(|| update.curseforge.as_ref().map(|c| (c.project_id.to_string(), c.file_id.to_string())))
+    }
+
+    /// Hash del `[download]`; None si la app no sabe comprobarlo (md5).
+    fn expected(&self) -> Option<ExpectedFile> {
+        expected_from_hash(&self.download.hash_format, &self.download.hash)
+    }
+}
+
+/// `hash-format` + `hash` de packwiz como ExpectedFile. None para formatos que no se pueden comprobar (md5).
+pub fn expected_from_hash(format: &str, hash: &str) -> Option<ExpectedFile> {
+    let hash = hash.trim().to_lowercase();
+    let mut expected = ExpectedFile::default();
+    match format.to_lowercase().as_str() {
+        "sha1" => expected.sha1 = Some(hash),
+        "sha256" => expected.sha256 = Some(hash),
+        "sha512" => expected.sha512 = Some(hash),
+        // packwiz escribe el fingerprint de CurseForge en decimal
+        "murmur2" => expected.cf_fingerprint = Some(hash.parse().ok()?),
+        _ => return None,
+    }
+    Some(expected)
+}
+
+/// Lee un archivo del pack y comprueba el hash con el que lo lista el índice.
+fn read_verified(path: &Path, format: &str, hash: &str) -> Result<Vec<u8>, String> {
+    let expected = expected_from_hash(format, hash)
+        .ok_or_else(|| format!("'{}': formato de hash '{}' no soportado", path.display(), format))?;
+    let bytes = fs::read(path).map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+    hashing::verify_bytes(&bytes, &expected).map_err(|e| format!("'{}': {}", path.display(), e))?;
+    Ok(bytes)
+}
+
+fn parse_toml<T: DeserializeOwned>(path: &Path, bytes: &[u8]) -> Result<T, String> {
+    let content = std::str::from_utf8(bytes).map_err(|e| format!("'{}' inválido: {}", path.display(), e))?;
+    toml::from_str(content).map_err(|e| format!("'{}' inválido: {}", path.display(), e))
+}
+
+/// Clave de `[versions]` para un loader de la app ("Fabric" → "fabric").
+pub fn loader_version_key(loader: &str) -> Option<&'static str> {
+    LOADER_VERSIONS.iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(loader))
+        .map(|(key, _)| *key)
+}
+
+/// Carpeta raíz de un pack packwiz a partir de la carpeta o de su `pack.toml`.
+pub fn pack_root(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        return path.join(PACK_FILE).is_file().then(|| path.to_path_buf());
+    }
+    if path.file_name().is_some_and(|n| n == PACK_FILE) && path.is_file() {
+        return path.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
+fn read_toml<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("'{}' inválido: {}", path.display(), e))
+}
+
+/// Nombre de archivo sin directorios (los `filename` de un `.pw.toml` no deberían llevarlos).
+fn plain_file_name(name: &str) -> Option<String> {
+    let file = Path::new(name).file_name()?.to_string_lossy().to_string();
+    (!file.is_empty() && file != "..").then_some(file)
+}
+
+/// Importa un pack packwiz (carpeta o `pack.toml`): crea `modpacks/<nombre>`, copia los archivos sueltos
+/// del índice y devuelve el perfil (con los mods fijados a su versión) y las descargas pendientes.
+/// Los mods de CurseForge en modo `metadata:curseforge` se resuelven contra la API.
+pub fn import_packwiz(path: &Path, db: &ProfilesDatabase, modpacks_root: &Path) -> Result<ImportedPack, String> {
+    let root = pack_root(path).ok_or_else(|| format!("'{}' no es un pack de packwiz (falta {})", path.display(), PACK_FILE))?;
+    let pack: PackToml = read_toml(&root.join(PACK_FILE))?;
+    let index_rel = safe_relative_path(&pack.index.file)
+        .ok_or_else(|| format!("Ruta de índice insegura: {}", pack.index.file))?;
+    let index_path = root.join(&index_rel);
+    // Un índice que no coincide con pack.toml no es fiable: no se importa nada
+    let index: IndexToml = parse_toml(&index_path, &read_verified(&index_path, &pack.index.hash_format, &pack.index.hash)?)?;
+    // Las rutas del índice son relativas a la carpeta de index.toml
+    let index_dir = index_path.parent().map(Path::to_path_buf).unwrap_or_else(|| root.clone());
+
+    let name = unique_pack_name(db, modpacks_root, &pack.name);
+    let folder = modpacks_root.join(&name);
+    fs::create_dir_all(&folder).map_err(|e| format!("No se pudo crear '{}': {}", folder.display(), e))?;
+
+    let mut profile = Profile::new(name, pack.description.clone());
+    profile.game_version = pack.versions.get("minecraft").cloned();
+    if let Some((loader, loader_version)) = pack.loader() {
+        profile.loader = Some(loader);
+        profile.loader_version = Some(loader_version);
+    }
+
+    let mut downloads = Vec::new();
+    let mut manual_downloads = Vec::new();
+    let mut warnings = Vec::new();
+    let mut overrides_extracted = 0;
+
+    // 1. Archivos sueltos y metafiles, cada uno comprobado contra el hash del índice
+    let mut metas: Vec<(PathBuf, String, PathBuf, ModToml)> = Vec::new(); // (ruta en el índice, archivo, carpeta destino, .pw.toml)
+    for entry in &index.files {
+        let Some(rel) = safe_relative_path(&entry.file) else {
+            warnings.push(format!("Ruta insegura ignorada: {}", entry.file));
+            continue;
+        };
+        let source = index_dir.join(&rel);
+        let hash_format = entry.hash_format.as_deref().unwrap_or(&index.hash_format);
+        let bytes = match read_verified(&source, hash_format, &entry.hash) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warnings.push(format!("Ignorado: {}", e));
+                continue;
+            }
+        };
+
+        if !entry.metafile && !entry.file.ends_with(METAFILE_SUFFIX) {
+            // Archivo suelto (config, jar sin metadatos...): se copia como override
+            let dest = destination_for(&folder, &rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("No se pudo crear '{}': {}", parent.display(), e))?;
+            }
+            match fs::write(&dest, &bytes) {
+                Ok(_) => overrides_extracted += 1,
+                Err(e) => warnings.push(format!("No se pudo copiar '{}': {}", entry.file, e)),
+            }
+            continue;
+        }
+
+        let meta: ModToml = match parse_toml(&source, &bytes) {
+            Ok(meta) => meta,
+            Err(e) => {
+                warnings.push(e);
+                continue;
+            }
+        };
+        if meta.side == "server" {
+            continue; // Solo servidor
+        }
+        let Some(filename) = plain_file_name(&meta.filename) else {
+            warnings.push(format!("Nombre de archivo inválido en {}: {}", entry.file, meta.filename));
+            continue;
+        };
+        let rel_dir = rel.parent().map(Path::to_path_buf).unwrap_or_default();
+        let dest = destination_for(&folder, &rel_dir.join(&filename));
+        let Some(dest_folder) = dest.parent().map(Path::to_path_buf) else { continue };
+        metas.push((rel, filename, dest_folder, meta));
+    }
+
+    // 2. Los de `metadata:curseforge` no traen URL: todos sus archivos en una sola petición
+    let cf_key = crate::fetch::cf_api_key();
+    let cf_file_ids: Vec<u32> = metas.iter()
+        .filter(|(.., meta)| meta.download.url.is_none())
+        .filter_map(|(.., meta)| meta.update.as_ref()?.curseforge.as_ref().map(|cf| cf.file_id))
+        .collect();
+    let cf_files = if cf_file_ids.is_empty() || cf_key.is_empty() {
+        HashMap::new()
+    } else {
+        curseforge_api::fetch_curseforge_files(&cf_file_ids, &cf_key)
+    };
+
+    // 3. Descargas fijadas; sin un hash comprobable no se encolan
+    for (rel, filename, dest_folder, meta) in metas {
+        let ids = meta.ids();
+        let cf_update = meta.update.as_ref().and_then(|u| u.curseforge.clone());
+        let cf_file = cf_update.as_ref().and_then(|cf| cf_files.get(&cf.file_id));
+        let url = meta.download.url.clone().or_else(|| cf_file.and_then(|file| file.download_url.clone()));
+        let expected = meta.expected()
+            .or_else(|| cf_file.map(|file| file.expected()).filter(ExpectedFile::has_hash));
+
+        let mut mi = ModInfo {
+            key: filename.clone(),
+            name: meta.name.clone(),
+            detected_project_id: ids.as_ref().map(|(project, _)| project.clone()),
+            confirmed_project_id: ids.as_ref().map(|(project, _)| project.clone()),
+            selected: true,
+            ..Default::default()
+        };
+        match (url, expected) {
+            (Some(url), Some(expected)) => {
+                mi.pinned = Some(PinnedFile {
+                    url,
+                    filename: filename.clone(),
+                    version_id: ids.map(|(_, version)| version),
+                    expected,
+                });
+                if let Some(info) = ModDownloadInfo::from_pinned(&mi) {
+                    downloads.push((dest_folder, info));
+                }
+            }
+            (Some(url), None) => {
+                warnings.push(format!(
+                    "'{}': hash '{}' no comprobable, no se descarga automáticamente", filename, meta.download.hash_format
+                ));
+                manual_downloads.push(ManualDownload { name: filename.clone(), url, folder: dest_folder });
+            }
+            (None, _) => manual_downloads.push(ManualDownload {
+                name: filename.clone(),
+                url: cf_update.map(|cf| project_page(cf.project_id)).unwrap_or_default(),
+                folder: dest_folder,
+            }),
+        }
+        // Solo los de mods/ forman parte del perfil; el resto (resourcepacks...) se descarga a overrides/
+        if rel.starts_with("mods") {
+            profile.mods.insert(filename, mi);
+        }
+    }
+
+    println!(
+        "📦 Importado '{}': {} archivos a descargar, {} manuales, {} archivos copiados",
+        profile.name, downloads.len(), manual_downloads.len(), overrides_extracted
+    );
+    Ok(ImportedPack { profile, folder, downloads, manual_downloads, overrides_extracted, warnings })
+}
+
+// ── Exportación ──────────────────────────────────────────────
+
+/// Nombre del `.pw.toml` de un mod: "Fabric API" → "fabric-api".
+pub fn metafile_slug(name: &str) -> String {
+    let slug: String = name.to_lowercase().chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "mod".to_string() } else { slug }
+}
+
+/// `[download]` con el mejor hash disponible (SHA-512, luego SHA-1).
+fn url_download(url: &str, expected: &ExpectedFile) -> Option<ModDownload> {
+    let (hash_format, hash) = match (&expected.sha512, &expected.sha1) {
+        (Some(sha512), _) => ("sha512", sha512),
+        (None, Some(sha1)) => ("sha1", sha1),
+        _ => return None,
+    };
+    Some(ModDownload { url: Some(url.to_string()), hash_format: hash_format.to_string(), hash: hash.clone(), mode: None })
+}
+
+fn modrinth_meta(name: &str, filename: &str, url: &str, expected: &ExpectedFile) -> Option<ModToml> {
+    let (mod_id, version) = ids_from_cdn_url(url)?;
+    Some(ModToml {
+        name: name.to_string(),
+        filename: filename.to_string(),
+        side: default_side(),
+        download: url_download(url, expected)?,
+        update: Some(ModUpdate { modrinth: Some(ModrinthUpdate { mod_id, version }), curseforge: None }),
+    })
+}
+
+/// Mod de CurseForge: sin URL (el launcher la pide a la API), con SHA-1 o el fingerprint como hash.
+fn curseforge_meta(name: &str, filename: &str, project_id: u32, file_id: u32, sha1: Option<String>, fingerprint: u32) -> ModToml {
+    let (hash_format, hash) = match sha1 {
+        Some(sha1) => ("sha1".to_string(), sha1),
+        None => ("murmur2".to_string(), fingerprint.to_string()),
+    };
+    ModToml {
+        name: name.to_string(),
+        filename: filename.to_string(),
+        side: default_side(),
+        download: ModDownload { url: None, hash_format, hash, mode: Some(CURSEFORGE_MODE.to_string()) },
+        update: Some(ModUpdate { modrinth: None, curseforge: Some(CurseForgeUpdate { file_id, project_id }) }),
+    }
+}
+
+/// Lado de un mod según `client_side`/`server_side` de Modrinth.
+fn side_for(client_side: &str, server_side: &str) -> String {
+    match (client_side, server_side) {
+        (_, "unsupported") => "client".to_string(),
+        ("unsupported", _) => "server".to_string(),
+        _ => default_side(),
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("No se pudo crear '{}': {}", parent.display(), e))?;
+    }
+    fs::write(path, bytes).map_err(|e| format!("No se pudo escribir '{}': {}", path.display(), e))
+}
+
+/// Exporta un perfil como carpeta packwiz en `output_dir` (se puede reexportar encima: los archivos
+/// del índice anterior que ya no forman parte del pack se borran, el resto de la carpeta no se toca).
+/// Cada mod se resuelve, por orden: archivo fijado (si el perfil es de esta versión de MC), jar local
+/// identificado por hash (Modrinth y luego CurseForge) y búsqueda normal. Los jars locales que ninguna
+/// plataforma conoce se copian a `mods/` como archivos sueltos del índice.
+pub fn export_packwiz(profile: &Profile, opts: &ExportOptions, output_dir: &Path) -> Result<ExportReport, String> {
+    let loader_key = loader_version_key(&opts.loader)
+        .ok_or_else(|| format!("Loader no soportado por packwiz: '{}'", opts.loader))?;
+    if opts.loader_version.trim().is_empty() {
+        return Err(format!("Falta la versión de {} para [versions]", opts.loader));
+    }
+
+    let pins_valid = profile.game_version.as_deref() == Some(opts.game_version.as_str());
+    let cf_key = crate::fetch::cf_api_key();
+
+    let mut report = ExportReport::default();
+    let mut metas: Vec<ModToml> = Vec::new();
+    let mut raw: Vec<(String, PathBuf)> = Vec::new();
+    let mut locals: Vec<(String, String, PathBuf, hashing::FileHashes)> = Vec::new(); // nombre, filename, ruta, hashes
+
+    for (key, mi) in &profile.mods {
+        if pins_valid
+            && let Some(pin) = &mi.pinned
+        {
+            let cf_ids = mi.confirmed_project_id.as_deref().and_then(|p| p.parse::<u32>().ok())
+                .zip(pin.version_id.as_deref().and_then(|v| v.parse::<u32>().ok()));
+            let meta = modrinth_meta(&mi.name, &pin.filename, &pin.url, &pin.expected).or_else(|| {
+                let (project_id, file_id) = cf_ids?;
+                let sha1 = pin.expected.sha1.clone()?;
+                Some(curseforge_meta(&mi.name, &pin.filename, project_id, file_id, Some(sha1), 0))
+            });
+            if let Some(meta) = meta {
+                metas.push(meta);
+                continue;
+            }
+        }
+
+        if let Some(path) = opts.local_folder.as_ref().map(|f| f.join(key)).filter(|p| p.is_file()) {
+            match hashing::hash_file(&path) {
+                Ok(h) => locals.push((mi.name.clone(), key.clone(), path, h)),
+                Err(_) => raw.push((key.clone(), path)),
+            }
+            continue;
+        }
+
+        let project_id = best_known_project_id(mi);
+        let Some(info) = fetch_from_api::find_mod_download(&mi.name, project_id.as_deref(), &opts.game_version, &opts.loader, &cf_key, &ContentType::Mod) else {
+            report.missing.push(mi.name.clone());
+            continue;
+        };
+        if let Some(meta) = modrinth_meta(&mi.name, &info.filename, &info.url, &info.expected) {
+            metas.push(meta);
+            continue;
+        }
+        // De CurseForge: hace falta el ID del archivo para [update.curseforge]
+        let cf_file = info.project_id.parse::<u32>().ok().and_then(|id| {
+            curseforge_api::fetch_curseforge_version_file(id, &opts.game_version, &opts.loader, &cf_key, &ContentType::Mod)
+                .map(|f| (id, f))
+        });
+        match cf_file {
+            Some((id, f)) => metas.push(curseforge_meta(&mi.name, &f.file_name, id, f.id, f.sha1(), f.file_fingerprint)),
+            None => report.missing.push(mi.name.clone()),
+        }
+    }
+
+    // Jars locales: Modrinth por SHA-1 en bloque, luego fingerprints de CurseForge, y si no, sueltos
+    let sha1s: Vec<String> = locals.iter().map(|(_, _, _, h)| h.sha1.clone()).collect();
+    let versions = if sha1s.is_empty() { Default::default() } else { modrinth_api::fetch_versions_by_hashes(&sha1s, "sha1") };
+    let mut unknown = Vec::new();
+    for (name, filename, path, hashes) in locals {
+        let meta = versions.get(&hashes.sha1).and_then(|version| {
+            let file = version.files.iter().find(|f| f.hashes.get("sha1") == Some(&hashes.sha1))?;
+            modrinth_meta(&name, &file.filename, &file.url, &file.expected())
+        });
+        match meta {
+            Some(meta) => metas.push(meta),
+            None => unknown.push((name, filename, path, hashes)),
+        }
+    }
+    let fingerprints: Vec<u32> = unknown.iter().map(|(_, _, _, h)| h.cf_fingerprint).collect();
+    let matches = if fingerprints.is_empty() || cf_key.is_empty() {
+        Default::default()
+    } else {
+        curseforge_api::fetch_fingerprint_matches(&fingerprints, &cf_key)
+    };
+    for (name, filename, path, hashes) in unknown {
+        match matches.get(&hashes.cf_fingerprint) {
+            Some(m) => metas.push(curseforge_meta(&name, &filename, m.id, m.file.id, Some(hashes.sha1), hashes.cf_fingerprint)),
+            None => raw.push((filename, path)),
+        }
+    }
+
+    // Lados cliente/servidor de los mods de Modrinth, en bloque
+    let ids: Vec<String> = metas.iter()
+        .filter_map(|m| m.update.as_ref()?.modrinth.as_ref().map(|u| u.mod_id.clone()))
+        .collect();
+    let projects = if ids.is_empty() { Default::default() } else { modrinth_api::fetch_modrinth_projects(&ids) };
+    for meta in &mut metas {
+        if let Some(update) = meta.update.as_ref().and_then(|u| u.modrinth.as_ref())
+            && let Some(project) = projects.values().find(|p| p.id == update.mod_id || p.slug == update.mod_id)
+        {
+            meta.side = side_for(&project.client_side, &project.server_side);
+        }
+    }
+
+    // Escritura: un .pw.toml por mod, los jars sueltos, index.toml y pack.toml
+    let mut entries: Vec<IndexEntry> = Vec::new();
+    let mut used_slugs = HashSet::new();
+    for meta in &metas {
+        let base = metafile_slug(&meta.name);
+        let slug = (1..).map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+            .find(|s| used_slugs.insert(s.clone()))
+            .unwrap_or(base);
+        let rel = format!("mods/{}{}", slug, METAFILE_SUFFIX);
+        let content = toml::to_string(meta).map_err(|e| e.to_string())?;
+        write_file(&output_dir.join(&rel), content.as_bytes())?;
+        entries.push(IndexEntry { file: rel, hash: hashing::sha256_hex(content.as_bytes()), hash_format: None, metafile: true });
+        report.indexed.push(meta.filename.clone());
+    }
+    for (filename, source) in &raw {
+        let rel = format!("mods/{}", filename);
+        let bytes = fs::read(source).map_err(|e| format!("No se pudo leer '{}': {}", source.display(), e))?;
+        write_file(&output_dir.join(&rel), &bytes)?;
+        entries.push(IndexEntry { file: rel, hash: hashing::sha256_hex(&bytes), hash_format: None, metafile: false });
+        report.bundled.push(filename.clone());
+    }
+
+    // Reexportación: borrar lo que listaba el índice anterior y ya no está
+    if let Ok(previous) = read_toml::<IndexToml>(&output_dir.join(INDEX_FILE)) {
+        let current: HashSet<&str> = entries.iter().map(|e| e.file.as_str()).collect();
+        for old in previous.files.iter().filter(|e| !current.contains(e.file.as_str())) {
+            if let Some(rel) = safe_relative_path(&old.file) {
+                let _ = fs::remove_file(output_dir.join(rel));
+            }
+        }
+    }
+
+    let index = IndexToml { hash_format: INDEX_HASH_FORMAT.to_string(), files: entries };
+    let index_content = toml::to_string(&index).map_err(|e| e.to_string())?;
+    write_file(&output_dir.join(INDEX_FILE), index_content.as_bytes())?;
+
+    let mut versions = IndexMap::new();
+    versions.insert("minecraft".to_string(), opts.game_version.clone());
+    versions.insert(loader_key.to_string(), opts.loader_version.trim().to_string());
+    let pack = PackToml {
+        name: profile.name.clone(),
+        author: String::new(),
+        version: opts.version_id.clone(),
+        description: profile.description.clone(),
+        pack_format: PACK_FORMAT.to_string(),
+        index: PackIndexRef {
+            file: INDEX_FILE.to_string(),
+            hash_format: INDEX_HASH_FORMAT.to_string(),
+            hash: hashing::sha256_hex(index_content.as_bytes()),
+        },
+        versions,
+    };
+    let pack_content = toml::to_string(&pack).map_err(|e| e.to_string())?;
+    write_file(&output_dir.join(PACK_FILE), pack_content.as_bytes())?;
+
+    println!("📦 Exportado '{}' (packwiz): {} .pw.toml, {} jars sueltos, {} sin resolver", profile.name, report.indexed.len(), report.bundled.len(), report.missing.len());
+    Ok(report)
+}
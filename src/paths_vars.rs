@@ -38,6 +38,13 @@ impl Paths {
 }
 
 pub fn get_default_game_folder() -> Option<PathBuf> {
+    // Override explícito (servidores de build, CLI sin sesión de escritorio)
+    if let Ok(custom) = std::env::var("MODS_UPDATER_GAME_DIR")
+        && !custom.trim().is_empty()
+    {
+        return Some(PathBuf::from(custom));
+    }
+
     // Windows: AppData/Roaming/.minecraft
     if let Some(mut dir) = dirs::data_dir() {
         // En Windows devuelve AppData\Roaming
@@ -11,6 +11,13 @@ pub struct Profile {
     pub created_at: u64,
     pub description: Option<String>,
     pub mods: IndexMap<String, ModInfo>,
+    /// Versión de MC y loader para los que se armó el perfil (p.ej. al importar un .mrpack).
+    #[serde(default)]
+    pub game_version: Option<String>,
+    #[serde(default)]
+    pub loader: Option<String>,
+    #[serde(default)]
+    pub loader_version: Option<String>,
 }
 
 impl Profile {
@@ -26,6 +33,9 @@ impl Profile {
             created_at: since_the_epoch,
             description,
             mods: IndexMap::new(),
+            game_version: None,
+            loader: None,
+            loader_version: None,
         }
     }
 
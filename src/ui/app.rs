@@ -9,21 +9,28 @@ use crate::local_mods_ops::{
     get_minecraft_versions,
     spawn_read_workers, ReadJob, ReadEvent,
 };
+use crate::local_mods_ops::validation::DependencyIssue;
 use crate::local_datapacks_ops::{
     DatapackInfo, DatapackReadJob, DatapackReadEvent,
     spawn_datapack_read_workers,
 };
+use crate::local_datapacks_ops::fs_ops::RepackOutcome;
 use crate::profiles::{ProfilesDatabase, load_profiles};
 use crate::fetch::async_download::{spawn_workers, DownloadJob, DownloadEvent};
+use crate::fetch::progress::TransferStats;
+use crate::fetch::fetch_from_api::DependencyResolution;
+use crate::fetch::update_check::{spawn_update_check_workers, UpdateCheckJob, UpdateCheckResult};
 use crate::fetch::search_provider::{UnifiedSearchResult, SearchRequest, ContentType, ContentSearchProvider};
 use crate::fetch::single_mod_search::ModSearchProvider;
 use crate::fetch::single_datapack_search::DatapackSearchProvider;
+use crate::pack_formats::{ExportReport, ImportedPack, ManualDownload};
 use crate::paths_vars::PATHS;
 use super::tui_theme::{self, tui_tab, tui_dim};
 
 // Import our newly extracted UI modules and types
 pub(crate) use super::types::{
-    ModStatus, UiModInfo, DeletionConfirmation, DownloadSource, AppTab, SearchSource, SearchState
+    ModStatus, UiModInfo, DeletionConfirmation, DownloadSource, AppTab, SearchSource, SearchState, ExportPackState,
+    PreparedDownloads, ProfileDepsReport,
 };
 
 pub struct ModUpdaterApp {
@@ -37,6 +44,13 @@ pub struct ModUpdaterApp {
     pub(crate) mods: IndexMap<String, UiModInfo>,
     pub(crate) tx_jobs: Sender<DownloadJob>,
     pub(crate) rx_events: Receiver<DownloadEvent>,
+    // Lotes de descarga que pasan por la identificación por hash antes de llegar a tx_jobs
+    pub(crate) tx_identify_jobs: Sender<Vec<DownloadJob>>,
+
+    // --- Update Check State ---
+    pub(crate) tx_update_checks: Sender<Vec<UpdateCheckJob>>,
+    pub(crate) rx_update_results: Receiver<UpdateCheckResult>,
+    pub(crate) update_checks_pending: usize,
     
     // --- Async Read State ---
     pub(crate) tx_read_jobs: Sender<ReadJob>,
@@ -54,6 +68,10 @@ pub struct ModUpdaterApp {
     pub(crate) loaders: Vec<String>,
     pub(crate) selected_loader: String,
     pub(crate) selected_modpack_ui: Option<String>,
+    // Al abrir un modpack: proponer loader en cuanto terminen de leerse sus jars
+    pub(crate) loader_suggestion_pending: bool,
+    // Resultado de la última validación de dependencias del explorador; Some = panel visible
+    pub(crate) dependency_report: Option<Vec<DependencyIssue>>,
     
     // --- Download Dialog State ---
     pub(crate) download_confirmation_name: Option<String>,
@@ -61,6 +79,18 @@ pub struct ModUpdaterApp {
     
     // --- Create Profile Dialog State ---
     pub(crate) create_profile_modal_name: Option<String>,
+    // Ruta del pack (.mrpack) a importar; Some = modal abierto
+    pub(crate) import_pack_path: Option<String>,
+    // Importación en curso: (ruta del pack, resultado) del hilo que lo importa
+    pub(crate) import_running: bool,
+    pub(crate) tx_import_result: Sender<(String, Result<ImportedPack, String>)>,
+    pub(crate) rx_import_result: Receiver<(String, Result<ImportedPack, String>)>,
+    // Exportación de perfiles: el modal y (archivo, resultado) del hilo que escribe el pack
+    pub(crate) export_pack_state: Option<ExportPackState>,
+    // Archivos del último pack importado que hay que descargar a mano; no vacío = ventana abierta
+    pub(crate) manual_downloads: Vec<ManualDownload>,
+    pub(crate) tx_export_result: Sender<(String, Result<ExportReport, String>)>,
+    pub(crate) rx_export_result: Receiver<(String, Result<ExportReport, String>)>,
     
     // --- Modpacks State ---
     pub(crate) active_modpack: Option<String>,
@@ -68,6 +98,8 @@ pub struct ModUpdaterApp {
     
     // --- Global Download State ---
     pub(crate) active_downloads: HashMap<String, ModStatus>,
+    // Bytes / velocidad / ETA agregados del lote de descargas en curso
+    pub(crate) transfer_stats: TransferStats,
 
     // --- Search State ---
     pub(crate) search_state: SearchState,
@@ -78,14 +110,18 @@ pub struct ModUpdaterApp {
     // (main_job, resolve_deps, existing_project_ids)
     pub(crate) tx_prepare_downloads: Sender<(DownloadJob, bool, std::collections::HashSet<String>)>,
     // Returns final list of jobs (main + deps)
-    pub(crate) rx_prepare_downloads_result: Receiver<Vec<DownloadJob>>,
+    pub(crate) rx_prepare_downloads_result: Receiver<PreparedDownloads>,
     pub(crate) pending_downloads: Option<Vec<crate::ui::types::DuplicateResolution>>,
+    // Incompatibilidades detectadas al resolver las dependencias de `pending_downloads`
+    pub(crate) download_conflicts: Vec<crate::fetch::fetch_from_api::DependencyConflict>,
 
     // --- Profile Dependency Resolution ---
     // (project_id, profile_name, version, loader, existing_project_ids)
     pub(crate) tx_resolve_profile_deps: Sender<(String, String, String, String, std::collections::HashSet<String>)>,
-    // Returns (profile_name, Vec<(dep_name, dep_filename, dep_project_id, dep_slug)>)
-    pub(crate) rx_profile_deps_resolved: Receiver<(String, Vec<(String, String, String, String)>)>,
+    // Returns (profile_name, obligatorias + opcionales + incompatibilidades)
+    pub(crate) rx_profile_deps_resolved: Receiver<(String, DependencyResolution)>,
+    // Opcionales e incompatibilidades de la última resolución, mostradas en el perfil
+    pub(crate) profile_deps_report: Option<ProfileDepsReport>,
 
     // --- Search Dep-Name Preview Resolution ---
     // (slug, project_id, version, loader, cf_key)
@@ -98,6 +134,9 @@ pub struct ModUpdaterApp {
     pub(crate) world_datapacks: IndexMap<String, IndexMap<String, DatapackInfo>>,
     pub(crate) tx_dp_read_jobs: Sender<DatapackReadJob>,
     pub(crate) rx_dp_read_events: Receiver<DatapackReadEvent>,
+    // ZIP/UNZIP de datapacks: (mundo, key del pack original, resultado) del hilo que lo hace
+    pub(crate) tx_dp_repack_result: Sender<(String, String, Result<RepackOutcome, String>)>,
+    pub(crate) rx_dp_repack_result: Receiver<(String, String, Result<RepackOutcome, String>)>,
     pub(crate) datapacks_loaded: bool,
 
     // --- Version Fetching State ---
@@ -140,28 +179,32 @@ impl ModUpdaterApp {
         if let Some(ref pack_name) = active_modpack {
             let pack_folder = PATHS.modpacks_folder.join(pack_name);
             if let Ok(entries) = std::fs::read_dir(&pack_folder) {
-                let mut entries_vec: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-                entries_vec.sort_by_key(|e| e.file_name());
-
-                for entry in entries_vec {
-                    let path = entry.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("jar") {
-                        let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                        let (file_size, file_mtime) = if let Ok(meta) = std::fs::metadata(&path) {
-                            (meta.len(), crate::local_mods_ops::scanner::get_file_mtime(&meta))
-                        } else { (0, 0) };
-
-                        let mut loaded = false;
-                        if let Some(cached) = crate::local_mods_ops::cache::get_mod(&filename) {
-                            if cached.file_size_bytes == Some(file_size) && cached.file_mtime_secs == Some(file_mtime) {
-                                ui_mods.insert(cached.key.clone(), UiModInfo::from(cached));
-                                loaded = true;
-                            }
-                        }
-                        if !loaded {
-                            let _ = tx_read_jobs.send(ReadJob { file_path: path });
+                let mut jars: Vec<(String, std::path::PathBuf)> = entries.filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("jar"))
+                    .map(|path| (path.file_name().unwrap_or_default().to_string_lossy().to_string(), path))
+                    .collect();
+                jars.sort();
+                // Una sola transacción de lectura para todo el modpack
+                let mut cache = crate::local_mods_ops::cache::get_mods(jars.iter().map(|(filename, _)| filename.as_str()));
+
+                for (filename, path) in jars {
+                    let (file_size, file_mtime) = if let Ok(meta) = std::fs::metadata(&path) {
+                        (meta.len(), crate::local_mods_ops::scanner::get_file_mtime(&meta))
+                    } else { (0, 0) };
+
+                    let mut loaded = false;
+                    if let Some(cached) = cache.remove(&filename) {
+                        if cached.file_size_bytes == Some(file_size) && cached.file_mtime_secs == Some(file_mtime)
+                            && !cached.loaders.is_empty() && cached.bundled.is_some()
+                        {
+                            ui_mods.insert(cached.key.clone(), UiModInfo::from(cached));
+                            loaded = true;
                         }
                     }
+                    if !loaded {
+                        let _ = tx_read_jobs.send(ReadJob { file_path: path });
+                    }
                 }
             }
         }
@@ -175,6 +218,49 @@ impl ModUpdaterApp {
         // Spawn read workers
         spawn_read_workers(workers, rx_read_jobs, tx_read_events.clone());
 
+        // --- Hash Identification for Download Batches ---
+        let (tx_identify_jobs, rx_identify_jobs) = unbounded::<Vec<DownloadJob>>();
+        {
+            let tx_jobs = tx_jobs.clone();
+            thread::spawn(move || {
+                while let Ok(mut jobs) = rx_identify_jobs.recv() {
+                    crate::fetch::async_download::identify_jobs(&mut jobs);
+                    crate::fetch::async_download::prefetch_jobs(&mut jobs);
+                    for job in jobs {
+                        let _ = tx_jobs.send(job);
+                    }
+                }
+            });
+        }
+
+        // --- Update Check Workers (identificación por hash del lote, consulta en bloque + pool para el resto) ---
+        let (tx_update_checks, rx_update_checks) = unbounded::<Vec<UpdateCheckJob>>();
+        let (tx_update_results, rx_update_results) = unbounded::<UpdateCheckResult>();
+        {
+            let (tx_check_jobs, rx_check_jobs) = unbounded::<UpdateCheckJob>();
+            let tx_bulk_results = tx_update_results.clone();
+            spawn_update_check_workers(workers, rx_check_jobs, tx_update_results);
+            thread::spawn(move || {
+                while let Ok(mut jobs) = rx_update_checks.recv() {
+                    {
+                        let mut mods: Vec<&mut crate::local_mods_ops::ModInfo> = jobs.iter_mut().map(|j| &mut j.modinfo).collect();
+                        crate::fetch::async_download::identify_mods(&mut mods);
+                    }
+                    // Los reconocidos por hash se resuelven en bloque; el resto va al pool uno a uno
+                    let (results, pending) = crate::fetch::update_check::check_updates_by_hash(jobs);
+                    for result in results {
+                        let _ = tx_bulk_results.send(result);
+                    }
+                    for job in pending {
+                        let _ = tx_check_jobs.send(job);
+                    }
+                }
+            });
+        }
+
+        let (tx_export_result, rx_export_result) = unbounded::<(String, Result<ExportReport, String>)>();
+        let (tx_import_result, rx_import_result) = unbounded::<(String, Result<ImportedPack, String>)>();
+
         // Search Channel
         let (tx_search, rx_search_req) = unbounded::<(SearchRequest, SearchSource)>();
         let (tx_search_res, rx_search) = unbounded();
@@ -214,12 +300,12 @@ impl ModUpdaterApp {
 
         // --- Dependency Resolver for Downloads ---
         let (tx_prepare_downloads, rx_prepare_downloads) = unbounded::<(DownloadJob, bool, std::collections::HashSet<String>)>();
-        let (tx_download_resolutions, rx_prepare_downloads_result) = unbounded::<Vec<DownloadJob>>();
+        let (tx_download_resolutions, rx_prepare_downloads_result) = unbounded::<PreparedDownloads>();
 
         {
             thread::spawn(move || {
                 while let Ok((main_job, resolve_deps, existing_ids)) = rx_prepare_downloads.recv() {
-                    let mut final_jobs = vec![main_job.clone()];
+                    let mut prepared = PreparedDownloads { jobs: vec![main_job.clone()], ..Default::default() };
                     
                     if resolve_deps {
                         let project_id_opt = main_job.modinfo.confirmed_project_id.as_ref()
@@ -229,7 +315,7 @@ impl ModUpdaterApp {
                             println!("🔍 Resolviendo dependencias para {}...", main_job.key);
                             let cf_key = crate::fetch::cf_api_key();
                             
-                            let dep_infos = crate::fetch::fetch_from_api::resolve_all_dependencies(
+                            let resolution = crate::fetch::fetch_from_api::resolve_all_dependencies(
                                 project_id,
                                 &main_job.raw_game_version, // Use actual Minecraft version here
                                 &main_job.selected_loader,
@@ -237,7 +323,7 @@ impl ModUpdaterApp {
                                 &existing_ids,
                             );
 
-                            for dep_info in dep_infos {
+                            let to_job = |dep_info: crate::fetch::fetch_from_api::ModDownloadInfo| {
                                 let dep_mod_info = crate::local_mods_ops::ModInfo {
                                     key: dep_info.filename.clone(),
                                     name: dep_info.name.clone(), // Use proper generic display name
@@ -250,9 +336,10 @@ impl ModUpdaterApp {
                                     file_mtime_secs: None,
                                     depends: None,
                                     has_local_icon: false,
+                                    ..Default::default()
                                 };
                                 
-                                let job = crate::fetch::async_download::DownloadJob {
+                                crate::fetch::async_download::DownloadJob {
                                     key: dep_info.filename.clone(),
                                     modinfo: dep_mod_info,
                                     output_folder: main_job.output_folder.clone(),
@@ -262,21 +349,23 @@ impl ModUpdaterApp {
                                     replaces_filename: None,
                                     raw_game_version: main_job.raw_game_version.clone(),
                                     pre_resolved: Some(dep_info),
-                                };
-                                final_jobs.push(job);
-                            }
+                                }
+                            };
+                            prepared.jobs.extend(resolution.required.into_iter().map(to_job));
+                            prepared.optional = resolution.optional.into_iter().map(to_job).collect();
+                            prepared.conflicts = resolution.conflicts;
                         }
                     }
 
                     // Send the fully resolved list to the UI
-                    let _ = tx_download_resolutions.send(final_jobs);
+                    let _ = tx_download_resolutions.send(prepared);
                 }
             });
         }
 
         // --- Profile Dependency Resolver ---
         let (tx_resolve_profile_deps, rx_resolve_profile_deps) = unbounded::<(String, String, String, String, std::collections::HashSet<String>)>();
-        let (tx_profile_deps_res, rx_profile_deps_resolved) = unbounded::<(String, Vec<(String, String, String, String)>)>();
+        let (tx_profile_deps_res, rx_profile_deps_resolved) = unbounded::<(String, DependencyResolution)>();
 
         {
             thread::spawn(move || {
@@ -284,7 +373,7 @@ impl ModUpdaterApp {
                     println!("🔍 Resolviendo deps para perfil '{}' (mod: {})...", profile_name, project_id);
                     let cf_key = crate::fetch::cf_api_key();
 
-                    let dep_infos = crate::fetch::fetch_from_api::resolve_all_dependencies(
+                    let resolution = crate::fetch::fetch_from_api::resolve_all_dependencies(
                         &project_id,
                         &version,
                         &loader,
@@ -292,12 +381,7 @@ impl ModUpdaterApp {
                         &existing_ids,
                     );
 
-                    // Send (name, filename, project_id, slug) so the UI can deduplicate against slug-based detected_project_id
-                    let deps: Vec<(String, String, String, String)> = dep_infos.into_iter()
-                        .map(|d| (d.name, d.filename, d.project_id, d.slug))
-                        .collect();
-
-                    let _ = tx_profile_deps_res.send((profile_name, deps));
+                    let _ = tx_profile_deps_res.send((profile_name, resolution));
                 }
             });
         }
@@ -318,6 +402,7 @@ impl ModUpdaterApp {
         // --- Datapack Read Workers ---
         let (tx_dp_read_jobs, rx_dp_read_jobs) = unbounded::<DatapackReadJob>();
         let (tx_dp_read_events_send, rx_dp_read_events) = unbounded::<DatapackReadEvent>();
+        let (tx_dp_repack_result, rx_dp_repack_result) = unbounded::<(String, String, Result<RepackOutcome, String>)>();
         {
             let dp_workers = crate::common::calculate_worker_count(10);
             spawn_datapack_read_workers(dp_workers, rx_dp_read_jobs, tx_dp_read_events_send);
@@ -349,6 +434,10 @@ impl ModUpdaterApp {
             selected_mc_version: selected_mc_version.clone(), 
             tx_jobs, 
             rx_events,
+            tx_identify_jobs,
+            tx_update_checks,
+            rx_update_results,
+            update_checks_pending: 0,
             tx_read_jobs,
             rx_read_events, 
             deletion_confirmation: DeletionConfirmation::None, 
@@ -363,6 +452,8 @@ impl ModUpdaterApp {
             profile_mods_pending_deletion: HashSet::new(),
 
             selected_modpack_ui: None,
+            loader_suggestion_pending: false,
+            dependency_report: None,
 
             cached_modpacks: crate::local_mods_ops::list_modpacks(),
 
@@ -379,8 +470,17 @@ impl ModUpdaterApp {
             download_confirmation_name: None,
             download_source: DownloadSource::None,
             create_profile_modal_name: None,
+            import_pack_path: None,
+            import_running: false,
+            tx_import_result,
+            rx_import_result,
+            export_pack_state: None,
+            manual_downloads: Vec::new(),
+            tx_export_result,
+            rx_export_result,
             loaded_icons: HashMap::new(),
             active_downloads: HashMap::new(),
+            transfer_stats: TransferStats::new(),
             
             search_state: SearchState {
                 loader: "Fabric".to_string(), // Initial default
@@ -392,8 +492,10 @@ impl ModUpdaterApp {
             tx_prepare_downloads,
             rx_prepare_downloads_result,
             pending_downloads: None,
+            download_conflicts: Vec::new(),
             tx_resolve_profile_deps,
             rx_profile_deps_resolved,
+            profile_deps_report: None,
             tx_fetch_dep_names,
             rx_dep_names_result,
 
@@ -402,6 +504,8 @@ impl ModUpdaterApp {
             world_datapacks: IndexMap::new(),
             tx_dp_read_jobs,
             rx_dp_read_events,
+            tx_dp_repack_result,
+            rx_dp_repack_result,
             tx_fetch_versions,
             rx_versions_result,
             datapacks_loaded: false,
@@ -461,11 +565,22 @@ impl eframe::App for ModUpdaterApp {
         self.process_download_events();
         self.process_read_events();
         self.process_datapack_events();
+        self.process_update_check_events();
+        self.process_export_events();
+        self.process_import_events();
+
+        // egui solo repinta con input: mientras haya descargas, refrescar al ritmo de los eventos de progreso
+        if self.active_downloads.values().any(|s| matches!(s, ModStatus::Resolving | ModStatus::Downloading(_))) {
+            ctx.request_repaint_after(crate::fetch::progress::PROGRESS_INTERVAL);
+        }
 
         // --- Modals ---
         self.render_deletion_modal(ctx);
         self.render_download_modal(ctx);
         self.render_create_profile_modal(ctx);
+        self.render_import_pack_modal(ctx);
+        self.render_export_pack_modal(ctx);
+        self.render_manual_downloads_modal(ctx);
         self.render_duplicate_resolution_modal(ctx);
 
         // --- Background Search / Dependency Events ---
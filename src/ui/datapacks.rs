@@ -1,7 +1,9 @@
 use eframe::egui::{self, ScrollArea};
 use indexmap::IndexMap;
 
-use crate::local_datapacks_ops::{list_worlds, DatapackReadJob};
+use crate::local_datapacks_ops::{is_datapack_path, list_worlds, DatapackReadJob};
+use crate::local_datapacks_ops::fs_ops::{unzip_datapack, zip_datapack_folder};
+use crate::local_datapacks_ops::pack_format_table::PackKind;
 use crate::paths_vars::PATHS;
 use super::tui_theme::{self, tui_button, tui_heading, tui_dim};
 
@@ -13,13 +15,13 @@ impl super::app::ModUpdaterApp {
         for w in &self.cached_worlds {
             self.world_datapacks.entry(w.clone()).or_insert_with(IndexMap::new);
         }
-        // Enqueue async read jobs for all .zip files in each world
+        // Enqueue async read jobs for all datapacks (.zip files and folders with pack.mcmeta) in each world
         for world in &self.cached_worlds {
             let dp_folder = PATHS.saves_folder.join(world).join("datapacks");
             if let Ok(entries) = std::fs::read_dir(&dp_folder) {
                 for entry in entries.filter_map(|e| e.ok()) {
                     let path = entry.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+                    if is_datapack_path(&path) {
                         let _ = self.tx_dp_read_jobs.send(DatapackReadJob {
                             file_path: path,
                             world_name: world.clone(),
@@ -49,6 +51,7 @@ impl super::app::ModUpdaterApp {
             return;
         }
 
+        let mut repack: Option<(String, String, bool)> = None;
         ScrollArea::vertical().id_salt("datapacks_scroll").show(ui, |ui| {
             let mut worlds_sorted = self.cached_worlds.clone();
             worlds_sorted.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
@@ -123,18 +126,29 @@ impl super::app::ModUpdaterApp {
                                                 .color(tui_theme::TEXT_DIM)
                                                 .size(11.0));
                                         }
+                                        if dp.is_folder {
+                                            ui.label(egui::RichText::new("[carpeta]")
+                                                .family(egui::FontFamily::Monospace)
+                                                .color(tui_theme::TEXT_DIM)
+                                                .size(11.0));
+                                        }
                                     });
 
                                     // Info line: pack_format → MC version
                                     let mut info_parts: Vec<String> = Vec::new();
-                                    if let Some(pf) = dp.pack_format {
+                                    if dp.pack_format.is_some() || dp.supported_formats.is_some() {
+                                        let pf = dp.pack_format.map(|pf| pf.to_string()).unwrap_or_else(|| "?".to_string());
                                         let mc = dp.mc_version.as_deref().unwrap_or("?");
-                                        info_parts.push(format!("pack{} → MC {}", pf, mc));
+                                        let kind = if dp.kind == PackKind::Resource { "RP " } else { "" };
+                                        info_parts.push(format!("{}pack{} → MC {}", kind, pf, mc));
                                     }
-                                    if let Some((min, max)) = dp.supported_formats {
-                                        if min != max {
-                                            info_parts.push(format!("formatos {}-{}", min, max));
-                                        }
+                                    if let Some(range) = dp.supported_formats
+                                        && !range.is_single()
+                                    {
+                                        info_parts.push(format!("formatos {}", range));
+                                    }
+                                    if !dp.overlays.is_empty() {
+                                        info_parts.push(format!("overlays: {}", dp.overlays.len()));
                                     }
                                     if let Some(slug) = &dp.detected_project_id {
                                         info_parts.push(format!("slug: {}", slug));
@@ -148,16 +162,29 @@ impl super::app::ModUpdaterApp {
                                         info_parts.push(size_str);
                                     }
                                     if !info_parts.is_empty() {
-                                        tui_dim(ui, &format!("├── {}", info_parts.join("  |  ")));
+                                        let info = tui_dim(ui, &format!("├── {}", info_parts.join("  |  ")));
+                                        if !dp.mc_versions.is_empty() {
+                                            info.on_hover_text(format!("MC: {}", dp.mc_versions.join(", ")));
+                                        }
                                     }
                                 });
 
                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                    if super::tui_theme::tui_button_c(ui, "DEL", super::tui_theme::NEON_RED).clicked() {
-                                        self.deletion_confirmation = crate::ui::types::DeletionConfirmation::Datapack(
-                                            world.clone(),
-                                            key.clone()
-                                        );
+                                    if dp.is_folder {
+                                        // Las carpetas son del usuario: la app no las borra
+                                        if tui_button(ui, "ZIP").on_hover_text("Empaquetar en un .zip (la carpeta se conserva)").clicked() {
+                                            repack = Some((world.clone(), key.clone(), true));
+                                        }
+                                    } else {
+                                        if super::tui_theme::tui_button_c(ui, "DEL", super::tui_theme::NEON_RED).clicked() {
+                                            self.deletion_confirmation = crate::ui::types::DeletionConfirmation::Datapack(
+                                                world.clone(),
+                                                key.clone()
+                                            );
+                                        }
+                                        if tui_button(ui, "UNZIP").on_hover_text("Descomprimir en una carpeta para editarlo (sustituye al .zip)").clicked() {
+                                            repack = Some((world.clone(), key.clone(), false));
+                                        }
                                     }
                                 });
                             });
@@ -167,5 +194,21 @@ impl super::app::ModUpdaterApp {
                 });
             }
         });
+
+        if let Some((world, key, is_folder)) = repack {
+            self.start_datapack_repack(world, key, is_folder);
+        }
+    }
+
+    /// Comprime una carpeta de datapack o descomprime un .zip en un hilo aparte;
+    /// el resultado llega por `rx_dp_repack_result`.
+    fn start_datapack_repack(&mut self, world: String, key: String, is_folder: bool) {
+        let path = PATHS.saves_folder.join(&world).join("datapacks").join(&key);
+        let tx = self.tx_dp_repack_result.clone();
+        self.status_msg = format!("{} '{}'...", if is_folder { "Comprimiendo" } else { "Descomprimiendo" }, key);
+        std::thread::spawn(move || {
+            let result = if is_folder { zip_datapack_folder(&path) } else { unzip_datapack(&path) };
+            let _ = tx.send((world, key, result));
+        });
     }
 }
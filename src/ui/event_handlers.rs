@@ -1,8 +1,9 @@
-use crate::local_mods_ops::ReadEvent;
-use crate::local_datapacks_ops::{DatapackReadEvent, DatapackInfo};
+use crate::local_mods_ops::{list_modpacks, ReadEvent};
+use crate::local_datapacks_ops::{DatapackReadEvent, DatapackReadJob, DatapackInfo};
 use crate::fetch::async_download::DownloadEvent;
+use crate::local_mods_ops::UpdateState;
 use crate::profiles::save_profiles;
-use crate::ui::types::{ModStatus, UiModInfo, DownloadAction, DuplicateResolution};
+use crate::ui::types::{ModStatus, UiModInfo, DownloadAction, DownloadSource, DuplicateResolution, ProfileDepsReport};
 use indexmap::IndexMap;
 
 impl super::app::ModUpdaterApp {
@@ -19,13 +20,19 @@ impl super::app::ModUpdaterApp {
                     if let Some(m) = self.mods.get_mut(&key) { m.status = ModStatus::Idle; }
                 }
                 DownloadEvent::Started { key } => {
+                    self.transfer_stats.start(&key);
                     self.active_downloads.insert(key.clone(), ModStatus::Downloading(0.0));
                     if let Some(m) = self.mods.get_mut(&key) { m.status = ModStatus::Downloading(0.0); }
                 }
-                DownloadEvent::Progress(key, p) => {
+                DownloadEvent::Progress { key, downloaded, total } => {
+                    self.transfer_stats.record(&key, downloaded, total);
+                    let p = total.filter(|t| *t > 0).map_or(0.0, |t| (downloaded as f32 / t as f32).min(1.0));
                     self.active_downloads.insert(key.clone(), ModStatus::Downloading(p));
                     if let Some(m) = self.mods.get_mut(&key) { m.status = ModStatus::Downloading(p); m.progress = p; }
                 }
+                DownloadEvent::Retrying { key, attempt, max, reason } => {
+                    self.status_msg = format!("Reintentando '{}' ({}/{}): {}", key, attempt, max, reason);
+                }
                 DownloadEvent::ResolvedInfo { key, confirmed_project_id, version_remote } => {
                     if let Some(m) = self.mods.get_mut(&key) {
                         m.inner.confirmed_project_id = confirmed_project_id.clone();
@@ -35,10 +42,12 @@ impl super::app::ModUpdaterApp {
                     }
                 }
                 DownloadEvent::Done { key } => {
+                    self.transfer_stats.finish(&key);
                     self.active_downloads.insert(key.clone(), ModStatus::Done);
                     if let Some(m) = self.mods.get_mut(&key) { m.status = ModStatus::Done; m.progress = 1.0; }
                 }
                 DownloadEvent::Error { key, msg } => {
+                    self.transfer_stats.finish(&key);
                     self.active_downloads.insert(key.clone(), ModStatus::Error(msg.clone()));
                     // Log error to status if urgent, otherwise just mark mod
                     if let Some(m) = self.mods.get_mut(&key) { m.status = ModStatus::Error(msg); }
@@ -47,18 +56,107 @@ impl super::app::ModUpdaterApp {
         }
     }
 
+    pub(crate) fn process_export_events(&mut self) {
+        for (output, result) in self.rx_export_result.try_iter() {
+            self.export_pack_state = None;
+            self.status_msg = match result {
+                Ok(report) => {
+                    for key in &report.missing {
+                        println!("⚠️ Sin archivo para '{}': no se incluye en el pack", key);
+                    }
+                    format!(
+                        "Pack exportado en '{}': {} referenciados, {} incluidos, {} sin resolver",
+                        output, report.indexed.len(), report.bundled.len(), report.missing.len()
+                    )
+                }
+                Err(e) => format!("Error al exportar '{}': {}", output, e),
+            };
+        }
+    }
+
+    /// Guarda el perfil de un pack importado y lanza sus descargas (ya fijadas y con hash) en el pool.
+    pub(crate) fn process_import_events(&mut self) {
+        for (path, result) in self.rx_import_result.try_iter() {
+            self.import_running = false;
+            let imported = match result {
+                Ok(imported) => imported,
+                Err(e) => {
+                    self.status_msg = format!("Error al importar '{}': {}", path, e);
+                    continue;
+                }
+            };
+            for warning in &imported.warnings {
+                println!("⚠️ {}", warning);
+            }
+            self.manual_downloads = imported.manual_downloads.clone();
+
+            let name = imported.profile.name.clone();
+            let jobs = imported.download_jobs();
+            let count = jobs.len();
+            self.profiles_db.add_profile(imported.profile);
+            save_profiles(&self.profiles_db);
+            self.selected_profile_name = Some(name.clone());
+            self.cached_modpacks = list_modpacks();
+
+            for job in jobs {
+                self.active_downloads.insert(job.key.clone(), ModStatus::Resolving);
+                let _ = self.tx_jobs.send(job);
+            }
+            // Abrir el modal de descarga solo para ver el progreso
+            self.download_confirmation_name = Some(name.clone());
+            self.download_source = DownloadSource::Import(name.clone());
+            self.status_msg = format!(
+                "Pack '{}' importado: {} descargas, {} manuales, {} overrides, {} avisos",
+                name, count, imported.manual_downloads.len(), imported.overrides_extracted, imported.warnings.len()
+            );
+        }
+    }
+
+    pub(crate) fn process_update_check_events(&mut self) {
+        for result in self.rx_update_results.try_iter() {
+            self.update_checks_pending = self.update_checks_pending.saturating_sub(1);
+
+            if let Some(m) = self.mods.get_mut(&result.key) {
+                m.inner.update_state = Some(result.state);
+                // Solo los desactualizados quedan marcados para descargar
+                m.inner.selected = result.state == UpdateState::Outdated;
+
+                if let Some(latest) = &result.latest {
+                    m.inner.confirmed_project_id = Some(latest.project_id.clone());
+                    m.inner.version_remote = Some(latest.version_remote.clone());
+                    m.inner.remote_date = Some(latest.date_published.clone());
+                    crate::local_mods_ops::cache::update_remote_check(
+                        &result.key,
+                        m.inner.confirmed_project_id.clone(),
+                        m.inner.version_remote.clone(),
+                        m.inner.remote_date.clone(),
+                    );
+                }
+            }
+
+            if self.update_checks_pending == 0 {
+                let count = |state: UpdateState| self.mods.values().filter(|m| m.update_state == Some(state)).count();
+                self.status_msg = format!(
+                    "Comprobación terminada: {} desactualizados, {} al día, {} sin versión para {} [{}]",
+                    count(UpdateState::Outdated), count(UpdateState::UpToDate), count(UpdateState::Unavailable),
+                    self.selected_mc_version, self.selected_loader,
+                );
+            }
+        }
+    }
+
     pub(crate) fn process_read_events(&mut self) {
         for ev in self.rx_read_events.try_iter() {
             match ev {
                 ReadEvent::Done { info } => {
                     let key = info.key.clone();
                     if let Some(placeholder) = self.mods.get_mut(&key) {
-                        placeholder.inner = info;
+                        placeholder.inner = *info;
                         placeholder.status = ModStatus::Idle;
                     } else {
                         // Edge case: Mod wasn't in placeholder (maybe new file appeared?)
                         // Insert it now
-                        self.mods.insert(key, UiModInfo::from(info));
+                        self.mods.insert(key, UiModInfo::from(*info));
                     }
                 }
                 ReadEvent::Error { path, msg } => {
@@ -71,6 +169,7 @@ impl super::app::ModUpdaterApp {
                 }
             }
         }
+        self.suggest_loader_if_loaded();
     }
 
     pub(crate) fn process_datapack_events(&mut self) {
@@ -81,7 +180,7 @@ impl super::app::ModUpdaterApp {
                     self.world_datapacks
                         .entry(world_name)
                         .or_insert_with(IndexMap::new)
-                        .insert(key, info);
+                        .insert(key, *info);
                 }
                 DatapackReadEvent::Error { world_name, path, msg } => {
                     println!("Error reading datapack {:?}: {}", path, msg);
@@ -89,6 +188,7 @@ impl super::app::ModUpdaterApp {
                     let fallback = DatapackInfo {
                         key: filename.clone(),
                         name: filename.clone(),
+                        is_folder: path.is_dir(),
                         selected: true,
                         ..Default::default()
                     };
@@ -99,6 +199,26 @@ impl super::app::ModUpdaterApp {
                 }
             }
         }
+
+        for (world_name, key, result) in self.rx_dp_repack_result.try_iter() {
+            self.status_msg = match result {
+                Ok(outcome) => {
+                    if outcome.source_removed
+                        && let Some(packs) = self.world_datapacks.get_mut(&world_name)
+                    {
+                        packs.shift_remove(&key);
+                    }
+                    let new_name = outcome.new_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    let _ = self.tx_dp_read_jobs.send(DatapackReadJob { file_path: outcome.new_path, world_name });
+                    if outcome.source_removed {
+                        format!("Datapack '{}' convertido en '{}'.", key, new_name)
+                    } else {
+                        format!("Creado '{}'. '{}' sigue en la carpeta: Minecraft cargará los dos hasta que quites uno.", new_name, key)
+                    }
+                }
+                Err(e) => format!("Error al convertir '{}': {}", key, e),
+            };
+        }
     }
 
     pub(crate) fn process_search_events(&mut self) {
@@ -132,15 +252,18 @@ impl super::app::ModUpdaterApp {
 
         // --- Phase 2: Handle Dependency Processing Results ---
         // Converts the returned list of DownloadJobs into DuplicateResolutions for the popup modal
-        while let Ok(jobs) = self.rx_prepare_downloads_result.try_recv() {
+        while let Ok(prepared) = self.rx_prepare_downloads_result.try_recv() {
             let mut resolutions = Vec::new();
-            for job in jobs {
+            let optional_jobs = prepared.optional.into_iter().map(|job| (job, true));
+            for (job, optional) in prepared.jobs.into_iter().map(|job| (job, false)).chain(optional_jobs) {
                 let pid1 = job.modinfo.confirmed_project_id.clone();
                 let pid2 = job.modinfo.detected_project_id.clone();
                 
                 let mut existing_filename = None;
                 let mut existing_version = None;
-                let mut action = DownloadAction::Install;
+                // Las opcionales son sugerencias: solo se instalan si se marcan.
+                // Con incompatibilidades, nada se instala por defecto.
+                let mut action = if optional || !prepared.conflicts.is_empty() { DownloadAction::Skip } else { DownloadAction::Install };
                 
                 // Compare with local mods to find duplicates
                 if pid1.is_some() || pid2.is_some() {
@@ -161,8 +284,10 @@ impl super::app::ModUpdaterApp {
                     existing_version,
                     action,
                     status: ModStatus::Idle,
+                    optional,
                 });
             }
+            self.download_conflicts = prepared.conflicts;
             // Triggers the modal open because pending_downloads is now Some(_)
             self.pending_downloads = Some(resolutions);
         }
@@ -176,18 +301,31 @@ impl super::app::ModUpdaterApp {
         }
 
         // --- Profile Dependency Resolution Results Poll ---
-        while let Ok((profile_name, deps)) = self.rx_profile_deps_resolved.try_recv() {
+        while let Ok((profile_name, resolution)) = self.rx_profile_deps_resolved.try_recv() {
             if let Some(profile) = self.profiles_db.get_profile_mut(&profile_name) {
-                for (dep_name, dep_filename, dep_project_id, dep_slug) in deps {
-                    if !profile.contains_mod(&dep_filename, &dep_project_id, &dep_slug) {
+                // Se comparan también los slugs: los detected_project_id locales suelen serlo
+                for dep in resolution.required {
+                    if !profile.contains_mod(&dep.filename, &dep.project_id, &dep.slug) {
                         profile.mods.insert(
-                            dep_filename.clone(),
-                            crate::local_mods_ops::ModInfo::from_dep(dep_filename, dep_name, dep_project_id, dep_slug),
+                            dep.filename.clone(),
+                            crate::local_mods_ops::ModInfo::from_dep(dep.filename, dep.name, dep.project_id, dep.slug),
                         );
                     }
                 }
+                let optional: Vec<_> = resolution.optional.into_iter()
+                    .filter(|d| !profile.contains_mod(&d.filename, &d.project_id, &d.slug))
+                    .collect();
                 save_profiles(&self.profiles_db);
-                self.status_msg = format!("Dependencias añadidas al perfil '{}'.", profile_name);
+                self.status_msg = if resolution.conflicts.is_empty() {
+                    format!("Dependencias añadidas al perfil '{}'.", profile_name)
+                } else {
+                    format!("Dependencias añadidas al perfil '{}', con {} incompatibilidades.", profile_name, resolution.conflicts.len())
+                };
+                self.profile_deps_report = if optional.is_empty() && resolution.conflicts.is_empty() {
+                    None
+                } else {
+                    Some(ProfileDepsReport { profile: profile_name, optional, conflicts: resolution.conflicts })
+                };
             }
         }
 
@@ -4,9 +4,11 @@ use indexmap::IndexMap;
 use crate::local_mods_ops::{list_modpacks, ModInfo};
 use crate::profiles::{Profile, save_profiles};
 use crate::fetch::async_download::DownloadJob;
+use crate::fetch::fetch_from_api::ModDownloadInfo;
+use crate::pack_formats::PackFormat;
 use crate::paths_vars::PATHS;
 use super::tui_theme::{self, tui_button_c, tui_separator, tui_dim, tui_number, tui_heading};
-use super::types::{DeletionConfirmation, DownloadSource, ModStatus, AppTab, DownloadAction};
+use super::types::{DeletionConfirmation, DownloadSource, ModStatus, AppTab, DownloadAction, ExportPackState};
 
 impl super::app::ModUpdaterApp {
     pub(crate) fn render_deletion_modal(&mut self, ctx: &egui::Context) {
@@ -91,8 +93,10 @@ impl super::app::ModUpdaterApp {
                                 }
                                 DeletionConfirmation::Datapack(world, key) => {
                                     let target_path = PATHS.saves_folder.join(&world).join("datapacks").join(&key);
-                                    if std::fs::remove_file(&target_path).is_ok()
-                                        || std::fs::remove_dir_all(&target_path).is_ok() {
+                                    // Las carpetas de datapack no las creó la app: no se borran de forma recursiva
+                                    if target_path.is_dir() {
+                                        self.status_msg = format!("'{}' es una carpeta: bórrala a mano si ya no la necesitas.", key);
+                                    } else if std::fs::remove_file(&target_path).is_ok() {
                                         
                                         // Update in-memory state
                                         if let Some(packs) = self.world_datapacks.get_mut(&world) {
@@ -123,40 +127,66 @@ impl super::app::ModUpdaterApp {
                 .resizable(true)
                 .open(&mut open)
                 .show(ctx, |ui| {
+                    let import_profile = match &self.download_source {
+                        DownloadSource::Import(profile_name) => Some(profile_name.clone()),
+                        _ => None,
+                    };
                     tui_dim(ui, "Carpeta del Modpack:");
-                    ui.text_edit_singleline(&mut name);
+                    if import_profile.is_some() {
+                        tui_number(ui, &name);
+                    } else {
+                        ui.text_edit_singleline(&mut name);
+                    }
                     
                     ui.add_space(8.0);
                     tui_separator(ui);
                     ui.add_space(5.0);
                     
-                    // 1. Selector de Loader
-                    ui.horizontal(|ui| {
-                        tui_dim(ui, "Loader:");
-                        egui::ComboBox::from_id_salt("loader-selector-modal")
-                            .selected_text(&self.selected_loader)
-                            .show_ui(ui, |ui| {
-                                for loader in &self.loaders {
-                                    ui.selectable_value(&mut self.selected_loader, loader.clone(), loader);
-                                }
+                    if let Some(profile_name) = &import_profile {
+                        // Las descargas de un pack importado ya están encoladas con la versión y el loader del pack
+                        if let Some(profile) = self.profiles_db.get_profile(profile_name) {
+                            ui.horizontal(|ui| {
+                                tui_dim(ui, "Loader:");
+                                tui_number(ui, profile.loader.as_deref().unwrap_or("?"));
+                                tui_dim(ui, "Version:");
+                                tui_number(ui, profile.game_version.as_deref().unwrap_or("?"));
                             });
+                        }
+                    } else {
+                        // 1. Selector de Loader
+                        ui.horizontal(|ui| {
+                            tui_dim(ui, "Loader:");
+                            egui::ComboBox::from_id_salt("loader-selector-modal")
+                                .selected_text(&self.selected_loader)
+                                .show_ui(ui, |ui| {
+                                    for loader in &self.loaders {
+                                        ui.selectable_value(&mut self.selected_loader, loader.clone(), loader);
+                                    }
+                                });
 
-                        // 2. Selector de Versión MC
-                        tui_dim(ui, "Version:");
-                        egui::ComboBox::from_id_salt("mc-version-box-modal")
-                            .selected_text(&self.selected_mc_version)
-                            .show_ui(ui, |ui| {
-                                for v in &self.mc_versions {
-                                    ui.selectable_value(&mut self.selected_mc_version, v.clone(), v);
-                                }
-                            ui.separator();
-                            ui.text_edit_singleline(&mut self.selected_mc_version);
+                            // 2. Selector de Versión MC
+                            tui_dim(ui, "Version:");
+                            egui::ComboBox::from_id_salt("mc-version-box-modal")
+                                .selected_text(&self.selected_mc_version)
+                                .show_ui(ui, |ui| {
+                                    for v in &self.mc_versions {
+                                        ui.selectable_value(&mut self.selected_mc_version, v.clone(), v);
+                                    }
+                                ui.separator();
+                                ui.text_edit_singleline(&mut self.selected_mc_version);
+                            });
                         });
-                    });
+                    }
 
                     ui.add_space(10.0);
 
                     ui.horizontal(|ui| {
+                        if import_profile.is_some() {
+                            if tui_button_c(ui, "CERRAR", tui_theme::NEON_GREEN).clicked() {
+                                close_requested = true;
+                            }
+                            return;
+                        }
                         if tui_button_c(ui, "CANCEL", tui_theme::NEON_RED).clicked() {
                             close_requested = true;
                         }
@@ -170,7 +200,7 @@ impl super::app::ModUpdaterApp {
                                 // Create dir
                                 let _ = std::fs::create_dir_all(&output_folder_path);
         
-                                let mut count = 0;
+                                let mut batch: Vec<DownloadJob> = Vec::new();
 
                                 match &self.download_source {
                                     DownloadSource::Explorer => {
@@ -187,13 +217,14 @@ impl super::app::ModUpdaterApp {
                                                     raw_game_version: self.selected_mc_version.clone(),
                                                     pre_resolved: None,
                                                 };
-                                                let _ = self.tx_jobs.send(job);
-                                                count += 1;
+                                                batch.push(job);
                                             }
                                         }
                                     },
                                     DownloadSource::Profile(profile_name) => {
                                         if let Some(profile) = self.profiles_db.get_profile(profile_name) {
+                                            // Los archivos fijados de un pack importado solo valen para su versión de MC
+                                            let pins_valid = profile.game_version.as_deref() == Some(self.selected_mc_version.as_str());
                                             for (k, m) in &profile.mods {
                                                 let job = DownloadJob {
                                                     key: k.clone(),
@@ -204,15 +235,17 @@ impl super::app::ModUpdaterApp {
                                                     content_type: crate::fetch::search_provider::ContentType::Mod,
                                                     replaces_filename: None,
                                                     raw_game_version: self.selected_mc_version.clone(),
-                                                    pre_resolved: None,
+                                                    pre_resolved: if pins_valid { ModDownloadInfo::from_pinned(m) } else { None },
                                                 };
-                                                let _ = self.tx_jobs.send(job);
-                                                count += 1;
+                                                batch.push(job);
                                             }
                                         }
                                     },
-                                    DownloadSource::None => {},
+                                    DownloadSource::Import(_) | DownloadSource::None => {},
                                 }
+                                let count = batch.len();
+                                // Identificación por hash antes de encolar (ver identify_jobs)
+                                let _ = self.tx_identify_jobs.send(batch);
                                 self.status_msg = format!("Iniciando descarga de {} mods en '{}'", count, name);
                                 self.cached_modpacks = list_modpacks();
                                 // Don't close — keep window open to show progress
@@ -280,6 +313,9 @@ impl super::app::ModUpdaterApp {
                             if err_count > 0 {
                                 tui_theme::tui_status(ui, &format!("{} errores", err_count), tui_theme::NEON_RED);
                             }
+                            if done_count + err_count < total {
+                                tui_dim(ui, &self.transfer_stats.summary(total));
+                            }
                         });
 
                         // Clear button when all done
@@ -287,6 +323,7 @@ impl super::app::ModUpdaterApp {
                             ui.add_space(4.0);
                             if tui_button_c(ui, "LIMPIAR", tui_theme::NEON_GREEN).clicked() {
                                 self.active_downloads.clear();
+                                self.transfer_stats.reset();
                             }
                         }
                     }
@@ -354,6 +391,208 @@ impl super::app::ModUpdaterApp {
         }
     }
 
+    pub(crate) fn render_import_pack_modal(&mut self, ctx: &egui::Context) {
+        if let Some(path_rc) = &self.import_pack_path.clone() {
+            let mut path = path_rc.clone();
+            let mut open = true;
+            let mut close_requested = false;
+
+            egui::Window::new("IMPORTAR MODPACK")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    tui_dim(ui, "Ruta del pack (.mrpack, .zip de CurseForge o carpeta packwiz):");
+                    ui.text_edit_singleline(&mut path);
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        if tui_button_c(ui, "CANCEL", tui_theme::NEON_RED).clicked() {
+                            close_requested = true;
+                        }
+                        if tui_button_c(ui, "IMPORT", tui_theme::NEON_GREEN).clicked() && !path.trim().is_empty() {
+                            self.import_pack(path.trim().trim_matches('"'));
+                            close_requested = true;
+                        }
+                    });
+                });
+
+            if !open || close_requested {
+                self.import_pack_path = None;
+            } else {
+                self.import_pack_path = Some(path);
+            }
+        }
+    }
+
+    /// Importa un pack como perfil nuevo en un hilo aparte (descargas de metadatos y extracción de
+    /// overrides); el resultado llega por `rx_import_result`.
+    fn import_pack(&mut self, path: &str) {
+        if self.import_running {
+            self.status_msg = "Ya hay una importación en curso.".to_string();
+            return;
+        }
+        let path = path.to_string();
+        let db = self.profiles_db.clone();
+        let tx = self.tx_import_result.clone();
+
+        self.import_running = true;
+        self.status_msg = format!("Importando '{}'...", path);
+        std::thread::spawn(move || {
+            let result = crate::pack_formats::import_pack(std::path::Path::new(&path), &db, &PATHS.modpacks_folder);
+            let _ = tx.send((path, result));
+        });
+    }
+
+    pub(crate) fn render_export_pack_modal(&mut self, ctx: &egui::Context) {
+        let Some(mut state) = self.export_pack_state.clone() else { return };
+        let mut open = true;
+        let mut close_requested = false;
+        let mut start = false;
+
+        egui::Window::new("EXPORTAR MODPACK")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| { tui_dim(ui, "Perfil:"); tui_heading(ui, &state.profile); });
+                ui.add_space(4.0);
+                ui.add_enabled_ui(!state.running, |ui| {
+                    egui::Grid::new("export_pack_grid").num_columns(2).show(ui, |ui| {
+                        tui_dim(ui, "Formato:");
+                        let previous = state.format;
+                        egui::ComboBox::from_id_salt("export_format")
+                            .selected_text(state.format.label())
+                            .show_ui(ui, |ui| {
+                                for format in PackFormat::ALL {
+                                    ui.selectable_value(&mut state.format, format, format.label());
+                                }
+                            });
+                        if state.format != previous {
+                            // Mismo directorio, nombre del nuevo formato
+                            let file_name = state.format.file_name(&state.profile, &state.game_version);
+                            let output = std::path::Path::new(&state.output).with_file_name(file_name);
+                            state.output = output.to_string_lossy().to_string();
+                        }
+                        ui.end_row();
+
+                        tui_dim(ui, "Minecraft:");
+                        egui::ComboBox::from_id_salt("export_mc_version")
+                            .selected_text(&state.game_version)
+                            .show_ui(ui, |ui| {
+                                for v in &self.mc_versions {
+                                    ui.selectable_value(&mut state.game_version, v.clone(), v);
+                                }
+                            });
+                        ui.end_row();
+
+                        tui_dim(ui, "Loader:");
+                        egui::ComboBox::from_id_salt("export_loader")
+                            .selected_text(&state.loader)
+                            .show_ui(ui, |ui| {
+                                for l in &self.loaders {
+                                    ui.selectable_value(&mut state.loader, l.clone(), l);
+                                }
+                            });
+                        ui.end_row();
+
+                        tui_dim(ui, "Versión del loader:");
+                        ui.text_edit_singleline(&mut state.loader_version);
+                        ui.end_row();
+
+                        tui_dim(ui, "Destino:");
+                        ui.text_edit_singleline(&mut state.output);
+                        ui.end_row();
+                    });
+                });
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    if tui_button_c(ui, "CANCEL", tui_theme::NEON_RED).clicked() {
+                        close_requested = true;
+                    }
+                    if state.running {
+                        tui_dim(ui, "Exportando...");
+                    } else if tui_button_c(ui, "EXPORT", tui_theme::NEON_GREEN).clicked()
+                        && !state.loader_version.trim().is_empty()
+                        && !state.output.trim().is_empty()
+                    {
+                        start = true;
+                    }
+                });
+            });
+
+        if start {
+            self.start_export(&mut state);
+        }
+        // Si se cierra con la exportación en marcha, el hilo termina igual y su resultado llega a la barra de estado
+        if !open || close_requested {
+            self.export_pack_state = None;
+        } else {
+            self.export_pack_state = Some(state);
+        }
+    }
+
+    pub(crate) fn render_manual_downloads_modal(&mut self, ctx: &egui::Context) {
+        if self.manual_downloads.is_empty() {
+            return;
+        }
+        let mut open = true;
+        let mut close_requested = false;
+
+        egui::Window::new("DESCARGAS MANUALES")
+            .collapsible(true)
+            .resizable(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                tui_theme::tui_status(ui, "[!] El autor no permite descargarlos desde otras apps", tui_theme::WARNING);
+                tui_dim(ui, "Descárgalos desde su página y déjalos en la carpeta indicada:");
+                tui_separator(ui);
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for manual in &self.manual_downloads {
+                        ui.horizontal(|ui| {
+                            ui.hyperlink_to(&manual.name, &manual.url);
+                            tui_dim(ui, &format!("→ {}", manual.folder.display()));
+                        });
+                    }
+                });
+                ui.add_space(8.0);
+                if tui_button_c(ui, "OK", tui_theme::NEON_GREEN).clicked() {
+                    close_requested = true;
+                }
+            });
+
+        if !open || close_requested {
+            self.manual_downloads.clear();
+        }
+    }
+
+    /// Lanza la exportación en un hilo: resolver mods en Modrinth y descargar los de CurseForge puede tardar.
+    fn start_export(&mut self, state: &mut ExportPackState) {
+        let Some(profile) = self.profiles_db.get_profile(&state.profile).cloned() else {
+            self.status_msg = format!("El perfil '{}' ya no existe.", state.profile);
+            return;
+        };
+        let local_folder = PATHS.modpacks_folder.join(&profile.name);
+        let opts = crate::pack_formats::ExportOptions {
+            game_version: state.game_version.clone(),
+            loader: state.loader.clone(),
+            loader_version: state.loader_version.trim().to_string(),
+            version_id: "1.0.0".to_string(),
+            local_folder: local_folder.is_dir().then_some(local_folder),
+        };
+        let output = std::path::PathBuf::from(state.output.trim().trim_matches('"'));
+        let tx = self.tx_export_result.clone();
+        let format = state.format;
+
+        state.running = true;
+        self.status_msg = format!("Exportando '{}'...", profile.name);
+        std::thread::spawn(move || {
+            let result = crate::pack_formats::export_pack(&profile, format, &opts, &output);
+            let _ = tx.send((output.display().to_string(), result));
+        });
+    }
+
     pub(crate) fn render_duplicate_resolution_modal(&mut self, ctx: &egui::Context) {
         if let Some(mut resolutions) = self.pending_downloads.take() {
             let mut open = true;
@@ -369,6 +608,14 @@ impl super::app::ModUpdaterApp {
                     tui_heading(ui, "Resolución de dependencias y duplicados");
                     ui.add_space(8.0);
 
+                    if !self.download_conflicts.is_empty() {
+                        tui_theme::tui_status(ui, "[!] Incompatibilidades: no se instalará nada salvo que lo marques", tui_theme::NEON_RED);
+                        for conflict in &self.download_conflicts {
+                            tui_dim(ui, &format!("  {} es incompatible con {}", conflict.declared_by, conflict.mod_id));
+                        }
+                        ui.add_space(8.0);
+                    }
+
                     let mut apply_all_action = None;
 
                     // Global actions at the top
@@ -429,6 +676,8 @@ impl super::app::ModUpdaterApp {
                                                         tui_dim(ui, &format!("Current -> {}   New -> {}", old_v, new_v));
                                                     });
                                                 }
+                                            } else if res.optional {
+                                                tui_theme::tui_status(ui, "OPCIONAL", tui_theme::TEXT_DIM);
                                             } else {
                                                 tui_theme::tui_status(ui, "NUEVO", tui_theme::NEON_GREEN);
                                             }
@@ -507,6 +756,7 @@ impl super::app::ModUpdaterApp {
 
             if !open || close_requested {
                 self.pending_downloads = None;
+                self.download_conflicts.clear();
             } else {
                 self.pending_downloads = Some(resolutions);
             }
@@ -5,7 +5,11 @@ use crate::local_mods_ops::{
     read_active_marker,
     ReadJob,
     ModInfo,
+    UpdateState,
 };
+use crate::local_mods_ops::loaders::{majority_loader, supports_loader};
+use crate::local_mods_ops::validation::{validate_modpack, DependencyIssue, ValidationTarget};
+use crate::fetch::update_check::UpdateCheckJob;
 use crate::paths_vars::PATHS;
 use super::utils::{format_dep_name, format_version_range};
 use super::tui_theme::{self, tui_button, tui_button_c, tui_checkbox, tui_heading, tui_dim};
@@ -15,52 +19,111 @@ impl super::app::ModUpdaterApp {
     /// Carga mods de una carpeta: intenta caché primero, si no, crea placeholder y envía ReadJob.
     pub(crate) fn load_mods_from_folder(&mut self, folder: &std::path::Path) {
         self.mods.clear();
+        self.dependency_report = None;
         if let Ok(entries) = std::fs::read_dir(folder) {
-            let mut entries_vec: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-            entries_vec.sort_by_key(|e| e.file_name());
-
-            for entry in entries_vec {
-                let path = entry.path();
-                if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jar") {
-                    let key = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                    let mut needs_scan = true;
-                    
-                    if let Some(cached_info) = crate::local_mods_ops::cache::get_mod(&key) {
-                        if let Ok(metadata) = std::fs::metadata(&path) {
-                            let size = metadata.len();
-                            let mtime = metadata.modified().ok()
-                                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                                .map(|d| d.as_secs());
-                                
-                            if cached_info.file_size_bytes == Some(size) && cached_info.file_mtime_secs == mtime {
-                                self.mods.insert(key.clone(), UiModInfo {
-                                    inner: cached_info,
-                                    status: ModStatus::Idle,
-                                    progress: 1.0,
-                                });
-                                needs_scan = false;
-                            }
+            let mut jars: Vec<(String, std::path::PathBuf)> = entries.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("jar"))
+                .map(|path| (path.file_name().unwrap_or_default().to_string_lossy().to_string(), path))
+                .collect();
+            jars.sort();
+            // Toda la carpeta en una sola lectura de la caché
+            let mut cached = crate::local_mods_ops::cache::get_mods(jars.iter().map(|(key, _)| key.as_str()));
+
+            for (key, path) in jars {
+                let mut needs_scan = true;
+                
+                if let Some(cached_info) = cached.remove(&key) {
+                    if let Ok(metadata) = std::fs::metadata(&path) {
+                        let size = metadata.len();
+                        let mtime = metadata.modified().ok()
+                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs());
+                            
+                        // Sin loaders o sin jars incluidos = caché de antes de detectarlos, se vuelve a leer el jar
+                        if cached_info.file_size_bytes == Some(size) && cached_info.file_mtime_secs == mtime
+                            && !cached_info.loaders.is_empty() && cached_info.bundled.is_some()
+                        {
+                            self.mods.insert(key.clone(), UiModInfo {
+                                inner: cached_info,
+                                status: ModStatus::Idle,
+                                progress: 1.0,
+                            });
+                            needs_scan = false;
                         }
                     }
+                }
 
-                    if needs_scan {
-                        let placeholder = ModInfo {
-                            key: key.clone(), name: key.clone(),
-                            detected_project_id: None, confirmed_project_id: None,
-                            version_local: None, version_remote: None, selected: true,
-                            file_size_bytes: None, file_mtime_secs: None, depends: None,
-                            has_local_icon: false,
-                        };
-                        self.mods.insert(key, UiModInfo {
-                            inner: placeholder, status: ModStatus::Resolving, progress: 0.0,
-                        });
-                        let _ = self.tx_read_jobs.send(ReadJob { file_path: path });
-                    }
+                if needs_scan {
+                    let placeholder = ModInfo {
+                        key: key.clone(), name: key.clone(),
+                        detected_project_id: None, confirmed_project_id: None,
+                        version_local: None, version_remote: None, selected: true,
+                        file_size_bytes: None, file_mtime_secs: None, depends: None,
+                        has_local_icon: false,
+                        ..Default::default()
+                    };
+                    self.mods.insert(key, UiModInfo {
+                        inner: placeholder, status: ModStatus::Resolving, progress: 0.0,
+                    });
+                    let _ = self.tx_read_jobs.send(ReadJob { file_path: path });
                 }
             }
         }
     }
 
+    /// Tras abrir un modpack, pone en el selector el loader de la mayoría de sus jars. Espera a que
+    /// no quede ninguno leyéndose; se vuelve a llamar desde `process_read_events`.
+    pub(crate) fn suggest_loader_if_loaded(&mut self) {
+        if !self.loader_suggestion_pending || self.mods.values().any(|m| matches!(m.status, ModStatus::Resolving)) {
+            return;
+        }
+        self.loader_suggestion_pending = false;
+        if let Some(loader) = majority_loader(self.mods.values().map(|m| &m.inner))
+            && loader != self.selected_loader
+            && self.loaders.contains(&loader)
+        {
+            self.status_msg = format!("Loader cambiado a {} (el de la mayoría de jars del modpack)", loader);
+            self.selected_loader = loader;
+        }
+    }
+
+    /// Panel con el resultado del botón DEPS, encima de la lista de mods.
+    fn render_dependency_report(&mut self, ui: &mut egui::Ui) {
+        let Some(issues) = &self.dependency_report else { return };
+        let mut close = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if issues.is_empty() {
+                    tui_theme::tui_status(ui, "[OK] Todas las dependencias se cumplen", tui_theme::NEON_GREEN);
+                } else {
+                    tui_theme::tui_status(ui, &format!("[!] {} problemas de dependencias", issues.len()), tui_theme::WARNING);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    close = tui_button(ui, "X").clicked();
+                });
+            });
+            ScrollArea::vertical().id_salt("dependency_report").max_height(160.0).show(ui, |ui| {
+                for issue in issues {
+                    let (tag, color) = match issue {
+                        DependencyIssue::Missing { .. } => ("FALTA", tui_theme::NEON_RED),
+                        DependencyIssue::VersionMismatch { .. } => ("VERSIÓN", tui_theme::NEON_YELLOW),
+                        DependencyIssue::Duplicate { .. } => ("DUPLICADO", tui_theme::NEON_YELLOW),
+                        DependencyIssue::ConflictingBundles { .. } => ("INCLUIDO", tui_theme::NEON_YELLOW),
+                    };
+                    ui.horizontal(|ui| {
+                        tui_theme::tui_status(ui, &format!("[{}]", tag), color);
+                        tui_dim(ui, &issue.describe());
+                    });
+                }
+            });
+        });
+        ui.add_space(4.0);
+        if close {
+            self.dependency_report = None;
+        }
+    }
+
     pub(crate) fn render_modpacks_side(&mut self, ctx: &egui::Context) {
         let max_len = self.cached_modpacks.iter().map(|s| s.len()).max().unwrap_or(0);
         // Approx 8px per char + space for buttons (X, OFF, ON), margin and scrollbar
@@ -105,9 +168,11 @@ impl super::app::ModUpdaterApp {
                                     PATHS.mods_folder.clone()
                                 } else {
                                     self.selected_modpack_ui = Some(mp.clone());
+                                    self.loader_suggestion_pending = true;
                                     PATHS.modpacks_folder.join(&mp)
                                 };
                                 self.load_mods_from_folder(&target_folder);
+                                self.suggest_loader_if_loaded();
                             }
 
                             // Right side: [ON] indicator + buttons
@@ -185,6 +250,27 @@ impl super::app::ModUpdaterApp {
                 self.search_state.page = 0;
             }
 
+            let checking = self.update_checks_pending > 0;
+            if checking {
+                tui_dim(ui, &format!("[CHK {}]", self.update_checks_pending));
+            } else if tui_button(ui, "CHK")
+                .on_hover_text(format!("Buscar actualizaciones para {} [{}]", self.selected_mc_version, self.selected_loader))
+                .clicked() {
+                    let jobs: Vec<UpdateCheckJob> = self.mods.values_mut()
+                        .map(|m| {
+                            m.inner.update_state = None;
+                            UpdateCheckJob {
+                                modinfo: m.inner.clone(),
+                                game_version: self.selected_mc_version.clone(),
+                                loader: self.selected_loader.clone(),
+                            }
+                        })
+                        .collect();
+                    self.update_checks_pending = jobs.len();
+                    self.status_msg = format!("Buscando actualizaciones de {} mods...", jobs.len());
+                    let _ = self.tx_update_checks.send(jobs);
+            }
+
             if tui_button_c(ui, "↓", tui_theme::NEON_YELLOW)
                 .on_hover_text("Actualizar mods")
                 .clicked() {
@@ -206,6 +292,19 @@ impl super::app::ModUpdaterApp {
                 }
             }
 
+            if tui_button(ui, "DEPS")
+                .on_hover_text(format!("Comprobar dependencias de los jars para {} [{}] (sin conexión)", self.selected_mc_version, self.selected_loader))
+                .clicked() {
+                    let target = ValidationTarget {
+                        mc_version: self.selected_mc_version.clone(),
+                        loader: self.selected_loader.clone(),
+                        loader_version: None,
+                    };
+                    let issues = validate_modpack(self.mods.values().map(|m| &m.inner), &target);
+                    self.status_msg = format!("Validación de dependencias: {} problemas", issues.len());
+                    self.dependency_report = Some(issues);
+            }
+
             if tui_button_c(ui, "SAVE", tui_theme::NEON_GREEN)
             .on_hover_text("Crea un perfil con los mods seleccionados.")
             .clicked() {
@@ -215,9 +314,17 @@ impl super::app::ModUpdaterApp {
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 tui_dim(ui, &format!("Total: {}", self.mods.len()));
+                let mismatched = self.mods.values().filter(|m| !supports_loader(&m.inner, &self.selected_loader)).count();
+                if mismatched > 0 {
+                    ui.label(egui::RichText::new(format!("[{} NO {}]", mismatched, self.selected_loader.to_uppercase()))
+                        .family(egui::FontFamily::Monospace)
+                        .color(tui_theme::NEON_RED))
+                        .on_hover_text(format!("{} jars están hechos para otro loader y no cargarán con {}", mismatched, self.selected_loader));
+                }
             });
         });
         ui.add_space(8.0);
+        self.render_dependency_report(ui);
         ScrollArea::vertical().show(ui, |ui| {
             let mut keys: Vec<String> = self.mods.keys().cloned().collect();
             keys.sort_by(|a, b| {
@@ -287,8 +394,37 @@ impl super::app::ModUpdaterApp {
                                         .color(tui_theme::TEXT_DIM)
                                         .size(11.0));
                                 }
+                                match m.update_state {
+                                    Some(UpdateState::UpToDate) => {
+                                        tui_theme::tui_status(ui, "[AL DÍA]", tui_theme::NEON_GREEN);
+                                    }
+                                    Some(UpdateState::Outdated) => {
+                                        let remote = m.version_remote.as_deref().unwrap_or("?");
+                                        ui.label(egui::RichText::new(format!("[→ {}]", remote))
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(tui_theme::NEON_YELLOW))
+                                            .on_hover_text(format!("Publicado: {}", m.remote_date.as_deref().unwrap_or("?")));
+                                    }
+                                    Some(UpdateState::Unavailable) => {
+                                        ui.label(egui::RichText::new("[N/D]")
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(tui_theme::NEON_RED))
+                                            .on_hover_text("No hay versión compatible para la versión/loader elegidos");
+                                    }
+                                    None => {}
+                                }
 
-
+                                if !m.loaders.is_empty() {
+                                    let label = format!("[{}]", m.loaders.join("/"));
+                                    if supports_loader(&m.inner, &self.selected_loader) {
+                                        tui_dim(ui, &label);
+                                    } else {
+                                        ui.label(egui::RichText::new(label)
+                                            .family(egui::FontFamily::Monospace)
+                                            .color(tui_theme::NEON_RED))
+                                            .on_hover_text(format!("Este jar no es para {}", self.selected_loader));
+                                    }
+                                }
                             });
 
                             if let Some(deps) = &m.depends {
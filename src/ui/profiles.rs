@@ -1,8 +1,11 @@
 use eframe::egui::{self, ScrollArea, SidePanel};
 
+use crate::local_mods_ops::ModInfo;
+use crate::pack_formats::PackFormat;
+use crate::paths_vars::PATHS;
 use crate::profiles::save_profiles;
 use super::tui_theme::{self, tui_button, tui_button_c, tui_separator, tui_dim, tui_number};
-use super::types::{DeletionConfirmation, DownloadSource, SearchSource};
+use super::types::{DeletionConfirmation, DownloadSource, ExportPackState, SearchSource};
 
 impl super::app::ModUpdaterApp {
     pub(crate) fn render_profiles_side(&mut self, ctx: &egui::Context) {
@@ -16,6 +19,9 @@ impl super::app::ModUpdaterApp {
                     if tui_button_c(ui, "+", tui_theme::NEON_GREEN).on_hover_text("Crear perfil").clicked() {
                          self.create_profile_modal_name = Some(String::new());
                     }
+                    if tui_button_c(ui, "IMP", tui_theme::ACCENT).on_hover_text("Importar modpack (.mrpack / CurseForge / packwiz)").clicked() {
+                        self.import_pack_path = Some(String::new());
+                    }
                     if tui_button_c(ui, "DL", tui_theme::NEON_YELLOW).on_hover_text("Instalar/Descargar").clicked() {
                         if let Some(selected) = &self.selected_profile_name {
                              self.download_confirmation_name = Some(selected.clone());
@@ -60,6 +66,7 @@ impl super::app::ModUpdaterApp {
         // Main Profile Editor
         if let Some(name) = &self.selected_profile_name.clone() {
             let mut should_save = false; 
+            let mut save_added = false;
             if let Some(profile) = self.profiles_db.get_profile_mut(name) {
                 ui.horizontal(|ui| {
                     tui_dim(ui, "Nombre:");
@@ -71,6 +78,21 @@ impl super::app::ModUpdaterApp {
                             self.status_msg = "Perfil guardado.".to_string();
                         }
                         ui.add_space(5.0);
+                        if tui_button(ui, "EXPORT").on_hover_text("Exportar como modpack (.mrpack / CurseForge / packwiz)").clicked() {
+                            // Por defecto la versión y el loader del pack importado, si no los de la app
+                            let game_version = profile.game_version.clone().unwrap_or_else(|| self.selected_mc_version.clone());
+                            let output = PATHS.modpacks_folder.join(PackFormat::Mrpack.file_name(name, &game_version));
+                            self.export_pack_state = Some(ExportPackState {
+                                profile: name.clone(),
+                                format: PackFormat::Mrpack,
+                                loader: profile.loader.clone().unwrap_or_else(|| self.selected_loader.clone()),
+                                game_version,
+                                loader_version: String::new(),
+                                output: output.to_string_lossy().to_string(),
+                                running: false,
+                            });
+                        }
+                        ui.add_space(5.0);
                         if tui_button(ui, "BUSCAR").on_hover_text("Buscar / Añadir Mod").clicked() {
                              self.search_state.open = true;
                              self.search_state.source = SearchSource::Profile(name.clone());
@@ -89,6 +111,35 @@ impl super::app::ModUpdaterApp {
                 
                 tui_separator(ui);
                 ui.horizontal(|ui| { tui_dim(ui, "Mods: "); tui_number(ui, &profile.mods.len().to_string()); });
+
+                // Opcionales e incompatibilidades de la última resolución de dependencias
+                let mut dismiss_report = false;
+                if let Some(report) = self.profile_deps_report.as_mut().filter(|r| &r.profile == name) {
+                    for conflict in &report.conflicts {
+                        tui_theme::tui_status(ui, &format!("[!] {} es incompatible con {}", conflict.declared_by, conflict.mod_id), tui_theme::NEON_RED);
+                    }
+                    let mut added = None;
+                    for (i, dep) in report.optional.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            tui_dim(ui, &format!("Opcional: {}", dep.name));
+                            if tui_button_c(ui, "ADD", tui_theme::NEON_GREEN).on_hover_text("Añadir al perfil").clicked() {
+                                added = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = added {
+                        let dep = report.optional.remove(i);
+                        profile.mods.insert(dep.filename.clone(), ModInfo::from_dep(dep.filename, dep.name, dep.project_id, dep.slug));
+                        save_added = true;
+                    }
+                    if tui_button(ui, "OK").on_hover_text("Ocultar aviso").clicked() {
+                        dismiss_report = true;
+                    }
+                    tui_separator(ui);
+                }
+                if dismiss_report {
+                    self.profile_deps_report = None;
+                }
                 
                 ScrollArea::vertical().id_salt("profile_mods_scroll").show(ui, |ui| {
                     // Collect toggle actions to avoid borrowing issues in loop
@@ -140,6 +191,10 @@ impl super::app::ModUpdaterApp {
                 ui.label("Perfil no encontrado (¿borrado?)");
             }
 
+            if save_added && !should_save {
+                save_profiles(&self.profiles_db);
+            }
+
             if should_save {
                 // Apply pending deletions first
                 if !self.profile_mods_pending_deletion.is_empty() {
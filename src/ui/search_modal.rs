@@ -247,6 +247,7 @@ impl super::app::ModUpdaterApp {
                                                                 file_mtime_secs: None,
                                                                 depends: None,
                                                                 has_local_icon: false,
+                                                                ..Default::default()
                                                             };
                                                             
                                                             let output_folder_path = match &self.search_state.source {
@@ -266,6 +267,10 @@ impl super::app::ModUpdaterApp {
                                                                 for m in self.mods.values() {
                                                                     if let Some(id) = &m.confirmed_project_id { existing_project_ids.insert(id.clone()); }
                                                                     if let Some(id) = &m.detected_project_id { existing_project_ids.insert(id.clone()); }
+                                                                    // Librerías incluidas (jar-in-jar): no hace falta bajarlas aparte
+                                                                    for jar in m.bundled.iter().flatten() {
+                                                                        if let Some(id) = &jar.mod_id { existing_project_ids.insert(id.clone()); }
+                                                                    }
                                                                 }
                                                             }
                                                             
@@ -289,9 +294,36 @@ impl super::app::ModUpdaterApp {
                                                         }
                                                     }
                                                 },
-                                                SearchSource::Profile(_p_name) => {
-                                                    if tui_button_c(ui, "ADD", tui_theme::NEON_GREEN).clicked() {
-                                                         // Future Profile version selection implementation
+                                                SearchSource::Profile(p_name) => {
+                                                    let project_id = selected_project.modrinth_id.clone()
+                                                        .or_else(|| selected_project.curseforge_id.map(|id| id.to_string()))
+                                                        .unwrap_or_else(|| selected_project.slug.clone());
+                                                    let in_profile = self.profiles_db.get_profile(p_name)
+                                                        .is_some_and(|p| p.contains_mod(&selected_project.slug, &project_id, &selected_project.slug));
+                                                    if in_profile {
+                                                        tui_theme::tui_status(ui, "[OK]", tui_theme::NEON_GREEN);
+                                                    } else if tui_button_c(ui, "ADD", tui_theme::NEON_GREEN).on_hover_text("Añadir al perfil").clicked()
+                                                        && let Some(profile) = self.profiles_db.get_profile_mut(p_name)
+                                                    {
+                                                        // El perfil guarda el proyecto; el archivo se elige al descargarlo para su versión
+                                                        profile.mods.insert(selected_project.slug.clone(), crate::local_mods_ops::ModInfo::from_dep(
+                                                            selected_project.slug.clone(), selected_project.name.clone(), project_id.clone(), selected_project.slug.clone(),
+                                                        ));
+                                                        if self.search_state.download_dependencies {
+                                                            let existing: std::collections::HashSet<String> = profile.mods.values()
+                                                                .flat_map(|m| [m.confirmed_project_id.clone(), m.detected_project_id.clone()])
+                                                                .flatten()
+                                                                .collect();
+                                                            let _ = self.tx_resolve_profile_deps.send((
+                                                                project_id,
+                                                                p_name.clone(),
+                                                                profile.game_version.clone().unwrap_or_else(|| self.search_state.version.clone()),
+                                                                profile.loader.clone().unwrap_or_else(|| self.search_state.loader.clone()),
+                                                                existing,
+                                                            ));
+                                                        }
+                                                        crate::profiles::save_profiles(&self.profiles_db);
+                                                        self.status_msg = format!("'{}' añadido al perfil '{}'.", selected_project.name, p_name);
                                                     }
                                                 }
                                             }
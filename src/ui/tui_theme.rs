@@ -217,13 +217,13 @@ pub fn tui_tab(ui: &mut egui::Ui, label: &str, selected: bool) -> egui::Response
 }
 
 /// Texto con color dim para info secundaria
-pub fn tui_dim(ui: &mut egui::Ui, text: &str) {
+pub fn tui_dim(ui: &mut egui::Ui, text: &str) -> egui::Response {
     ui.label(
         egui::RichText::new(text)
             .family(FontFamily::Monospace)
             .color(TEXT_DIM)
             .size(11.0)
-    );
+    )
 }
 
 /// Texto accent para nombres/títulos
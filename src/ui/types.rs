@@ -2,6 +2,7 @@ use std::ops::{Deref, DerefMut};
 use crate::local_mods_ops::ModInfo;
 use crate::fetch::search_provider::{UnifiedSearchResult, ContentType};
 use crate::fetch::async_download::DownloadJob;
+use crate::fetch::fetch_from_api::{DependencyConflict, ModDownloadInfo};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DownloadAction {
@@ -18,6 +19,26 @@ pub struct DuplicateResolution {
     pub existing_version: Option<String>,
     pub action: DownloadAction,
     pub status: ModStatus,
+    /// Dependencia opcional sugerida: no se instala salvo que se marque
+    pub optional: bool,
+}
+
+/// Resultado del hilo que resuelve dependencias antes de abrir el modal de descargas.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedDownloads {
+    /// El mod pedido primero y después sus dependencias obligatorias
+    pub jobs: Vec<DownloadJob>,
+    pub optional: Vec<DownloadJob>,
+    pub conflicts: Vec<DependencyConflict>,
+}
+
+/// Lo que no se añade solo al resolver las dependencias de un mod del perfil.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileDepsReport {
+    pub profile: String,
+    /// Opcionales sugeridas: se añaden al perfil solo si se pulsa ADD
+    pub optional: Vec<ModDownloadInfo>,
+    pub conflicts: Vec<DependencyConflict>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -66,6 +87,20 @@ impl Default for SearchState {
     }
 }
 
+/// Modal de exportación de un perfil (`.mrpack` o zip de CurseForge).
+#[derive(Debug, Clone)]
+pub struct ExportPackState {
+    pub profile: String,
+    pub format: crate::pack_formats::PackFormat,
+    pub game_version: String,
+    pub loader: String,
+    pub loader_version: String,
+    /// Ruta del archivo a escribir
+    pub output: String,
+    /// Hay una exportación en marcha en segundo plano
+    pub running: bool,
+}
+
 #[derive(Debug, Clone)]
 pub enum ModStatus {
     Idle,
@@ -111,6 +146,8 @@ pub enum DownloadSource {
     None,
     Explorer,
     Profile(String),
+    /// Pack recién importado: sus descargas ya van fijadas, el modal solo muestra el progreso
+    Import(String),
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
@@ -1,7 +1,10 @@
+mod common;
+
 use mods_updater::fetch::modrinth_api;
 use mods_updater::fetch::curseforge_api;
 use mods_updater::fetch::fetch_from_api;
-use std::env;
+use mods_updater::fetch::search_provider::ContentType;
+use mods_updater::fetch::cf_api_key;
 
 /// Verifica que al arrancar, ambas APIs reportan capacidad disponible.
 #[test]
@@ -17,7 +20,8 @@ fn test_both_apis_report_capacity_at_startup() {
 /// Este test confirma que el flujo completo Modrinth → CurseForge funciona.
 #[test]
 fn test_find_mod_download_modrinth_primary() {
-    let cf_key = env::var("CURSEFORGE_API_KEY").unwrap_or_default();
+    common::start_stub_server();
+    let cf_key = cf_api_key();
     
     // Sodium es un mod muy popular, debería encontrarse en Modrinth
     let result = fetch_from_api::find_mod_download(
@@ -26,6 +30,7 @@ fn test_find_mod_download_modrinth_primary() {
         "1.20.1",
         "fabric",
         &cf_key,
+        &ContentType::Mod,
     );
 
     assert!(result.is_some(), "Should find Sodium via load-balanced download");
@@ -38,7 +43,8 @@ fn test_find_mod_download_modrinth_primary() {
 /// (sin ID), ejercitando el fallback de búsqueda.
 #[test]
 fn test_find_mod_download_by_name_only() {
-    let cf_key = env::var("CURSEFORGE_API_KEY").unwrap_or_default();
+    common::start_stub_server();
+    let cf_key = cf_api_key();
     
     let result = fetch_from_api::find_mod_download(
         "Fabric API",
@@ -46,6 +52,7 @@ fn test_find_mod_download_by_name_only() {
         "1.20.1",
         "fabric",
         &cf_key,
+        &ContentType::Mod,
     );
 
     assert!(result.is_some(), "Should find Fabric API by name search");
@@ -59,7 +66,8 @@ fn test_find_mod_download_by_name_only() {
 /// Verifica que un mod inexistente devuelve None sin panic.
 #[test]
 fn test_find_mod_download_not_found() {
-    let cf_key = env::var("CURSEFORGE_API_KEY").unwrap_or_default();
+    common::start_stub_server();
+    let cf_key = cf_api_key();
     
     let result = fetch_from_api::find_mod_download(
         "sdflkjhsdflkgjhsdlfkgjh_nonexistent_mod",
@@ -67,7 +75,43 @@ fn test_find_mod_download_not_found() {
         "1.20.1",
         "fabric",
         &cf_key,
+        &ContentType::Mod,
     );
 
     assert!(result.is_none(), "Should return None for nonexistent mod");
 }
+
+/// Un ID numérico (p.ej. identificado por fingerprint) se usa directamente en CurseForge
+/// sin pasar por la búsqueda por nombre.
+#[test]
+fn test_find_mod_download_curseforge_numeric_id() {
+    common::start_stub_server();
+    let cf_key = cf_api_key();
+
+    let result = fetch_from_api::find_mod_download(
+        "Nombre que no coincide con nada",
+        Some("238222"),
+        "1.20.1",
+        "forge",
+        &cf_key,
+        &ContentType::Mod,
+    );
+
+    let info = result.expect("Should resolve JEI by its CurseForge ID");
+    assert_eq!(info.project_id, "238222");
+    assert!(info.filename.starts_with("jei-"));
+}
+
+/// Las dependencias se resuelven solo por ID (nombre vacío): si el ID no existe no se
+/// devuelve el primer resultado de una búsqueda vacía.
+#[test]
+fn test_find_mod_download_empty_name_never_searches() {
+    common::start_stub_server();
+    let cf_key = cf_api_key();
+
+    let missing = fetch_from_api::find_mod_download("", Some("no-such-project"), "1.20.1", "fabric", &cf_key, &ContentType::Mod);
+    assert!(missing.is_none(), "An unknown dependency must not match an arbitrary project: {:?}", missing.map(|i| i.filename));
+
+    let by_cf_id = fetch_from_api::find_mod_download("", Some("238222"), "1.20.1", "forge", &cf_key, &ContentType::Mod);
+    assert_eq!(by_cf_id.map(|i| i.project_id).as_deref(), Some("238222"));
+}
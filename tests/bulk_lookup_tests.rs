@@ -0,0 +1,110 @@
+mod common;
+
+use mods_updater::fetch::async_download::{prefetch_jobs, DownloadJob};
+use mods_updater::fetch::curseforge_api::fetch_curseforge_files;
+use mods_updater::fetch::fetch_from_api::{fetch_dependency_names, find_mod_downloads_in_bulk};
+use mods_updater::fetch::search_provider::ContentType;
+use mods_updater::fetch::update_check::{check_updates_by_hash, UpdateCheckJob};
+use mods_updater::local_mods_ops::hashing::{hash_bytes, hash_file};
+use mods_updater::local_mods_ops::{ModInfo, UpdateState};
+
+fn local_mod(key: &str, download: Option<&str>) -> ModInfo {
+    ModInfo {
+        key: key.to_string(),
+        name: key.to_string(),
+        hashes: Some(match download {
+            Some(file) => hash_file(&common::fixture_path(&format!("api/downloads/{}", file))).unwrap(),
+            None => hash_bytes(key.as_bytes()),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Carpeta de 153 mods: dos que Modrinth reconoce por hash y el resto desconocidos.
+fn folder_mods() -> Vec<ModInfo> {
+    let mut mods = vec![
+        local_mod("sodium.jar", Some("sodium-fabric-0.5.3+mc1.20.1.jar")),
+        local_mod("fabric-api.jar", Some("fabric-api-0.92.2+1.20.1.jar")),
+        local_mod("unknown.jar", None),
+    ];
+    for i in 0..150 {
+        mods.push(local_mod(&format!("unknown-{}.jar", i), None));
+    }
+    mods
+}
+
+#[test]
+fn test_update_check_uses_bulk_endpoints() {
+    let mut requests = common::RequestCounter::start();
+
+    // Los mods reconocidos en dos peticiones, el resto queda pendiente
+    let jobs: Vec<UpdateCheckJob> = folder_mods().into_iter()
+        .map(|m| UpdateCheckJob { modinfo: m, game_version: "1.20.1".to_string(), loader: "Fabric".to_string() })
+        .collect();
+    let (results, pending) = check_updates_by_hash(jobs);
+    assert_eq!(requests.modrinth(), 2, "version_files/update + projects");
+    assert_eq!(pending.len(), 151);
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.state == UpdateState::UpToDate), "{:?}", results);
+    let sodium = results.iter().find(|r| r.key == "sodium.jar").and_then(|r| r.latest.as_ref()).unwrap();
+    assert_eq!((sodium.name.as_str(), sodium.slug.as_str()), ("Sodium", "sodium"));
+}
+
+#[test]
+fn test_prefetch_uses_bulk_endpoints() {
+    let mut requests = common::RequestCounter::start();
+
+    // Descarga a otra versión de MC: el job queda resuelto con el archivo de esa versión
+    let mut jobs: Vec<DownloadJob> = folder_mods().into_iter().take(3)
+        .map(|m| DownloadJob {
+            key: m.key.clone(),
+            modinfo: m,
+            output_folder: String::new(),
+            selected_version: "1.21.1".to_string(),
+            selected_loader: "Fabric".to_string(),
+            content_type: ContentType::Mod,
+            replaces_filename: None,
+            raw_game_version: "1.21.1".to_string(),
+            pre_resolved: None,
+        })
+        .collect();
+    prefetch_jobs(&mut jobs);
+    assert_eq!(requests.modrinth(), 2, "version_files/update + projects");
+    assert_eq!(jobs[0].pre_resolved.as_ref().map(|i| i.filename.as_str()), Some("sodium-fabric-0.6.0+mc1.21.1.jar"));
+    assert!(jobs[1].pre_resolved.is_none(), "Fabric API has no 1.21.1 file in the fixtures");
+    assert!(jobs[2].pre_resolved.is_none());
+}
+
+#[test]
+fn test_dependency_names_use_bulk_endpoints() {
+    let mut requests = common::RequestCounter::start();
+
+    // La dependencia de Iris solo fija version_id; se resuelve con /versions y /projects
+    let names = fetch_dependency_names("YL57xq9U", "1.20.1", "fabric", "");
+    assert_eq!(names, vec!["Sodium".to_string()]);
+    assert_eq!(requests.modrinth(), 3, "project version + versions + projects");
+}
+
+#[test]
+fn test_curseforge_batch_lookups() {
+    let mut requests = common::RequestCounter::start();
+
+    // Sin hashes: solo CurseForge, por los IDs confirmados (fingerprint)
+    let mods: Vec<ModInfo> = [("jei.jar", "238222"), ("fabric-api.jar", "306612"), ("restricted.jar", "400000"), ("modrinth.jar", "AANobbMI")]
+        .iter()
+        .map(|(key, id)| ModInfo { key: key.to_string(), name: key.to_string(), confirmed_project_id: Some(id.to_string()), ..Default::default() })
+        .collect();
+
+    let found = find_mod_downloads_in_bulk(&mods, "1.20.1", "Forge", common::STUB_CF_KEY);
+    assert_eq!(requests.curseforge(), 2, "POST /mods + POST /mods/files");
+    let keys: Vec<&str> = found.keys().map(String::as_str).collect();
+    assert_eq!(keys, vec!["jei.jar"], "Fabric API is not for Forge and the restricted mod has no download URL");
+    assert_eq!(found["jei.jar"].filename, "jei-1.20.1-forge-15.2.0.27.jar");
+    assert_eq!(found["jei.jar"].project_id, "238222");
+
+    // Archivos de un manifest: una petición para todos
+    let files = fetch_curseforge_files(&[4712866, 5383715, 999], common::STUB_CF_KEY);
+    assert_eq!(requests.curseforge(), 1);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[&5383715].file_name, "fabric-api-0.92.2+1.20.1.jar");
+}
@@ -0,0 +1,515 @@
+//! Servidor HTTP stub para los tests de integración.
+//! Sirve las respuestas de `tests/fixtures/api` imitando las rutas de Modrinth, CurseForge
+//! y sus CDN, de forma que los tests no dependan de internet.
+//!
+//! Rutas:
+//! - `/modrinth/v2/...`  → API de Modrinth (`Endpoints::modrinth_api_url`)
+//! - `/curseforge/v1/...` → API de CurseForge (`Endpoints::curseforge_api_url`)
+//! - cualquier otra ruta → descarga de `fixtures/api/downloads/<último segmento>` (`Endpoints::download_host`),
+//!   con soporte de `Range`; bajo `/flaky/<n>/` las `n` primeras peticiones devuelven 503
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// API key que el stub acepta para CurseForge. Cualquier otra devuelve 403.
+pub const STUB_CF_KEY: &str = "stub-cf-key";
+
+static SERVER: OnceLock<String> = OnceLock::new();
+static MODRINTH_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+static CURSEFORGE_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Arranca el servidor stub (una vez por binario de test) y apunta las URLs base de la app a él.
+/// Devuelve la URL base del servidor (`http://127.0.0.1:<puerto>`).
+pub fn start_stub_server() -> String {
+    SERVER.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("No se pudo abrir el puerto del stub");
+        let base = format!("http://{}", listener.local_addr().expect("Dirección del stub"));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                std::thread::spawn(move || handle_connection(stream));
+            }
+        });
+
+        // Se fijan en la propia app y no con set_var: modificar el entorno mientras otros tests lo leen es UB
+        mods_updater::fetch::set_endpoints(mods_updater::fetch::Endpoints {
+            modrinth_api_url: Some(format!("{}/modrinth/v2", base)),
+            curseforge_api_url: Some(format!("{}/curseforge/v1", base)),
+            download_host: Some(base.clone()),
+            curseforge_api_key: Some(STUB_CF_KEY.to_string()),
+        });
+        base
+    }).clone()
+}
+
+/// Ruta a `tests/fixtures/<rel>`.
+pub fn fixture_path(rel: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(rel)
+}
+
+/// Carpeta temporal limpia para un test.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("test_mods_updater_{}", name));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).expect("No se pudo crear la carpeta temporal");
+    path
+}
+
+/// Crea un jar/zip con las entradas indicadas (ruta dentro del zip, contenido).
+pub fn write_jar(path: &std::path::Path, entries: &[(&str, &[u8])]) {
+    let file = std::fs::File::create(path).expect("No se pudo crear el jar de prueba");
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    for (name, content) in entries {
+        zip.start_file(*name, options).expect("Entrada del jar");
+        zip.write_all(content).expect("Contenido del jar");
+    }
+    zip.finish().expect("Cerrar el jar");
+}
+
+// ── Servidor ─────────────────────────────────────────────────
+
+pub struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+pub struct StubResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl StubResponse {
+    fn json(body: Vec<u8>) -> Self {
+        Self { status: 200, content_type: "application/json", headers: Vec::new(), body }
+    }
+
+    fn json_value(value: &serde_json::Value) -> Self {
+        Self::json(value.to_string().into_bytes())
+    }
+
+    fn status(status: u16) -> Self {
+        Self { status, content_type: "text/plain", headers: Vec::new(), body: Vec::new() }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream) {
+    let Some(req) = read_request(&stream) else { return };
+    let resp = route(&req);
+
+    let reason = match resp.status {
+        200 => "OK",
+        206 => "Partial Content",
+        403 => "Forbidden",
+        404 => "Not Found",
+        416 => "Range Not Satisfiable",
+        503 => "Service Unavailable",
+        _ => "Stub",
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        resp.status, reason, resp.content_type, resp.body.len()
+    );
+    for (k, v) in &resp.headers {
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes());
+    if req.method != "HEAD" {
+        let _ = stream.write_all(&resp.body);
+    }
+    let _ = stream.flush();
+}
+
+fn read_request(stream: &TcpStream) -> Option<StubRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut h = String::new();
+        reader.read_line(&mut h).ok()?;
+        let h = h.trim_end();
+        if h.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = h.split_once(':') {
+            headers.insert(k.trim().to_lowercase(), v.trim().to_string());
+        }
+    }
+
+    let len: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body).ok()?;
+
+    let url = reqwest::Url::parse(&format!("http://stub{}", target)).ok()?;
+    let query = url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+
+    Some(StubRequest { method, path: url.path().to_string(), query, headers, body })
+}
+
+fn route(req: &StubRequest) -> StubResponse {
+    if let Some(rest) = req.path.strip_prefix("/modrinth/v2/") {
+        return route_modrinth(req, rest);
+    }
+    if let Some(rest) = req.path.strip_prefix("/curseforge/v1/") {
+        if req.headers.get("x-api-key").map(String::as_str) != Some(STUB_CF_KEY) {
+            return StubResponse::status(403);
+        }
+        return route_curseforge(req, rest);
+    }
+    route_download(req)
+}
+
+/// Normaliza una búsqueda para usarla como nombre de fixture: "Fabric API" → "fabric_api".
+fn query_slug(query: &str) -> String {
+    query.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn read_fixture_json(rel: &str) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(fixture_path(rel)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Interpreta un parámetro tipo `["a","b"]` de Modrinth.
+fn json_list_param(req: &StubRequest, name: &str) -> Option<Vec<String>> {
+    req.query.get(name).and_then(|v| serde_json::from_str(v).ok())
+}
+
+fn value_list_contains_any(value: &serde_json::Value, key: &str, wanted: &[String]) -> bool {
+    value[key].as_array().is_some_and(|arr| {
+        arr.iter().filter_map(|v| v.as_str()).any(|s| wanted.iter().any(|w| w.eq_ignore_ascii_case(s)))
+    })
+}
+
+fn route_modrinth(req: &StubRequest, rest: &str) -> StubResponse {
+    MODRINTH_REQUESTS.fetch_add(1, Ordering::SeqCst);
+    let segments: Vec<&str> = rest.split('/').collect();
+    match segments.as_slice() {
+        ["search"] => {
+            let query = req.query.get("query").map(|q| query_slug(q)).unwrap_or_default();
+            // Como la API real, una búsqueda vacía devuelve proyectos populares
+            let query = if query.is_empty() { "sodium".to_string() } else { query };
+            let body = read_fixture_json(&format!("api/modrinth/search/{}.json", query))
+                .unwrap_or_else(|| serde_json::json!({ "hits": [] }));
+            let mut resp = StubResponse::json_value(&body);
+            resp.headers.push(("x-ratelimit-remaining".to_string(), "299".to_string()));
+            resp.headers.push(("x-ratelimit-reset".to_string(), "60".to_string()));
+            resp
+        }
+        ["projects"] => {
+            let ids = json_list_param(req, "ids").unwrap_or_default();
+            // Un fixture por proyecto (por id y por slug): cada id pedido se resuelve una vez
+            let projects: Vec<serde_json::Value> = ids.iter()
+                .filter_map(|id| read_fixture_json(&format!("api/modrinth/projects/{}.json", id)))
+                .fold(Vec::new(), |mut acc, p| {
+                    if !acc.iter().any(|q| q["id"] == p["id"]) {
+                        acc.push(p);
+                    }
+                    acc
+                });
+            StubResponse::json_value(&serde_json::Value::Array(projects))
+        }
+        ["project", id] => match read_fixture_json(&format!("api/modrinth/projects/{}.json", id)) {
+            Some(body) => StubResponse::json_value(&body),
+            None => StubResponse::status(404),
+        },
+        ["project", id, "version"] => {
+            let Some(body) = read_fixture_json(&format!("api/modrinth/versions/{}.json", id)) else {
+                return StubResponse::status(404);
+            };
+            let loaders = json_list_param(req, "loaders");
+            let game_versions = json_list_param(req, "game_versions");
+            let filtered: Vec<serde_json::Value> = body.as_array().cloned().unwrap_or_default().into_iter()
+                .filter(|v| loaders.as_ref().is_none_or(|l| value_list_contains_any(v, "loaders", l)))
+                .filter(|v| game_versions.as_ref().is_none_or(|g| value_list_contains_any(v, "game_versions", g)))
+                .collect();
+            StubResponse::json_value(&serde_json::Value::Array(filtered))
+        }
+        ["version", id] => {
+            match all_fixture_entries("api/modrinth/versions", None).into_iter().find(|v| v["id"] == *id) {
+                Some(version) => StubResponse::json_value(&version),
+                None => StubResponse::status(404),
+            }
+        }
+        ["version_files"] if req.method == "POST" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let algorithm = body["algorithm"].as_str().unwrap_or("sha1").to_string();
+            let wanted: Vec<String> = body["hashes"].as_array().cloned().unwrap_or_default().iter()
+                .filter_map(|h| h.as_str().map(|s| s.to_string()))
+                .collect();
+
+            let mut found = serde_json::Map::new();
+            for version in all_fixture_entries("api/modrinth/versions", None) {
+                for file in version["files"].as_array().cloned().unwrap_or_default() {
+                    if let Some(hash) = file["hashes"][&algorithm].as_str()
+                        && wanted.iter().any(|w| w == hash)
+                    {
+                        found.insert(hash.to_string(), version.clone());
+                    }
+                }
+            }
+            StubResponse::json_value(&serde_json::Value::Object(found))
+        }
+        ["versions"] => {
+            let ids = json_list_param(req, "ids").unwrap_or_default();
+            let versions: Vec<serde_json::Value> = ids.iter()
+                .filter_map(|id| all_fixture_entries("api/modrinth/versions", None).into_iter().find(|v| v["id"] == *id))
+                .collect();
+            StubResponse::json_value(&serde_json::Value::Array(versions))
+        }
+        ["version_files", "update"] if req.method == "POST" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let algorithm = body["algorithm"].as_str().unwrap_or("sha1").to_string();
+            let strings = |key: &str| -> Vec<String> {
+                body[key].as_array().cloned().unwrap_or_default().iter().filter_map(|h| h.as_str().map(|s| s.to_string())).collect()
+            };
+            let (wanted, loaders, game_versions) = (strings("hashes"), strings("loaders"), strings("game_versions"));
+
+            // Hash → proyecto → su versión más reciente que cumpla los filtros
+            let all = all_fixture_entries("api/modrinth/versions", None);
+            let mut found = serde_json::Map::new();
+            for hash in wanted {
+                let project = all.iter().find(|v| {
+                    v["files"].as_array().is_some_and(|files| files.iter().any(|f| f["hashes"][&algorithm].as_str() == Some(hash.as_str())))
+                }).map(|v| v["project_id"].clone());
+                let Some(project) = project else { continue };
+                let latest = all.iter()
+                    .filter(|v| v["project_id"] == project)
+                    .filter(|v| loaders.is_empty() || value_list_contains_any(v, "loaders", &loaders))
+                    .filter(|v| game_versions.is_empty() || value_list_contains_any(v, "game_versions", &game_versions))
+                    .max_by_key(|v| v["date_published"].as_str().unwrap_or("").to_string());
+                if let Some(latest) = latest {
+                    found.insert(hash, latest.clone());
+                }
+            }
+            StubResponse::json_value(&serde_json::Value::Object(found))
+        }
+        _ => StubResponse::status(404),
+    }
+}
+
+/// Cuenta las peticiones que recibe el stub durante un test. Los contadores del servidor son de
+/// todo el binario, así que mientras viva un `RequestCounter` no puede haber otro: los tests que
+/// cuentan peticiones se ejecutan de uno en uno y ninguna petición ajena se mezcla en la cuenta.
+/// Todos los tests de un binario que use `RequestCounter` deben crearlo antes de hacer peticiones.
+pub struct RequestCounter {
+    _serial: MutexGuard<'static, ()>,
+    modrinth: usize,
+    curseforge: usize,
+}
+
+static COUNTING: Mutex<()> = Mutex::new(());
+
+impl RequestCounter {
+    pub fn start() -> Self {
+        start_stub_server();
+        // Un test que falla envenena el mutex; los demás pueden seguir contando
+        let serial = COUNTING.lock().unwrap_or_else(|e| e.into_inner());
+        Self {
+            _serial: serial,
+            modrinth: MODRINTH_REQUESTS.load(Ordering::SeqCst),
+            curseforge: CURSEFORGE_REQUESTS.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Peticiones a la API de Modrinth desde la última llamada (o desde `start`).
+    pub fn modrinth(&mut self) -> usize {
+        let now = MODRINTH_REQUESTS.load(Ordering::SeqCst);
+        std::mem::replace(&mut self.modrinth, now).abs_diff(now)
+    }
+
+    /// Peticiones a la API de CurseForge (con API key válida) desde la última llamada (o desde `start`).
+    pub fn curseforge(&mut self) -> usize {
+        let now = CURSEFORGE_REQUESTS.load(Ordering::SeqCst);
+        std::mem::replace(&mut self.curseforge, now).abs_diff(now)
+    }
+}
+
+/// Todas las entradas de los fixtures JSON de una carpeta (arrays, o el array bajo `field`).
+fn all_fixture_entries(dir: &str, field: Option<&str>) -> Vec<serde_json::Value> {
+    let Ok(entries) = std::fs::read_dir(fixture_path(dir)) else { return Vec::new() };
+    let mut out = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(content) = std::fs::read_to_string(entry.path()) else { continue };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+        let list = match field {
+            Some(f) => value[f].clone(),
+            None => value,
+        };
+        out.extend(list.as_array().cloned().unwrap_or_default());
+    }
+    out
+}
+
+/// Nombre del loader de CurseForge a partir de su `modLoaderType`.
+fn cf_loader_name(id: &str) -> Option<&'static str> {
+    match id {
+        "1" => Some("Forge"),
+        "4" => Some("Fabric"),
+        "5" => Some("Quilt"),
+        "6" => Some("NeoForge"),
+        _ => None,
+    }
+}
+
+fn route_curseforge(req: &StubRequest, rest: &str) -> StubResponse {
+    CURSEFORGE_REQUESTS.fetch_add(1, Ordering::SeqCst);
+    let segments: Vec<&str> = rest.split('/').collect();
+    match segments.as_slice() {
+        ["mods", "search"] => {
+            let query = req.query.get("searchFilter").map(|q| query_slug(q)).unwrap_or_default();
+            let body = read_fixture_json(&format!("api/curseforge/search/{}.json", query))
+                .unwrap_or_else(|| serde_json::json!({ "data": [] }));
+            StubResponse::json_value(&body)
+        }
+        ["mods", id, "files"] => {
+            let Some(body) = read_fixture_json(&format!("api/curseforge/files/{}.json", id)) else {
+                return StubResponse::status(404);
+            };
+            let game_version = req.query.get("gameVersion").cloned();
+            let loader = req.query.get("modLoaderType").and_then(|l| cf_loader_name(l));
+            let filtered: Vec<serde_json::Value> = body["data"].as_array().cloned().unwrap_or_default().into_iter()
+                .filter(|f| game_version.as_ref().is_none_or(|g| value_list_contains_any(f, "gameVersions", std::slice::from_ref(g))))
+                // CurseForge lista el loader dentro de gameVersions ("Forge", "Fabric"...)
+                .filter(|f| loader.is_none_or(|l| value_list_contains_any(f, "gameVersions", &[l.to_string()])))
+                .collect();
+            StubResponse::json_value(&serde_json::json!({ "data": filtered }))
+        }
+        ["mods", id, "files", file_id] => {
+            let Some(body) = read_fixture_json(&format!("api/curseforge/files/{}.json", id)) else {
+                return StubResponse::status(404);
+            };
+            match body["data"].as_array().and_then(|files| files.iter().find(|f| f["id"].as_u64().is_some_and(|id| file_id.parse() == Ok(id)))) {
+                Some(file) => StubResponse::json_value(&serde_json::json!({ "data": file })),
+                None => StubResponse::status(404),
+            }
+        }
+        ["mods"] if req.method == "POST" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let wanted: Vec<u64> = body["modIds"].as_array().cloned().unwrap_or_default().iter().filter_map(|id| id.as_u64()).collect();
+            let searched = all_fixture_entries("api/curseforge/search", Some("data"));
+            let mods: Vec<serde_json::Value> = wanted.iter()
+                .filter_map(|id| {
+                    let files = read_fixture_json(&format!("api/curseforge/files/{}.json", id))?;
+                    let mut m = searched.iter().find(|m| m["id"].as_u64() == Some(*id)).cloned()
+                        .unwrap_or_else(|| serde_json::json!({ "id": id, "name": format!("Mod {}", id), "slug": id.to_string() }));
+                    // latestFilesIndexes a partir de los archivos: una entrada por versión de MC y loader
+                    let mut indexes = Vec::new();
+                    for file in files["data"].as_array().cloned().unwrap_or_default() {
+                        let versions: Vec<String> = file["gameVersions"].as_array().cloned().unwrap_or_default().iter()
+                            .filter_map(|v| v.as_str().map(str::to_string)).collect();
+                        let loader = ["1", "4", "5", "6"].into_iter()
+                            .find(|l| cf_loader_name(l).is_some_and(|name| versions.iter().any(|v| v == name)))
+                            .and_then(|l| l.parse::<u32>().ok());
+                        for version in versions.iter().filter(|v| v.starts_with(|c: char| c.is_ascii_digit())) {
+                            indexes.push(serde_json::json!({ "gameVersion": version, "fileId": file["id"], "filename": file["fileName"], "modLoader": loader }));
+                        }
+                    }
+                    m["latestFilesIndexes"] = serde_json::Value::Array(indexes);
+                    Some(m)
+                })
+                .collect();
+            StubResponse::json_value(&serde_json::json!({ "data": mods }))
+        }
+        ["mods", "files"] if req.method == "POST" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let wanted: Vec<u64> = body["fileIds"].as_array().cloned().unwrap_or_default().iter().filter_map(|id| id.as_u64()).collect();
+            let files: Vec<serde_json::Value> = all_fixture_entries("api/curseforge/files", Some("data")).into_iter()
+                .filter(|f| f["id"].as_u64().is_some_and(|id| wanted.contains(&id)))
+                .collect();
+            StubResponse::json_value(&serde_json::json!({ "data": files }))
+        }
+        ["fingerprints", "432"] if req.method == "POST" => {
+            let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap_or_default();
+            let wanted: Vec<u64> = body["fingerprints"].as_array().cloned().unwrap_or_default().iter()
+                .filter_map(|f| f.as_u64())
+                .collect();
+
+            let matches: Vec<serde_json::Value> = all_fixture_entries("api/curseforge/files", Some("data")).into_iter()
+                .filter(|f| f["fileFingerprint"].as_u64().is_some_and(|fp| wanted.contains(&fp)))
+                .map(|f| serde_json::json!({ "id": f["modId"], "file": f, "latestFiles": [] }))
+                .collect();
+            StubResponse::json_value(&serde_json::json!({ "data": { "exactMatches": matches } }))
+        }
+        _ => StubResponse::status(404),
+    }
+}
+
+/// Peticiones recibidas por cada ruta `/flaky/<n>/...` (las `n` primeras responden 503).
+static FLAKY_HITS: OnceLock<std::sync::Mutex<HashMap<String, u32>>> = OnceLock::new();
+
+fn route_download(req: &StubRequest) -> StubResponse {
+    // `/flaky/<n>/<archivo>`: falla con 503 las primeras `n` veces para probar los reintentos
+    if let Some(rest) = req.path.strip_prefix("/flaky/")
+        && let Some((n, _)) = rest.split_once('/')
+        && let Ok(failures) = n.parse::<u32>()
+    {
+        let mut hits = FLAKY_HITS.get_or_init(Default::default).lock().unwrap();
+        let count = hits.entry(req.path.clone()).or_insert(0);
+        *count += 1;
+        if *count <= failures {
+            return StubResponse::status(503);
+        }
+    }
+
+    let filename = percent_decode(req.path.rsplit('/').next().unwrap_or_default());
+    let Ok(body) = std::fs::read(fixture_path(&format!("api/downloads/{}", filename))) else {
+        return StubResponse::status(404);
+    };
+
+    // Range "bytes=N-" → 206 con el resto del archivo
+    let range_start = req.headers.get("range")
+        .and_then(|r| r.strip_prefix("bytes="))
+        .and_then(|r| r.strip_suffix('-'))
+        .and_then(|n| n.parse::<usize>().ok());
+    match range_start {
+        Some(start) if start >= body.len() => StubResponse::status(416),
+        Some(start) => StubResponse {
+            status: 206,
+            content_type: "application/java-archive",
+            headers: vec![("Content-Range".to_string(), format!("bytes {}-{}/{}", start, body.len() - 1, body.len()))],
+            body: body[start..].to_vec(),
+        },
+        None => StubResponse { status: 200, content_type: "application/java-archive", headers: Vec::new(), body },
+    }
+}
+
+/// Decodifica `%XX` en un segmento de ruta (los CDN codifican el `+` de los nombres de archivo).
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let Ok(b) = u8::from_str_radix(&s[i + 1..i + 3], 16)
+        {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
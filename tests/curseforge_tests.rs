@@ -1,9 +1,13 @@
+mod common;
+
 use mods_updater::fetch::curseforge_api;
-use std::env;
+use mods_updater::fetch::search_provider::ContentType;
+use mods_updater::fetch::cf_api_key;
 
-// Helper to ensure we have an API key for testing. 
+// Helper to ensure we have an API key for testing (the stub server accepts `STUB_CF_KEY`).
 fn get_api_key() -> Option<String> {
-    env::var("CURSEFORGE_API_KEY").ok().filter(|k| !k.is_empty())
+    common::start_stub_server();
+    Some(cf_api_key()).filter(|k| !k.is_empty())
 }
 
 #[test]
@@ -17,7 +21,7 @@ fn test_search_basic() {
     };
 
     // Buscamos "JEI", un mod muy común
-    let results = curseforge_api::search_curseforge("JEI", &api_key, &None, &None, 0, 5);
+    let results = curseforge_api::search_curseforge("JEI", &api_key, &None, &None, 0, 5, None);
     
     assert!(!results.is_empty(), "Should return at least one result for 'JEI'");
     
@@ -38,7 +42,7 @@ fn test_search_with_filters() {
     let loader = Some("Fabric".to_string());
     let version = Some("1.20.1".to_string());
     
-    let results = curseforge_api::search_curseforge("Fabric API", &api_key, &loader, &version, 0, 5);
+    let results = curseforge_api::search_curseforge("Fabric API", &api_key, &loader, &version, 0, 5, None);
 
     assert!(!results.is_empty(), "Should return results for Fabric API 1.20.1");
     assert!(results[0].name.contains("Fabric"), "First result should likely contain 'Fabric'");
@@ -54,7 +58,7 @@ fn test_search_no_results() {
         }
     };
 
-    let results = curseforge_api::search_curseforge("sdlfkjhsldkfjhsdlkfjh", &api_key, &None, &None, 0, 5);
+    let results = curseforge_api::search_curseforge("sdlfkjhsldkfjhsdlkfjh", &api_key, &None, &None, 0, 5, None);
     assert!(results.is_empty(), "Should return no results for random garbage string");
 }
 
@@ -88,7 +92,8 @@ fn test_fetch_version_file() {
         238222, 
         "1.20.1", 
         "Forge", 
-        &api_key
+        &api_key,
+        &ContentType::Mod,
     );
 
     assert!(file.is_some(), "Should find a file for JEI 1.20.1 (Forge)");
@@ -111,7 +116,8 @@ fn test_fetch_file_invalid_params() {
         238222, 
         "0.0.1-beta-alpha-omega", 
         "Forge", 
-        &api_key
+        &api_key,
+        &ContentType::Mod,
     );
 
     assert!(file.is_none(), "Should NOT find a file for invalid version");
@@ -119,7 +125,8 @@ fn test_fetch_file_invalid_params() {
 
 #[test]
 fn test_invalid_api_key() {
-    let results = curseforge_api::search_curseforge("JEI", "INVALID_KEY_12345", &None, &None, 0, 5);
+    common::start_stub_server();
+    let results = curseforge_api::search_curseforge("JEI", "INVALID_KEY_12345", &None, &None, 0, 5, None);
     assert!(results.is_empty(), "Should return empty results with invalid API key");
 }
 
@@ -133,7 +140,8 @@ fn test_curseforge_has_capacity() {
 fn test_curseforge_is_available_without_key() {
     // Sin la variable de entorno CURSEFORGE_API_KEY, is_available() debería devolver false
     // (a menos que esté configurada en el entorno de test)
-    let has_key = env::var("CURSEFORGE_API_KEY").is_ok_and(|k| !k.is_empty());
+    common::start_stub_server();
+    let has_key = !cf_api_key().is_empty();
     let available = curseforge_api::is_available();
     
     if has_key {
@@ -0,0 +1,140 @@
+mod common;
+
+use mods_updater::local_datapacks_ops::pack_format_table::{FormatRange, PackFormatTable, PackKind, PackVersion};
+use mods_updater::local_datapacks_ops::fs_ops::{unzip_datapack, zip_datapack_folder};
+use mods_updater::local_datapacks_ops::{is_datapack_path, pack_stamp, read_datapack_cached, read_single_datapack};
+use mods_updater::local_mods_ops::cache::{get_datapack, init_with_path, prune_db, upsert_datapack};
+
+#[test]
+fn test_pack_format_table_covers_minor_formats() {
+    let table = PackFormatTable::embedded();
+    assert!(!table.entries(PackKind::Data).is_empty(), "The embedded table must parse");
+
+    let range = |min: PackVersion, max: PackVersion| vec![FormatRange { min, max }];
+    let whole = |major| PackVersion::new(major, None);
+    assert_eq!(table.releases_in(PackKind::Data, &range(whole(15), whole(15))), vec!["1.20", "1.20.1"]);
+    assert_eq!(table.releases_in(PackKind::Resource, &range(whole(15), whole(15))), vec!["1.20", "1.20.1"]);
+    assert_eq!(table.releases_in(PackKind::Resource, &range(whole(34), whole(34))), vec!["1.21", "1.21.1"]);
+    // Un entero como máximo abarca todas las minor; [88, 0] como mínimo no incluye 81
+    assert_eq!(table.releases_in(PackKind::Data, &range(PackVersion::new(88, Some(0)), whole(94))), vec!["1.21.9", "1.21.10", "1.21.11"]);
+    assert_eq!(table.releases_in(PackKind::Data, &range(whole(94), PackVersion::new(94, Some(0)))), Vec::<String>::new());
+
+    // La tabla del usuario corrige y amplía la embebida
+    let mut table = table;
+    let user = PackFormatTable::from_json(r#"{ "data": [ { "format": 15, "releases": ["1.20.1"] }, { "format": "101.2", "releases": ["1.22"] } ] }"#).unwrap();
+    table.merge(user);
+    assert_eq!(table.releases_in(PackKind::Data, &range(whole(15), whole(15))), vec!["1.20.1"]);
+    assert_eq!(table.entries(PackKind::Data).last().map(|e| e.format), Some(PackVersion::new(101, Some(2))));
+    assert!(PackFormatTable::from_json(r#"{ "data": [ { "format": "x", "releases": [] } ] }"#).is_err());
+}
+
+#[test]
+fn test_read_datapack_formats_and_overlays() {
+    let dir = common::temp_dir("datapack_formats");
+
+    let modern = dir.join("modern.zip");
+    let mcmeta = r#"{
+        "pack": { "description": "", "min_format": [88, 0], "max_format": 94.1 },
+        "overlays": { "entries": [
+            { "directory": "legacy", "formats": { "min_inclusive": 48, "max_inclusive": 57 } },
+            { "directory": "broken" }
+        ] }
+    }"#;
+    common::write_jar(&modern, &[("pack.mcmeta", mcmeta.as_bytes()), ("data/terrain/worldgen/a.json", b"{}")]);
+    let info = read_single_datapack(&modern).unwrap();
+    assert_eq!(info.kind, PackKind::Data);
+    assert_eq!(info.pack_format, None);
+    assert_eq!(info.supported_formats.map(|r| r.to_string()).as_deref(), Some("88.0-94.1"));
+    assert_eq!(info.overlays.len(), 1, "Overlays without formats are ignored");
+    assert_eq!(info.overlays[0].directory, "legacy");
+    assert_eq!(info.mc_versions, vec!["1.21", "1.21.1", "1.21.2", "1.21.3", "1.21.9", "1.21.10", "1.21.11"]);
+    assert_eq!(info.mc_version.as_deref(), Some("1.21 – 1.21.3, 1.21.9 – 1.21.11"), "Gaps in the table split the summary");
+
+    // Formato antiguo (float) y un resource pack: misma tabla, otra numeración
+    let resource = dir.join("resource.zip");
+    common::write_jar(&resource, &[("pack.mcmeta", br#"{ "pack": { "pack_format": 34.0, "supported_formats": [34, 200] } }"#), ("assets/minecraft/lang/en_us.json", b"{}")]);
+    let info = read_single_datapack(&resource).unwrap();
+    assert_eq!(info.kind, PackKind::Resource);
+    assert_eq!(info.pack_format, Some(PackVersion::new(34, Some(0))));
+    assert_eq!(info.mc_versions.first().map(String::as_str), Some("1.21"));
+    assert_eq!(info.mc_version.as_deref(), Some("1.21 – 1.21.11+"), "Formats beyond the table are flagged");
+}
+
+#[test]
+fn test_datapack_cache_by_world_and_mtime() {
+    let dir = common::temp_dir("datapack_cache");
+    init_with_path(dir.join("cache.redb"));
+    let pack = dir.join("terralith.zip");
+    common::write_jar(&pack, &[("pack.mcmeta", br#"{ "pack": { "pack_format": 48 } }"#), ("data/terralith/a.json", b"{}")]);
+
+    let first = read_datapack_cached("World", &pack).unwrap();
+    assert_eq!(first.mc_version.as_deref(), Some("1.21 – 1.21.1"));
+    assert!(get_datapack("World", "terralith.zip").is_some());
+    assert!(get_datapack("Other", "terralith.zip").is_none(), "Entries are per world");
+
+    // Con tamaño y mtime iguales no se reabre el zip: lo que haya en la caché es lo que se devuelve
+    let mut tampered = first.clone();
+    tampered.name = "From cache".to_string();
+    upsert_datapack("World", &pack, &tampered);
+    assert_eq!(read_datapack_cached("World", &pack).unwrap().name, "From cache");
+
+    // Si el archivo cambia, se vuelve a leer
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    common::write_jar(&pack, &[("pack.mcmeta", br#"{ "pack": { "pack_format": 61 } }"#), ("data/terralith/a.json", b"{}")]);
+    let reread = read_datapack_cached("World", &pack).unwrap();
+    assert_eq!(reread.name, "terralith");
+    assert_eq!(reread.mc_version.as_deref(), Some("1.21.4"));
+
+    // prune_db descarta los packs cuyo archivo ya no existe
+    std::fs::remove_file(&pack).unwrap();
+    assert_eq!(prune_db(&std::collections::HashSet::new()), 1);
+    assert!(get_datapack("World", "terralith.zip").is_none());
+}
+
+#[test]
+fn test_folder_datapacks_and_repacking() {
+    let dir = common::temp_dir("datapack_folders");
+    let folder = dir.join("my_pack");
+    std::fs::create_dir_all(folder.join("data/mypack/function")).unwrap();
+    std::fs::create_dir_all(folder.join("data/minecraft/tags")).unwrap();
+    std::fs::write(folder.join("pack.mcmeta"), r#"{ "pack": { "pack_format": 48, "description": "dev" } }"#).unwrap();
+    std::fs::write(folder.join("data/mypack/function/tick.mcfunction"), "say hi").unwrap();
+    std::fs::create_dir_all(dir.join("not_a_pack")).unwrap();
+
+    assert!(is_datapack_path(&folder));
+    assert!(!is_datapack_path(&dir.join("not_a_pack")), "Folders need a pack.mcmeta");
+    let info = read_single_datapack(&folder).unwrap();
+    assert!(info.is_folder);
+    assert_eq!(info.key, "my_pack");
+    assert_eq!(info.detected_project_id.as_deref(), Some("mypack"));
+    assert_eq!(info.mc_version.as_deref(), Some("1.21 – 1.21.1"));
+
+    // Editar un archivo de dentro cambia la marca con la que se invalida la caché
+    let before = pack_stamp(&folder);
+    std::fs::write(folder.join("data/mypack/function/tick.mcfunction"), "say hello world").unwrap();
+    assert_ne!(pack_stamp(&folder), before);
+
+    // ZIP: crea my_pack.zip con el mismo contenido y conserva la carpeta
+    let zipped = zip_datapack_folder(&folder).unwrap();
+    assert_eq!(zipped.new_path, dir.join("my_pack.zip"));
+    assert!(!zipped.source_removed && folder.is_dir());
+    let from_zip = read_single_datapack(&zipped.new_path).unwrap();
+    assert!(!from_zip.is_folder);
+    assert_eq!(from_zip.detected_project_id.as_deref(), Some("mypack"));
+    assert!(zip_datapack_folder(&folder).is_err(), "An existing zip is never overwritten");
+
+    // UNZIP: no pisa una carpeta existente; con el destino libre extrae y quita el zip
+    assert!(unzip_datapack(&zipped.new_path).is_err());
+    let moved = dir.join("other.zip");
+    std::fs::rename(&zipped.new_path, &moved).unwrap();
+    let unzipped = unzip_datapack(&moved).unwrap();
+    assert_eq!(unzipped.new_path, dir.join("other"));
+    assert!(unzipped.source_removed && !moved.exists());
+    assert_eq!(std::fs::read_to_string(dir.join("other/data/mypack/function/tick.mcfunction")).unwrap(), "say hello world");
+
+    // Un zip sin pack.mcmeta en la raíz no se descomprime
+    let bad = dir.join("bad.zip");
+    common::write_jar(&bad, &[("inner/pack.mcmeta", b"{}")]);
+    assert!(unzip_datapack(&bad).is_err());
+    assert!(bad.exists() && !dir.join("bad").exists() && !dir.join("bad.unzipping").exists());
+}
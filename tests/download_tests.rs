@@ -0,0 +1,160 @@
+mod common;
+
+use mods_updater::fetch::fetch_from_api;
+use mods_updater::fetch::progress::{format_bytes, format_eta, TransferStats, TransferUpdate};
+use mods_updater::fetch::search_provider::ContentType;
+use mods_updater::local_mods_ops::hashing::ExpectedFile;
+
+/// Flujo completo contra el servidor stub: resolver la versión y descargar el archivo
+/// desde el host de descargas configurado en lugar del CDN real.
+#[test]
+fn test_resolve_and_download_from_mirror() {
+    common::start_stub_server();
+    let out = common::temp_dir("download_mirror");
+
+    let info = fetch_from_api::find_mod_download("Sodium", Some("AANobbMI"), "1.20.1", "fabric", "", &ContentType::Mod)
+        .expect("Should resolve Sodium for 1.20.1");
+    assert!(info.url.starts_with("https://cdn.modrinth.com/"), "Resolved URL keeps the original CDN host");
+
+    assert!(info.expected.sha512.is_some() && info.expected.size.is_some(), "Modrinth publishes size and hashes");
+    let mut reports: Vec<(u64, Option<u64>)> = Vec::new();
+    fetch_from_api::download_mod_file(&info.url, &out.to_string_lossy(), &info.filename, &info.expected, |u| if let TransferUpdate::Bytes { downloaded, total } = u { reports.push((downloaded, total)) })
+        .expect("Download through the mirror should succeed");
+    let size = info.expected.size.unwrap();
+    assert_eq!(reports.first(), Some(&(0, Some(size))), "Progress starts at 0 with the Content-Length");
+    assert_eq!(reports.last(), Some(&(size, Some(size))), "Progress always ends with the full size");
+
+    let downloaded = std::fs::read(out.join(&info.filename)).expect("Downloaded file should exist");
+    let expected = std::fs::read(common::fixture_path(&format!("api/downloads/{}", info.filename))).unwrap();
+    assert_eq!(downloaded, expected);
+    assert!(!out.join(format!("{}.part", info.filename)).exists(), "No .part file should remain");
+}
+
+#[test]
+fn test_download_missing_file_fails() {
+    common::start_stub_server();
+    let out = common::temp_dir("download_missing");
+
+    let result = fetch_from_api::download_mod_file(
+        "https://cdn.modrinth.com/data/XXXX/versions/YYYY/missing.jar",
+        &out.to_string_lossy(),
+        "missing.jar",
+        &ExpectedFile::default(),
+        |_| {},
+    );
+    assert!(result.is_err(), "A 404 from the mirror should be an error");
+    assert!(!out.join("missing.jar").exists(), "No file should be written on 404");
+}
+
+#[test]
+fn test_download_hash_mismatch_is_rejected() {
+    common::start_stub_server();
+    let out = common::temp_dir("download_mismatch");
+
+    let info = fetch_from_api::find_mod_download("JEI", Some("238222"), "1.20.1", "forge", common::STUB_CF_KEY, &ContentType::Mod)
+        .expect("Should resolve JEI on CurseForge");
+    let mut tampered = info.expected.clone();
+    tampered.sha1 = Some("0000000000000000000000000000000000000000".to_string());
+
+    let err = fetch_from_api::download_mod_file(&info.url, &out.to_string_lossy(), &info.filename, &tampered, |_| {})
+        .expect_err("A SHA-1 mismatch should fail the download");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(!out.join(&info.filename).exists(), "Corrupt file must not reach the modpack");
+    assert!(!out.join(format!("{}.part", info.filename)).exists(), "The .part file should be cleaned up");
+
+    // Con lo publicado de verdad, la misma descarga pasa
+    fetch_from_api::download_mod_file(&info.url, &out.to_string_lossy(), &info.filename, &info.expected, |_| {})
+        .expect("Published size and SHA-1 should match");
+}
+
+#[test]
+fn test_download_url_rewrite() {
+    let base = common::start_stub_server();
+    assert_eq!(
+        mods_updater::fetch::download_url("https://edge.forgecdn.net/files/4712/866/jei.jar?x=1"),
+        format!("{}/files/4712/866/jei.jar?x=1", base)
+    );
+}
+
+#[test]
+fn test_download_resumes_partial_file() {
+    let base = common::start_stub_server();
+    let out = common::temp_dir("download_resume");
+    let filename = "sodium-fabric-0.6.0+mc1.21.1.jar";
+    let full = std::fs::read(common::fixture_path(&format!("api/downloads/{}", filename))).unwrap();
+    let hashes = mods_updater::local_mods_ops::hashing::hash_bytes(&full);
+    let expected = ExpectedFile { size: Some(full.len() as u64), sha1: Some(hashes.sha1), sha512: Some(hashes.sha512), ..Default::default() };
+
+    // Descarga interrumpida a medias
+    std::fs::write(out.join(format!("{}.part", filename)), &full[..10]).unwrap();
+
+    let mut first = None;
+    fetch_from_api::download_mod_file(&format!("{}/files/{}", base, filename), &out.to_string_lossy(), filename, &expected, |u| {
+        if let TransferUpdate::Bytes { downloaded, .. } = u { first.get_or_insert(downloaded); }
+    }).expect("Resumed download should succeed");
+
+    assert_eq!(first, Some(10), "Download continues from the existing .part");
+    assert_eq!(std::fs::read(out.join(filename)).unwrap(), full);
+}
+
+#[test]
+fn test_download_without_expected_discards_partial_file() {
+    let base = common::start_stub_server();
+    let out = common::temp_dir("download_no_expected");
+    let filename = "sodium-fabric-0.6.0+mc1.21.1.jar";
+    let full = std::fs::read(common::fixture_path(&format!("api/downloads/{}", filename))).unwrap();
+
+    // Un .part de otro archivo: sin tamaño ni hash no se puede comprobar, así que no se continúa
+    std::fs::write(out.join(format!("{}.part", filename)), b"stale bytes").unwrap();
+
+    let mut first = None;
+    fetch_from_api::download_mod_file(&format!("{}/files/{}", base, filename), &out.to_string_lossy(), filename, &ExpectedFile::default(), |u| {
+        if let TransferUpdate::Bytes { downloaded, .. } = u { first.get_or_insert(downloaded); }
+    }).expect("Download should succeed");
+
+    assert_ne!(first, Some(11), "The stale .part is not resumed");
+    assert_eq!(std::fs::read(out.join(filename)).unwrap(), full);
+}
+
+#[test]
+fn test_download_retries_transient_errors() {
+    let base = common::start_stub_server();
+    let out = common::temp_dir("download_retry");
+    let filename = "jei-1.20.1-forge-15.2.0.27.jar";
+
+    let mut retries = Vec::new();
+    fetch_from_api::download_mod_file(&format!("{}/flaky/2/{}", base, filename), &out.to_string_lossy(), filename, &ExpectedFile::default(), |u| {
+        if let TransferUpdate::Retry { attempt, .. } = u { retries.push(attempt); }
+    }).expect("Should succeed after two 503s");
+
+    assert_eq!(retries, vec![1, 2]);
+    assert!(out.join(filename).exists());
+}
+
+#[test]
+fn test_transfer_stats_estimates_remaining() {
+    let mut stats = TransferStats::new();
+    stats.start("a.jar");
+    stats.record("a.jar", 400, Some(1000));
+    stats.start("b.jar");
+    stats.record("b.jar", 3000, Some(3000));
+    stats.finish("b.jar");
+
+    assert_eq!(stats.downloaded_bytes(), 3400);
+    // a.jar: 600 pendientes + 2 archivos sin empezar a tamaño medio (2000)
+    assert_eq!(stats.remaining_bytes(4), 600 + 2 * 2000);
+
+    // Con todo terminado, el siguiente archivo empieza un lote nuevo
+    stats.finish("a.jar");
+    stats.start("c.jar");
+    assert_eq!(stats.downloaded_bytes(), 0);
+}
+
+#[test]
+fn test_progress_formatting() {
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(1536), "1.5 KB");
+    assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    assert_eq!(format_eta(std::time::Duration::from_secs(42)), "0:42");
+    assert_eq!(format_eta(std::time::Duration::from_secs(3725)), "1:02:05");
+}
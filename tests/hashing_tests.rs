@@ -0,0 +1,88 @@
+mod common;
+
+use mods_updater::fetch::fetch_from_api;
+use mods_updater::local_mods_ops::hashing::{curseforge_fingerprint, hash_bytes, hash_file, verify_file, ExpectedFile};
+use mods_updater::local_mods_ops::{read_single_mod, ModInfo};
+
+#[test]
+fn test_sha_digests() {
+    let h = hash_bytes(b"abc");
+    assert_eq!(h.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(
+        h.sha512,
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+    );
+}
+
+#[test]
+fn test_curseforge_fingerprint_ignores_whitespace() {
+    assert_eq!(curseforge_fingerprint(b"a b\nc\r\t"), curseforge_fingerprint(b"abc"));
+    assert_eq!(curseforge_fingerprint(b"abc"), 1621425345);
+
+    // Mismo valor que publica el fixture de CurseForge para este archivo
+    let jei = hash_file(&common::fixture_path("api/downloads/jei-1.20.1-forge-15.2.0.27.jar")).unwrap();
+    assert_eq!(jei.cf_fingerprint, 3488238490);
+}
+
+#[test]
+fn test_verify_file_size_and_hashes() {
+    let dir = common::temp_dir("hashing_verify");
+    let path = dir.join("file.jar");
+    std::fs::write(&path, b"abc").unwrap();
+    let h = hash_bytes(b"abc");
+
+    let ok = ExpectedFile { size: Some(3), sha1: Some(h.sha1.to_uppercase()), sha512: Some(h.sha512), ..Default::default() };
+    assert!(verify_file(&path, &ok).is_ok());
+    assert!(verify_file(&path, &ExpectedFile::default()).is_ok(), "Nothing published, nothing to check");
+
+    let truncated = ExpectedFile { size: Some(4), ..ok.clone() };
+    assert!(verify_file(&path, &truncated).is_err());
+
+    let tampered = ExpectedFile { sha512: Some("00".repeat(64)), ..ok };
+    assert!(verify_file(&path, &tampered).is_err());
+}
+
+#[test]
+fn test_read_single_mod_stores_hashes() {
+    let dir = common::temp_dir("hashing_read_single");
+    let jar = dir.join("examplemod-1.0.0.jar");
+    common::write_jar(&jar, &[(
+        "fabric.mod.json",
+        br#"{"schemaVersion":1,"id":"examplemod","version":"1.0.0","name":"Example Mod"}"#,
+    )]);
+
+    let info = read_single_mod(&jar).expect("Should parse the fabric jar");
+    assert_eq!(info.hashes, Some(hash_file(&jar).unwrap()));
+}
+
+#[test]
+fn test_identify_mods_by_hash() {
+    common::start_stub_server();
+
+    let with_hash = |key: &str, file: &str| ModInfo {
+        key: key.to_string(),
+        name: "Nombre genérico".to_string(),
+        hashes: Some(hash_file(&common::fixture_path(&format!("api/downloads/{}", file))).unwrap()),
+        ..Default::default()
+    };
+    let unknown = ModInfo {
+        key: "unknown.jar".to_string(),
+        hashes: Some(hash_bytes(b"not published anywhere")),
+        ..Default::default()
+    };
+    let no_hash = ModInfo { key: "placeholder.jar".to_string(), ..Default::default() };
+
+    let mods = vec![
+        with_hash("sodium.jar", "sodium-fabric-0.5.3+mc1.20.1.jar"),
+        with_hash("jei.jar", "jei-1.20.1-forge-15.2.0.27.jar"),
+        unknown,
+        no_hash,
+    ];
+    let identified = fetch_from_api::identify_mods_by_hash(&mods, common::STUB_CF_KEY);
+
+    // Modrinth por SHA-1, CurseForge por fingerprint para lo que Modrinth no conoce
+    assert_eq!(identified.get("sodium.jar").map(String::as_str), Some("AANobbMI"));
+    assert_eq!(identified.get("jei.jar").map(String::as_str), Some("238222"));
+    assert!(!identified.contains_key("unknown.jar"));
+    assert!(!identified.contains_key("placeholder.jar"));
+}
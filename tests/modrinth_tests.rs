@@ -1,9 +1,17 @@
+mod common;
+
+use std::collections::HashSet;
+
+use mods_updater::fetch::fetch_from_api::{resolve_all_dependencies, DependencyKind};
 use mods_updater::fetch::modrinth_api;
+use mods_updater::fetch::search_provider::ContentType;
 
 #[test]
 fn test_modrinth_search_basic() {
+    common::start_stub_server();
+
     // Buscamos "Sodium", muy popular
-    let results = modrinth_api::search_modrinth_project("Sodium", &None, &None, 0, 5);
+    let results = modrinth_api::search_modrinth_project("Sodium", &None, &None, 0, 5, &[]);
     
     assert!(!results.is_empty(), "Should return results for 'Sodium'");
     assert!(results[0].title.contains("Sodium"), "First result should be related to Sodium");
@@ -11,29 +19,101 @@ fn test_modrinth_search_basic() {
 
 #[test]
 fn test_modrinth_version_lookup() {
+    common::start_stub_server();
+
     // Sodium ID: AANobbMI
     // Buscamos versión para Fabric 1.20.1
-    let version = modrinth_api::fetch_modrinth_version("AANobbMI", "1.20.1", "fabric");
+    let version = modrinth_api::fetch_modrinth_version("AANobbMI", "1.20.1", "fabric", &ContentType::Mod);
     
     assert!(version.is_some(), "Should find Sodium for Fabric 1.20.1");
     let v = version.unwrap();
+    assert!(v.game_versions.contains(&"1.20.1".to_string()), "Should return the 1.20.1 build, not the newest one");
     assert!(v.first_file().is_some(), "Should have a file to download");
 }
 
+#[test]
+fn test_modrinth_version_lookup_no_match() {
+    common::start_stub_server();
+
+    let version = modrinth_api::fetch_modrinth_version("AANobbMI", "1.20.1", "forge", &ContentType::Mod);
+    assert!(version.is_none(), "Sodium has no Forge build in the fixtures");
+}
+
 #[test]
 fn test_modrinth_facets() {
+    common::start_stub_server();
+
     // Buscamos "Fabric API" filtrando por versión y loader
     let loader = Some("Fabric".to_string());
     let version = Some("1.20.1".to_string());
     
-    let results = modrinth_api::search_modrinth_project("Fabric API", &loader, &version, 0, 5);
+    let results = modrinth_api::search_modrinth_project("Fabric API", &loader, &version, 0, 5, &[]);
     
     assert!(!results.is_empty(), "Should return results");
     assert!(results[0].title.contains("Fabric API"), "Should find Fabric API");
 }
 
+#[test]
+fn test_modrinth_project_info() {
+    common::start_stub_server();
+
+    let info = modrinth_api::fetch_modrinth_project_info("AANobbMI");
+    assert_eq!(info, Some(("Sodium".to_string(), "sodium".to_string())));
+}
+
 #[test]
 fn test_modrinth_has_capacity() {
     // Al inicio siempre debería tener capacidad (300 requests, umbral 10)
     assert!(modrinth_api::has_capacity(), "Should have capacity at startup");
 }
+
+#[test]
+fn test_resolve_dependency_types() {
+    common::start_stub_server();
+
+    // Iris: Sodium obligatorio fijado a la build de 1.21.1, Fabric API opcional, una librería
+    // embebida y dos incompatibles (solo una instalada)
+    let installed: HashSet<String> = HashSet::from(["OPTIFINE".to_string()]);
+    let resolution = resolve_all_dependencies("YL57xq9U", "1.20.1", "fabric", common::STUB_CF_KEY, &installed);
+
+    let required: Vec<&str> = resolution.required.iter().map(|d| d.filename.as_str()).collect();
+    assert_eq!(required, vec!["sodium-fabric-0.6.0+mc1.21.1.jar"], "The pinned version wins over the newest 1.20.1 build");
+    assert_eq!(resolution.required[0].project_id, "AANobbMI");
+    assert_eq!(resolution.required[0].name, "Sodium");
+
+    let optional: Vec<&str> = resolution.optional.iter().map(|d| d.project_id.as_str()).collect();
+    assert_eq!(optional, vec!["P7dR8mSH"]);
+
+    assert_eq!(resolution.conflicts.len(), 1, "{:?}", resolution.conflicts);
+    assert_eq!(resolution.conflicts[0].mod_id, "OPTIFINE");
+    assert_eq!(resolution.conflicts[0].declared_by, "Iris Shaders");
+
+    // Lo instalado se conoce por slug: la incompatibilidad por project ID se cruza con él
+    let installed: HashSet<String> = HashSet::from(["not-here".to_string()]);
+    let resolution = resolve_all_dependencies("YL57xq9U", "1.20.1", "fabric", common::STUB_CF_KEY, &installed);
+    let conflicts: Vec<(&str, &str)> = resolution.conflicts.iter().map(|c| (c.mod_id.as_str(), c.declared_by.as_str())).collect();
+    assert_eq!(conflicts, vec![("not-here", "Iris Shaders")]);
+
+    // Sodium ya instalado: la dependencia fijada solo por version_id se reconoce por su proyecto
+    let installed: HashSet<String> = HashSet::from(["AANobbMI".to_string(), "P7dR8mSH".to_string()]);
+    let resolution = resolve_all_dependencies("YL57xq9U", "1.20.1", "fabric", common::STUB_CF_KEY, &installed);
+    assert!(resolution.required.is_empty(), "{:?}", resolution.required);
+    assert!(resolution.optional.is_empty());
+    assert!(resolution.conflicts.is_empty());
+
+    // Fabric API instalado (por slug): la opcional tampoco se vuelve a ofrecer
+    let installed: HashSet<String> = HashSet::from(["fabric-api".to_string()]);
+    let resolution = resolve_all_dependencies("YL57xq9U", "1.20.1", "fabric", common::STUB_CF_KEY, &installed);
+    assert!(resolution.optional.is_empty(), "{:?}", resolution.optional);
+    assert_eq!(resolution.required.len(), 1);
+}
+
+#[test]
+fn test_curseforge_relation_types() {
+    assert_eq!(DependencyKind::from_curseforge(3), Some(DependencyKind::Required));
+    assert_eq!(DependencyKind::from_curseforge(2), Some(DependencyKind::Optional));
+    assert_eq!(DependencyKind::from_curseforge(5), Some(DependencyKind::Incompatible));
+    assert_eq!(DependencyKind::from_curseforge(1), Some(DependencyKind::Embedded));
+    assert_eq!(DependencyKind::from_curseforge(6), Some(DependencyKind::Embedded));
+    assert_eq!(DependencyKind::from_curseforge(4), None, "Tools are not game dependencies");
+}
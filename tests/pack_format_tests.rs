@@ -0,0 +1,449 @@
+mod common;
+
+use std::path::Path;
+
+use mods_updater::fetch::fetch_from_api;
+use mods_updater::local_mods_ops::hashing::{curseforge_fingerprint, hash_file, sha256_hex, verify_bytes, ExpectedFile};
+use mods_updater::local_mods_ops::{ModInfo, PinnedFile};
+use mods_updater::pack_formats::curseforge::read_manifest;
+use mods_updater::pack_formats::mrpack::read_index;
+use mods_updater::pack_formats::packwiz::{metafile_slug, ModToml};
+use mods_updater::pack_formats::{export_pack, export_scratch_dir, import_pack, safe_relative_path, unique_pack_name, ExportOptions, PackFormat};
+use mods_updater::profiles::{Profile, ProfilesDatabase};
+
+fn mrpack_file(path: &str, fixture: &str, url: &str, env: Option<(&str, &str)>) -> serde_json::Value {
+    let hashes = hash_file(&common::fixture_path(&format!("api/downloads/{}", fixture))).unwrap();
+    let size = std::fs::metadata(common::fixture_path(&format!("api/downloads/{}", fixture))).unwrap().len();
+    let mut file = serde_json::json!({
+        "path": path,
+        "hashes": { "sha1": hashes.sha1, "sha512": hashes.sha512 },
+        "downloads": [url],
+        "fileSize": size,
+    });
+    if let Some((client, server)) = env {
+        file["env"] = serde_json::json!({ "client": client, "server": server });
+    }
+    file
+}
+
+fn write_test_mrpack(path: &Path) {
+    let index = serde_json::json!({
+        "formatVersion": 1,
+        "game": "minecraft",
+        "versionId": "1.0.0",
+        "name": "Test Pack",
+        "summary": "Pack de prueba",
+        "files": [
+            mrpack_file("mods/sodium-fabric-0.5.3+mc1.20.1.jar", "sodium-fabric-0.5.3+mc1.20.1.jar",
+                "https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium-fabric-0.5.3%2Bmc1.20.1.jar", None),
+            mrpack_file("mods/fabric-api-0.92.2+1.20.1.jar", "fabric-api-0.92.2+1.20.1.jar",
+                "https://cdn.modrinth.com/data/P7dR8mSH/versions/YblXvXbj/fabric-api-0.92.2%2B1.20.1.jar", Some(("required", "required"))),
+            mrpack_file("mods/server-only.jar", "jei-1.20.1-forge-15.2.0.27.jar",
+                "https://cdn.modrinth.com/data/x/versions/y/server-only.jar", Some(("unsupported", "required"))),
+            mrpack_file("../escape.jar", "jei-1.20.1-forge-15.2.0.27.jar",
+                "https://cdn.modrinth.com/data/x/versions/y/escape.jar", None),
+            mrpack_file("mods/evil.jar", "jei-1.20.1-forge-15.2.0.27.jar", "https://evil.example.com/evil.jar", None),
+        ],
+        "dependencies": { "minecraft": "1.20.1", "fabric-loader": "0.15.11" },
+    });
+    let index = serde_json::to_vec(&index).unwrap();
+    common::write_jar(path, &[
+        ("modrinth.index.json", index.as_slice()),
+        ("overrides/config/sodium-options.json", b"{}"),
+        ("overrides/mods/local-only.jar", b"local jar"),
+        ("overrides/../../outside.txt", b"nope"),
+        ("client-overrides/options.txt", b"fov:90"),
+    ]);
+}
+
+#[test]
+fn test_import_mrpack() {
+    common::start_stub_server();
+    let dir = common::temp_dir("pack_import_mrpack");
+    let root = dir.join("modpacks");
+    let pack = dir.join("Test Pack.mrpack");
+    write_test_mrpack(&pack);
+
+    let imported = import_pack(&pack, &ProfilesDatabase::new(), &root).expect("Import should succeed");
+
+    let profile = &imported.profile;
+    assert_eq!(profile.name, "Test Pack");
+    assert_eq!(profile.game_version.as_deref(), Some("1.20.1"));
+    assert_eq!(profile.loader.as_deref(), Some("Fabric"));
+    assert_eq!(profile.loader_version.as_deref(), Some("0.15.11"));
+    assert_eq!(profile.mods.len(), 2, "Server-only, unsafe and non-allowed entries are skipped");
+
+    let sodium = &profile.mods["sodium-fabric-0.5.3+mc1.20.1.jar"];
+    assert_eq!(sodium.confirmed_project_id.as_deref(), Some("AANobbMI"));
+    let pin = sodium.pinned.as_ref().expect("Imported mods are pinned");
+    assert_eq!(pin.version_id.as_deref(), Some("OihdIimA"));
+    assert!(pin.expected.sha512.is_some());
+    assert_eq!(imported.warnings.len(), 3, "Unsafe file path, disallowed host and unsafe override: {:?}", imported.warnings);
+
+    // Overrides: jars a la raíz del modpack, el resto a overrides/; nada fuera de la carpeta
+    let folder = root.join("Test Pack");
+    assert_eq!(imported.folder, folder);
+    assert_eq!(imported.overrides_extracted, 3);
+    assert!(folder.join("local-only.jar").exists());
+    assert!(folder.join("overrides/config/sodium-options.json").exists());
+    assert!(folder.join("overrides/options.txt").exists());
+    assert!(!dir.join("outside.txt").exists() && !root.join("outside.txt").exists());
+
+    // Las descargas van fijadas y con hash
+    let jobs = imported.download_jobs();
+    assert_eq!(jobs.len(), 2);
+    for job in &jobs {
+        let info = job.pre_resolved.as_ref().expect("Pinned jobs skip resolution");
+        fetch_from_api::download_mod_file(&info.url, &job.output_folder, &info.filename, &info.expected, |_| {})
+            .expect("Pinned download should verify");
+        assert!(folder.join(&info.filename).exists());
+    }
+}
+
+fn export_options(local_folder: &Path) -> ExportOptions {
+    ExportOptions {
+        game_version: "1.20.1".to_string(),
+        loader: "Fabric".to_string(),
+        loader_version: "0.15.11".to_string(),
+        version_id: "1.0.0".to_string(),
+        local_folder: Some(local_folder.to_path_buf()),
+    }
+}
+
+#[test]
+fn test_export_mrpack() {
+    common::start_stub_server();
+    let dir = common::temp_dir("pack_export_mrpack");
+    let local = dir.join("modpacks/Export Pack");
+    std::fs::create_dir_all(&local).unwrap();
+    std::fs::copy(
+        common::fixture_path("api/downloads/fabric-api-0.92.2+1.20.1.jar"),
+        local.join("fabric-api-0.92.2+1.20.1.jar"),
+    ).unwrap();
+    common::write_jar(&local.join("unknown-local.jar"), &[("fabric.mod.json", b"{}")]);
+
+    let sodium_path = common::fixture_path("api/downloads/sodium-fabric-0.5.3+mc1.20.1.jar");
+    let sodium_hashes = hash_file(&sodium_path).unwrap();
+    let mut profile = Profile::new("Export Pack".to_string(), Some("Pack exportado".to_string()));
+    profile.game_version = Some("1.20.1".to_string());
+    let mods = [
+        ModInfo {
+            key: "sodium-fabric-0.5.3+mc1.20.1.jar".to_string(),
+            name: "Sodium".to_string(),
+            confirmed_project_id: Some("AANobbMI".to_string()),
+            pinned: Some(PinnedFile {
+                url: "https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium-fabric-0.5.3%2Bmc1.20.1.jar".to_string(),
+                filename: "sodium-fabric-0.5.3+mc1.20.1.jar".to_string(),
+                version_id: Some("OihdIimA".to_string()),
+                expected: ExpectedFile {
+                    size: Some(std::fs::metadata(&sodium_path).unwrap().len()),
+                    sha1: Some(sodium_hashes.sha1),
+                    sha512: Some(sodium_hashes.sha512),
+                    ..Default::default()
+                },
+            }),
+            ..Default::default()
+        },
+        ModInfo { key: "fabric-api-0.92.2+1.20.1.jar".to_string(), name: "Fabric API".to_string(), ..Default::default() },
+        ModInfo { key: "unknown-local.jar".to_string(), name: "Unknown".to_string(), ..Default::default() },
+        // Solo en CurseForge (ID numérico): se descarga y se incluye en overrides/mods
+        ModInfo { key: "cf-only".to_string(), name: "CF Only".to_string(), confirmed_project_id: Some("306612".to_string()), ..Default::default() },
+        ModInfo { key: "ghost".to_string(), name: "Ghost Mod".to_string(), ..Default::default() },
+    ];
+    for m in mods {
+        profile.mods.insert(m.key.clone(), m);
+    }
+
+    let output = dir.join("Export Pack-1.20.1.mrpack");
+    let report = export_pack(&profile, PackFormat::Mrpack, &export_options(&local), &output).expect("Export should succeed");
+
+    assert_eq!(report.indexed, vec!["mods/sodium-fabric-0.5.3+mc1.20.1.jar", "mods/fabric-api-0.92.2+1.20.1.jar"]);
+    assert_eq!(report.bundled.len(), 2);
+    assert!(report.bundled.contains(&"unknown-local.jar".to_string()));
+    assert!(report.bundled.contains(&"fabric-api-0.92.2+1.20.1.jar".to_string()));
+    assert_eq!(report.missing, vec!["Ghost Mod"]);
+
+    let index = read_index(&output).expect("Exported index should parse");
+    assert_eq!(index.name, "Export Pack");
+    assert_eq!(index.dependencies.get("minecraft").map(String::as_str), Some("1.20.1"));
+    assert_eq!(index.dependencies.get("fabric-loader").map(String::as_str), Some("0.15.11"));
+
+    let sodium = &index.files[0];
+    assert_eq!(sodium.downloads[0], "https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium-fabric-0.5.3%2Bmc1.20.1.jar");
+    let env = sodium.env.as_ref().expect("Env comes from the project sides");
+    assert_eq!((env.client.as_str(), env.server.as_str()), ("required", "unsupported"));
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+    assert!(archive.by_name("overrides/mods/unknown-local.jar").is_ok());
+    assert!(!dir.join("Export Pack-1.20.1.tmp").exists());
+}
+
+#[test]
+fn test_export_scratch_dirs_are_unique() {
+    // Dos exportaciones a la vez no pueden compartir (ni borrarse) la carpeta temporal
+    let first = export_scratch_dir("tetanos_mrpack_export");
+    let second = export_scratch_dir("tetanos_mrpack_export");
+    assert_ne!(first, second);
+    assert!(first.starts_with(std::env::temp_dir()));
+}
+
+#[test]
+fn test_export_rejects_bad_loader() {
+    let dir = common::temp_dir("pack_export_bad_loader");
+    let profile = Profile::new("Pack".to_string(), None);
+    let output = dir.join("pack.mrpack");
+
+    let mut opts = export_options(&dir);
+    opts.loader = "LiteLoader".to_string();
+    assert!(export_pack(&profile, PackFormat::Mrpack, &opts, &output).is_err());
+
+    let mut opts = export_options(&dir);
+    opts.loader_version = " ".to_string();
+    assert!(export_pack(&profile, PackFormat::Mrpack, &opts, &output).is_err());
+    assert!(!output.exists());
+}
+
+fn write_test_curseforge_pack(path: &Path) {
+    let manifest = serde_json::json!({
+        "minecraft": { "version": "1.20.1", "modLoaders": [{ "id": "forge-47.2.0", "primary": true }] },
+        "manifestType": "minecraftModpack",
+        "manifestVersion": 1,
+        "name": "CF Pack",
+        "version": "2.0",
+        "author": "tester",
+        "files": [
+            { "projectID": 238222, "fileID": 4712866, "required": true },
+            // Sin downloadUrl: el autor no permite la distribución por terceros
+            { "projectID": 400000, "fileID": 4000001, "required": true },
+            // El archivo ya no existe en CurseForge
+            { "projectID": 238222, "fileID": 1, "required": true },
+            { "projectID": 306612, "fileID": 5383715, "required": false },
+        ],
+        "overrides": "overrides",
+    });
+    let manifest = serde_json::to_vec(&manifest).unwrap();
+    common::write_jar(path, &[
+        ("manifest.json", manifest.as_slice()),
+        ("overrides/config/jei/jei-client.ini", b"[search]"),
+    ]);
+}
+
+#[test]
+fn test_import_curseforge_pack() {
+    common::start_stub_server();
+    let dir = common::temp_dir("pack_import_curseforge");
+    let root = dir.join("modpacks");
+    let pack = dir.join("cf-pack.zip");
+    write_test_curseforge_pack(&pack);
+
+    let imported = import_pack(&pack, &ProfilesDatabase::new(), &root).expect("Import should succeed");
+
+    let profile = &imported.profile;
+    assert_eq!(profile.name, "CF Pack");
+    assert_eq!(profile.game_version.as_deref(), Some("1.20.1"));
+    assert_eq!(profile.loader.as_deref(), Some("Forge"));
+    assert_eq!(profile.loader_version.as_deref(), Some("47.2.0"));
+    assert_eq!(profile.mods.len(), 2, "JEI and the restricted mod, optional file skipped");
+    assert!(profile.mods["restricted-mod-1.0.0.jar"].pinned.is_none());
+    assert_eq!(imported.warnings.len(), 1, "Optional file: {:?}", imported.warnings);
+
+    // Sin URL de descarga: se listan para descargarlos a mano en lugar de perderlos
+    let manual: Vec<&str> = imported.manual_downloads.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(manual, vec!["restricted-mod-1.0.0.jar", "238222/1"]);
+    assert_eq!(imported.manual_downloads[0].url, "https://www.curseforge.com/projects/400000");
+
+    let folder = root.join("CF Pack");
+    assert!(folder.join("overrides/config/jei/jei-client.ini").exists());
+    let jobs = imported.download_jobs();
+    assert_eq!(jobs.len(), 1);
+    let info = jobs[0].pre_resolved.as_ref().unwrap();
+    assert_eq!(info.project_id, "238222");
+    fetch_from_api::download_mod_file(&info.url, &jobs[0].output_folder, &info.filename, &info.expected, |_| {})
+        .expect("Pinned CurseForge download should verify");
+    assert!(folder.join("jei-1.20.1-forge-15.2.0.27.jar").exists());
+}
+
+#[test]
+fn test_export_curseforge_pack() {
+    common::start_stub_server();
+    let dir = common::temp_dir("pack_export_curseforge");
+    let local = dir.join("modpacks/CF Export");
+    std::fs::create_dir_all(&local).unwrap();
+    std::fs::copy(
+        common::fixture_path("api/downloads/fabric-api-0.92.2+1.20.1.jar"),
+        local.join("fabric-api-0.92.2+1.20.1.jar"),
+    ).unwrap();
+    common::write_jar(&local.join("unknown-local.jar"), &[("fabric.mod.json", b"{}")]);
+
+    let mut profile = Profile::new("CF Export".to_string(), None);
+    profile.game_version = Some("1.20.1".to_string());
+    let mods = [
+        ModInfo {
+            key: "jei-1.20.1-forge-15.2.0.27.jar".to_string(),
+            name: "JEI".to_string(),
+            confirmed_project_id: Some("238222".to_string()),
+            pinned: Some(PinnedFile {
+                url: "https://edge.forgecdn.net/files/4712/866/jei-1.20.1-forge-15.2.0.27.jar".to_string(),
+                filename: "jei-1.20.1-forge-15.2.0.27.jar".to_string(),
+                version_id: Some("4712866".to_string()),
+                expected: ExpectedFile::default(),
+            }),
+            ..Default::default()
+        },
+        ModInfo { key: "fabric-api-0.92.2+1.20.1.jar".to_string(), name: "Fabric API".to_string(), ..Default::default() },
+        ModInfo { key: "unknown-local.jar".to_string(), name: "Unknown".to_string(), ..Default::default() },
+        // Solo en Modrinth: se descarga y se incluye en overrides/mods
+        ModInfo { key: "sodium".to_string(), name: "Sodium".to_string(), confirmed_project_id: Some("AANobbMI".to_string()), ..Default::default() },
+        ModInfo { key: "ghost".to_string(), name: "Ghost Mod".to_string(), ..Default::default() },
+    ];
+    for m in mods {
+        profile.mods.insert(m.key.clone(), m);
+    }
+
+    let output = dir.join("CF Export-1.20.1-curseforge.zip");
+    let report = export_pack(&profile, PackFormat::CurseForge, &export_options(&local), &output).expect("Export should succeed");
+    assert_eq!(report.indexed, vec!["jei-1.20.1-forge-15.2.0.27.jar", "fabric-api-0.92.2+1.20.1.jar"]);
+    assert_eq!(report.bundled.len(), 2);
+    assert!(report.bundled.contains(&"sodium-fabric-0.5.3+mc1.20.1.jar".to_string()));
+    assert!(report.bundled.contains(&"unknown-local.jar".to_string()));
+    assert_eq!(report.missing, vec!["Ghost Mod"]);
+
+    let manifest = read_manifest(&output).expect("Exported manifest should parse");
+    assert_eq!(manifest.manifest_type, "minecraftModpack");
+    assert_eq!(manifest.minecraft.mod_loaders[0].id, "fabric-0.15.11");
+    let ids: Vec<(u32, u32)> = manifest.files.iter().map(|f| (f.project_id, f.file_id)).collect();
+    assert_eq!(ids, vec![(238222, 4712866), (306612, 5383715)]);
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+    assert!(archive.by_name("overrides/mods/sodium-fabric-0.5.3+mc1.20.1.jar").is_ok());
+}
+
+#[test]
+fn test_packwiz_round_trip() {
+    common::start_stub_server();
+    let dir = common::temp_dir("pack_packwiz");
+    let local = dir.join("modpacks/Wiz");
+    std::fs::create_dir_all(&local).unwrap();
+    for jar in ["fabric-api-0.92.2+1.20.1.jar", "jei-1.20.1-forge-15.2.0.27.jar"] {
+        std::fs::copy(common::fixture_path(&format!("api/downloads/{}", jar)), local.join(jar)).unwrap();
+    }
+    common::write_jar(&local.join("unknown-local.jar"), &[("fabric.mod.json", b"{}")]);
+
+    let mut profile = Profile::new("Wiz".to_string(), Some("Pack en git".to_string()));
+    for (key, name) in [
+        ("fabric-api-0.92.2+1.20.1.jar", "Fabric API"),
+        ("jei-1.20.1-forge-15.2.0.27.jar", "JEI"),
+        ("unknown-local.jar", "Unknown"),
+        ("ghost", "Ghost Mod"),
+    ] {
+        profile.mods.insert(key.to_string(), ModInfo { key: key.to_string(), name: name.to_string(), ..Default::default() });
+    }
+
+    let tree = dir.join("wiz-packwiz");
+    let report = export_pack(&profile, PackFormat::Packwiz, &export_options(&local), &tree).expect("Export should succeed");
+    assert_eq!(report.indexed.len(), 2, "Fabric API via Modrinth, JEI via CurseForge fingerprint");
+    assert_eq!(report.bundled, vec!["unknown-local.jar"]);
+    assert_eq!(report.missing, vec!["Ghost Mod"]);
+
+    let fabric_api: ModToml = toml::from_str(&std::fs::read_to_string(tree.join("mods/fabric-api.pw.toml")).unwrap()).unwrap();
+    assert_eq!(fabric_api.side, "both");
+    assert_eq!(fabric_api.download.hash_format, "sha512");
+    assert_eq!(fabric_api.ids().map(|(project, _)| project).as_deref(), Some("P7dR8mSH"));
+    let jei: ModToml = toml::from_str(&std::fs::read_to_string(tree.join("mods/jei.pw.toml")).unwrap()).unwrap();
+    assert_eq!(jei.download.mode.as_deref(), Some("metadata:curseforge"));
+    assert_eq!(jei.ids(), Some(("238222".to_string(), "4712866".to_string())));
+    assert!(tree.join("mods/unknown-local.jar").exists());
+
+    // Reimportar el árbol: project IDs desde [update.*] y archivos sueltos copiados al modpack
+    let root = dir.join("modpacks");
+    let imported = import_pack(&tree, &ProfilesDatabase::new(), &root).expect("Import should succeed");
+    assert_eq!(imported.profile.name, "Wiz (2)", "The local folder already uses the name");
+    assert_eq!(imported.profile.loader.as_deref(), Some("Fabric"));
+    assert_eq!(imported.profile.description.as_deref(), Some("Pack en git"));
+    let ids: Vec<(&str, Option<&str>)> = imported.profile.mods.values()
+        .map(|m| (m.key.as_str(), m.confirmed_project_id.as_deref()))
+        .collect();
+    assert_eq!(ids, vec![("fabric-api-0.92.2+1.20.1.jar", Some("P7dR8mSH")), ("jei-1.20.1-forge-15.2.0.27.jar", Some("238222"))]);
+    assert_eq!(imported.downloads.len(), 2, "The CurseForge file is resolved through the API");
+    assert!(imported.manual_downloads.is_empty());
+    assert!(imported.folder.join("unknown-local.jar").exists());
+
+    // Reexportar sin el jar suelto lo quita del árbol
+    profile.mods.shift_remove("unknown-local.jar");
+    export_pack(&profile, PackFormat::Packwiz, &export_options(&local), &tree).unwrap();
+    assert!(!tree.join("mods/unknown-local.jar").exists());
+    assert!(tree.join("mods/jei.pw.toml").exists());
+}
+
+#[test]
+fn test_packwiz_import_verifies_hashes() {
+    let dir = common::temp_dir("pack_packwiz_hashes");
+    let tree = dir.join("tree");
+    std::fs::create_dir_all(tree.join("mods")).unwrap();
+    let jar = std::fs::read(common::fixture_path("api/downloads/jei-1.20.1-forge-15.2.0.27.jar")).unwrap();
+    let metafile = |name: &str, format: &str, hash: &str| format!(
+        "name = \"{name}\"\nfilename = \"{name}.jar\"\nside = \"both\"\n\n[download]\nurl = \"https://cdn.modrinth.com/data/x/versions/y/{name}.jar\"\nhash-format = \"{format}\"\nhash = \"{hash}\"\n"
+    );
+    let files = [
+        ("mods/sha256.pw.toml", metafile("sha256", "sha256", &sha256_hex(&jar))),
+        ("mods/murmur.pw.toml", metafile("murmur", "murmur2", &curseforge_fingerprint(&jar).to_string())),
+        ("mods/md5.pw.toml", metafile("md5", "md5", "d41d8cd98f00b204e9800998ecf8427e")),
+        ("mods/tampered.pw.toml", metafile("tampered", "sha256", &sha256_hex(&jar))),
+        ("config/a.txt", "a=1".to_string()),
+    ];
+    let mut index = String::from("hash-format = \"sha256\"\n");
+    for (path, content) in &files {
+        std::fs::create_dir_all(tree.join(path).parent().unwrap()).unwrap();
+        std::fs::write(tree.join(path), content).unwrap();
+        index.push_str(&format!("\n[[files]]\nfile = \"{}\"\nhash = \"{}\"\nmetafile = {}\n", path, sha256_hex(content.as_bytes()), path.ends_with(".pw.toml")));
+    }
+    // El metafile cambia después de indexarlo
+    std::fs::write(tree.join("mods/tampered.pw.toml"), metafile("tampered", "sha1", "0000")).unwrap();
+    std::fs::write(tree.join("index.toml"), &index).unwrap();
+    let pack = |index_hash: &str| format!(
+        "name = \"Hashes\"\npack-format = \"packwiz:1.1.0\"\n\n[index]\nfile = \"index.toml\"\nhash-format = \"sha256\"\nhash = \"{index_hash}\"\n\n[versions]\nminecraft = \"1.20.1\"\nforge = \"47.2.0\"\n"
+    );
+
+    std::fs::write(tree.join("pack.toml"), pack("0000")).unwrap();
+    assert!(import_pack(&tree, &ProfilesDatabase::new(), &dir.join("modpacks")).is_err(), "A mismatching index.toml aborts the import");
+
+    std::fs::write(tree.join("pack.toml"), pack(&sha256_hex(index.as_bytes()))).unwrap();
+    let imported = import_pack(&tree, &ProfilesDatabase::new(), &dir.join("modpacks")).expect("Import should succeed");
+    let queued: Vec<&str> = imported.downloads.iter().map(|(_, d)| d.filename.as_str()).collect();
+    assert_eq!(queued, vec!["sha256.jar", "murmur.jar"], "Only verifiable hashes are queued");
+    let sha256 = &imported.downloads[0].1.expected;
+    assert!(sha256.sha256.is_some() && verify_bytes(&jar, sha256).is_ok());
+    assert!(verify_bytes(&jar, &imported.downloads[1].1.expected).is_ok());
+    let manual: Vec<&str> = imported.manual_downloads.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(manual, vec!["md5.jar"], "md5 cannot be checked: left for a manual download");
+    assert!(imported.warnings.iter().any(|w| w.contains("tampered.pw.toml")));
+    assert!(!imported.profile.mods.contains_key("tampered.jar"));
+    assert_eq!(std::fs::read_to_string(imported.folder.join("overrides/config/a.txt")).unwrap(), "a=1");
+}
+
+#[test]
+fn test_import_rejects_unknown_format() {
+    let dir = common::temp_dir("pack_import_unknown");
+    let pack = dir.join("pack.7z");
+    std::fs::write(&pack, b"x").unwrap();
+    assert!(import_pack(&pack, &ProfilesDatabase::new(), &dir).is_err());
+}
+
+#[test]
+fn test_pack_names_and_paths() {
+    assert_eq!(safe_relative_path("mods/a.jar"), Some(Path::new("mods/a.jar").to_path_buf()));
+    assert_eq!(safe_relative_path("./config/x"), Some(Path::new("config/x").to_path_buf()));
+    assert_eq!(safe_relative_path("../a.jar"), None);
+    assert_eq!(safe_relative_path("/etc/passwd"), None);
+    assert_eq!(safe_relative_path(""), None);
+
+    let dir = common::temp_dir("pack_names");
+    let mut db = ProfilesDatabase::new();
+    assert_eq!(unique_pack_name(&db, &dir, "My: Pack?"), "My_ Pack_");
+    db.add_profile(Profile::new("Pack".to_string(), None));
+    std::fs::create_dir_all(dir.join("Pack (2)")).unwrap();
+    assert_eq!(unique_pack_name(&db, &dir, "Pack"), "Pack (3)");
+
+    assert_eq!(metafile_slug("Fabric API"), "fabric-api");
+    assert_eq!(metafile_slug("Just Enough Items (JEI)"), "just-enough-items-jei");
+    assert_eq!(metafile_slug("!!"), "mod");
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use std::path::Path;
+
+use mods_updater::local_mods_ops::parsers::try_all;
+use mods_updater::local_mods_ops::loaders::{majority_loader, supports_loader};
+use mods_updater::local_mods_ops::ModInfo;
+
+/// Crea un jar con las entradas indicadas (en su propia carpeta: los tests corren en paralelo) y lo pasa por `try_all`.
+fn parse_jar(name: &str, entries: &[(&str, &[u8])]) -> Option<ModInfo> {
+    let dir = common::temp_dir(&format!("parsers_{}", name));
+    let path = dir.join(name);
+    common::write_jar(&path, entries);
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+    try_all(&mut zip, Path::new(&path))
+}
+
+#[test]
+fn test_parse_quilt_mod_json() {
+    let quilt = br#"{
+        "schema_version": 1,
+        "quilt_loader": {
+            "group": "org.example",
+            "id": "example_mod",
+            "version": "2.1.0",
+            "metadata": { "name": "Example Mod", "icon": { "32": "assets/example/icon32.png" } },
+            "depends": [
+                "quilt_loader",
+                { "id": "minecraft", "versions": ">=1.20 <1.21" },
+                { "id": "qsl", "versions": ["6.1.0", "7.0.0"] },
+                { "id": "quilted_fabric_api", "versions": { "any": [">=7.0.0", "~6.2"] } },
+                { "id": "modmenu", "versions": "*", "optional": true }
+            ]
+        }
+    }"#;
+    let mi = parse_jar("example-quilt.jar", &[("quilt.mod.json", quilt)]).expect("Quilt-only jars should parse");
+
+    assert_eq!(mi.key, "example-quilt.jar");
+    assert_eq!(mi.name, "Example Mod");
+    assert_eq!(mi.detected_project_id.as_deref(), Some("example_mod"));
+    assert_eq!(mi.version_local.as_deref(), Some("2.1.0"));
+    assert!(!mi.has_local_icon);
+
+    let depends = mi.depends.expect("Dependencies should be filled");
+    assert_eq!(depends.len(), 4, "Optional dependencies are skipped: {:?}", depends);
+    assert_eq!(depends["quilt_loader"], "*");
+    assert_eq!(depends["minecraft"], ">=1.20 <1.21");
+    assert_eq!(depends["qsl"], "6.1.0 || 7.0.0");
+    assert_eq!(depends["quilted_fabric_api"], ">=7.0.0 || ~6.2");
+}
+
+#[test]
+fn test_parse_quilt_dependency_groups() {
+    // Un array dentro de `depends` se cumple con cualquiera de sus entradas
+    let quilt = br#"{
+        "schema_version": 1,
+        "quilt_loader": {
+            "group": "org.example",
+            "id": "grouped",
+            "version": "1.0",
+            "depends": [
+                [ { "id": "minecraft", "versions": "1.20.1" }, { "id": "minecraft", "versions": ">=1.20.4 <1.21" } ],
+                [ "sodium", { "id": "embeddium" } ],
+                [ { "id": "modmenu", "optional": true } ]
+            ]
+        }
+    }"#;
+    let mi = parse_jar("grouped-quilt.jar", &[("quilt.mod.json", quilt)]).expect("Dependency groups must not reject the jar");
+
+    let depends = mi.depends.expect("Dependencies should be filled");
+    assert_eq!(depends.len(), 1, "Groups of different mods or optional ones are not checked: {:?}", depends);
+    assert_eq!(depends["minecraft"], "1.20.1 || >=1.20.4 <1.21");
+}
+
+#[test]
+fn test_parse_quilt_without_metadata_name() {
+    let quilt = br#"{ "schema_version": 1, "quilt_loader": { "group": "a", "id": "bare", "version": "1.0" } }"#;
+    let mi = parse_jar("bare.jar", &[("quilt.mod.json", quilt)]).unwrap();
+    assert_eq!(mi.name, "bare");
+    assert!(mi.depends.is_none());
+}
+
+#[test]
+fn test_parse_jar_without_metadata() {
+    assert!(parse_jar("empty.jar", &[("README.txt", b"nothing")]).is_none());
+}
+
+#[test]
+fn test_parse_neoforge_mods_toml() {
+    let neoforge = br#"
+modLoader = "javafml"
+loaderVersion = "[2,)"
+
+[[mods]]
+modId = "examplemod"
+version = "3.0.1"
+displayName = "Example NeoForge Mod"
+
+[[dependencies.examplemod]]
+modId = "neoforge"
+type = "required"
+versionRange = "[20.4,)"
+
+[[dependencies.examplemod]]
+modId = "jei"
+type = "optional"
+versionRange = "[17,)"
+
+[[dependencies.examplemod]]
+modId = "optifine"
+type = "incompatible"
+versionRange = "*"
+"#;
+    let mi = parse_jar("example-neoforge.jar", &[("META-INF/neoforge.mods.toml", neoforge)])
+        .expect("neoforge.mods.toml jars should parse");
+
+    assert_eq!(mi.name, "Example NeoForge Mod");
+    assert_eq!(mi.version_local.as_deref(), Some("3.0.1"));
+    assert_eq!(mi.loaders, vec!["NeoForge"]);
+    let depends = mi.depends.expect("Required dependencies");
+    assert_eq!(depends.len(), 1, "Optional dependencies are skipped: {:?}", depends);
+    assert_eq!(depends["neoforge"], "[20.4,)");
+    assert_eq!(mi.breaks.expect("Incompatible dependencies")["optifine"], "*");
+}
+
+#[test]
+fn test_parse_forge_mods_toml() {
+    let forge = br#"
+modLoader = "javafml"
+loaderVersion = "[47,)"
+
+[[mods]]
+modId = "forgemod"
+version = "${file.jarVersion}"
+
+[[dependencies.forgemod]]
+modId = "forge"
+mandatory = true
+versionRange = "[47,)"
+
+[[dependencies.forgemod]]
+modId = "curios"
+mandatory = false
+versionRange = "[5,)"
+"#;
+    let mi = parse_jar("forgemod.jar", &[("META-INF/mods.toml", forge)]).unwrap();
+    assert_eq!(mi.name, "forgemod");
+    assert_eq!(mi.version_local, None, "Placeholder versions are dropped");
+    assert_eq!(mi.loaders, vec!["Forge"]);
+    assert_eq!(mi.depends.unwrap().keys().collect::<Vec<_>>(), vec!["forge"]);
+    assert!(mi.breaks.is_none());
+
+    // Jar multi-loader: metadata de neoforge.mods.toml, loaders de ambos archivos
+    let neoforge = br#"
+[[mods]]
+modId = "forgemod"
+version = "1.0"
+"#;
+    let both = parse_jar("multi.jar", &[("META-INF/mods.toml", forge), ("META-INF/neoforge.mods.toml", neoforge)]).unwrap();
+    assert_eq!(both.version_local.as_deref(), Some("1.0"));
+    assert_eq!(both.loaders, vec!["NeoForge", "Forge"]);
+}
+
+#[test]
+fn test_parse_legacy_mcmod_info() {
+    // Array clásico, con un salto de línea dentro de la descripción (JSON inválido estricto)
+    let info = b"[\n{\n  \"modid\": \"ironchest\",\n  \"name\": \"Iron Chests\",\n  \"description\": \"Larger\nchests\",\n  \"version\": \"7.0.72\",\n  \"mcversion\": \"1.12.2\",\n  \"requiredMods\": [\"Forge@[14.23.5,)\", \"CodeChickenLib\"],\n  \"logoFile\": \"/assets/ironchest/logo.png\"\n}\n]";
+    let mi = parse_jar("ironchest-1.12.2.jar", &[("mcmod.info", info)]).expect("mcmod.info jars should parse");
+    assert_eq!(mi.name, "Iron Chests");
+    assert_eq!(mi.detected_project_id.as_deref(), Some("ironchest"));
+    assert_eq!(mi.version_local.as_deref(), Some("7.0.72"));
+    assert_eq!(mi.loaders, vec!["Forge"]);
+    let depends = mi.depends.unwrap();
+    assert_eq!(depends["Forge"], "[14.23.5,)");
+    assert_eq!(depends["CodeChickenLib"], "*");
+
+    // modListVersion 2 con versión sin sustituir
+    let v2 = br#"{ "modListVersion": 2, "modList": [{ "modid": "oldmod", "version": "${version}" }] }"#;
+    let mi = parse_jar("oldmod.jar", &[("mcmod.info", v2)]).unwrap();
+    assert_eq!(mi.name, "oldmod");
+    assert_eq!(mi.version_local, None);
+    assert!(mi.depends.is_none());
+}
+
+#[test]
+fn test_loaders_of_multi_loader_jar() {
+    let fabric = br#"{ "schemaVersion": 1, "id": "multi", "name": "Multi", "version": "1.0" }"#;
+    let forge = br#"
+[[mods]]
+modId = "multi"
+"#;
+    let only_fabric = parse_jar("multi-fabric.jar", &[("fabric.mod.json", fabric)]).unwrap();
+    assert_eq!(only_fabric.loaders, vec!["Fabric"]);
+    assert!(supports_loader(&only_fabric, "Fabric"));
+    assert!(supports_loader(&only_fabric, "Quilt"), "Quilt loads Fabric mods");
+    assert!(!supports_loader(&only_fabric, "Forge"));
+
+    // Gana fabric.mod.json, pero los loaders salen de toda la metadata del jar
+    let all = parse_jar("multi-all.jar", &[("fabric.mod.json", fabric), ("META-INF/mods.toml", forge)]).unwrap();
+    assert_eq!(all.name, "Multi");
+    assert_eq!(all.loaders, vec!["Fabric", "Forge"]);
+    assert!(supports_loader(&all, "Forge"));
+    assert!(!supports_loader(&all, "NeoForge"));
+
+    let unknown = ModInfo::default();
+    assert!(supports_loader(&unknown, "NeoForge"), "Jars without detected loaders never warn");
+
+    let quilt_only = ModInfo { loaders: vec!["Quilt".to_string()], ..Default::default() };
+    assert!(!supports_loader(&quilt_only, "Fabric"));
+
+    assert_eq!(majority_loader([&only_fabric, &all, &quilt_only]).as_deref(), Some("Fabric"));
+    assert_eq!(majority_loader([&all]).as_deref(), Some("Fabric"), "Ties resolve alphabetically");
+    assert_eq!(majority_loader([&unknown]), None);
+}
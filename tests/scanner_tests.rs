@@ -0,0 +1,64 @@
+mod common;
+
+use std::path::Path;
+use std::time::Instant;
+
+use mods_updater::local_mods_ops::cache::{get_mod, get_mods, init_with_path, upsert_mod};
+use mods_updater::local_mods_ops::read_mods_in_folder;
+
+const JARS: usize = 200;
+
+fn write_fabric_jar(path: &Path, id: &str, version: &str) {
+    let json = format!(r#"{{ "schemaVersion": 1, "id": "{}", "name": "{}", "version": "{}" }}"#, id, id, version);
+    // Relleno para que leer y hashear cada jar cueste algo, como un mod real
+    let padding = vec![b'x'; 64 * 1024];
+    common::write_jar(path, &[("fabric.mod.json", json.as_bytes()), ("assets/data.bin", &padding)]);
+}
+
+fn as_json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[test]
+fn test_scan_folder_reuses_cache() {
+    let dir = common::temp_dir("scanner_bench");
+    init_with_path(dir.join("cache.redb"));
+    let mods_dir = dir.join("mods");
+    std::fs::create_dir_all(&mods_dir).unwrap();
+    for i in 0..JARS {
+        write_fabric_jar(&mods_dir.join(format!("mod-{:03}.jar", i)), &format!("mod{:03}", i), "1.0.0");
+    }
+    std::fs::write(mods_dir.join("broken.jar"), b"not a zip").unwrap();
+    std::fs::write(mods_dir.join("readme.txt"), b"ignored").unwrap();
+    let folder = mods_dir.to_string_lossy().to_string();
+
+    let start = Instant::now();
+    let cold = read_mods_in_folder(&folder);
+    let cold_time = start.elapsed();
+    assert_eq!(cold.len(), JARS, "The broken jar and non-jar files are skipped");
+    assert!(cold.keys().zip(cold.keys().skip(1)).all(|(a, b)| a < b), "Results keep filename order");
+    assert!(cold.values().all(|m| m.hashes.is_some() && m.bundled.is_some()));
+
+    // Una fila marcada a mano: si el segundo escaneo volviera a leer el jar, la marca desaparecería
+    let mut marked = get_mod("mod-000.jar").expect("Parsed jars are written to the cache");
+    marked.name = "From cache".to_string();
+    upsert_mod("mod-000.jar", &marked);
+    let rows = get_mods(cold.keys().map(String::as_str));
+
+    let start = Instant::now();
+    let warm = read_mods_in_folder(&folder);
+    println!("Escaneo de {} jars: en frío {:?}, con caché {:?}", JARS, cold_time, start.elapsed());
+
+    assert_eq!(warm["mod-000.jar"].name, "From cache", "The cached scan must not re-parse unchanged jars");
+    assert_eq!(as_json(&warm.values().skip(1).collect::<Vec<_>>()), as_json(&cold.values().skip(1).collect::<Vec<_>>()), "The cached scan returns the same mods");
+    let rows_after = get_mods(cold.keys().map(String::as_str));
+    assert_eq!(rows.len(), JARS);
+    assert!(rows.iter().all(|(key, row)| as_json(row) == as_json(&rows_after[key])), "The cache rows are left untouched");
+
+    // Solo el jar que cambia se vuelve a leer
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    write_fabric_jar(&mods_dir.join("mod-007.jar"), "mod007", "2.0.0");
+    let rescanned = read_mods_in_folder(&folder);
+    assert_eq!(rescanned["mod-007.jar"].version_local.as_deref(), Some("2.0.0"));
+    assert_eq!(as_json(&rescanned["mod-008.jar"]), as_json(&warm["mod-008.jar"]));
+}
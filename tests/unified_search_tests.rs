@@ -1,12 +1,17 @@
-use mods_updater::fetch::single_mod_search::{search_unified, SearchRequest};
-use std::env;
+mod common;
+
+use mods_updater::fetch::search_provider::{ContentSearchProvider, ContentType, SearchRequest};
+use mods_updater::fetch::single_mod_search::ModSearchProvider;
+use mods_updater::fetch::cf_api_key;
 
 fn has_curse_key() -> bool {
-    env::var("CURSEFORGE_API_KEY").is_ok_and(|k| !k.is_empty())
+    !cf_api_key().is_empty()
 }
 
 #[test]
 fn test_unified_flow() {
+    common::start_stub_server();
+
     // JEI suele estar en ambos
     let req = SearchRequest {
         query: "JEI".to_string(),
@@ -14,9 +19,10 @@ fn test_unified_flow() {
         version: Some("1.20.1".to_string()),
         offset: 0,
         limit: 10,
+        content_type: ContentType::Mod,
     };
 
-    let results = search_unified(&req);
+    let results = ModSearchProvider.search(&req);
 
     assert!(!results.is_empty(), "Should return results for JEI");
 
@@ -25,17 +31,11 @@ fn test_unified_flow() {
     assert!(first.modrinth_id.is_some(), "Should have Modrinth ID (primary source)");
 
     if has_curse_key() {
+        // Mismo slug en ambas plataformas → un único resultado con los dos IDs
         let merged = results.iter().find(|r| r.modrinth_id.is_some() && r.curseforge_id.is_some());
-        
-        if let Some(m) = merged {
-            println!("✅ Successfully merged result found: {} (Modrinth: {:?}, CF: {:?})", 
-                m.name, m.modrinth_id, m.curseforge_id);
-        } else {
-            println!("⚠️ Warning: No merged results found for JEI. Check naming/slug inconsistencies.");
-            for r in &results {
-                println!("- {} (M: {:?}, C: {:?})", r.name, r.modrinth_id, r.curseforge_id);
-            }
-        }
+        assert!(merged.is_some(), "JEI should be merged across Modrinth and CurseForge");
+        assert_eq!(results.len(), 1, "Merged results should not be duplicated");
+        assert!(results[0].icon_url.is_some(), "Icon should come from CurseForge when Modrinth has none");
     } else {
         println!("Skipping merge validation: CURSEFORGE_API_KEY not set");
     }
@@ -0,0 +1,69 @@
+mod common;
+
+use mods_updater::fetch::fetch_from_api::ModDownloadInfo;
+use mods_updater::fetch::update_check::{check_mod_update, compare_with_local};
+use mods_updater::local_mods_ops::hashing::{hash_bytes, hash_file};
+use mods_updater::local_mods_ops::{ModInfo, UpdateState};
+
+fn sodium(hashes_of: Option<&str>) -> ModInfo {
+    ModInfo {
+        key: "sodium.jar".to_string(),
+        name: "Sodium".to_string(),
+        confirmed_project_id: Some("AANobbMI".to_string()),
+        hashes: hashes_of.map(|file| hash_file(&common::fixture_path(&format!("api/downloads/{}", file))).unwrap()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_same_hash_is_up_to_date() {
+    common::start_stub_server();
+
+    let result = check_mod_update(&sodium(Some("sodium-fabric-0.5.3+mc1.20.1.jar")), "1.20.1", "fabric", common::STUB_CF_KEY);
+    assert_eq!(result.state, UpdateState::UpToDate);
+
+    let latest = result.latest.expect("Should report the latest file");
+    assert_eq!(latest.version_remote, "mc1.20.1-0.5.3");
+    assert_eq!(latest.date_published, "2023-09-21");
+}
+
+#[test]
+fn test_different_hash_is_outdated() {
+    common::start_stub_server();
+
+    let mut mi = sodium(None);
+    mi.hashes = Some(hash_bytes(b"sodium 0.5.0"));
+    let result = check_mod_update(&mi, "1.20.1", "fabric", common::STUB_CF_KEY);
+    assert_eq!(result.state, UpdateState::Outdated);
+}
+
+#[test]
+fn test_no_compatible_file_is_unavailable() {
+    common::start_stub_server();
+
+    let result = check_mod_update(&sodium(None), "1.20.1", "forge", common::STUB_CF_KEY);
+    assert_eq!(result.state, UpdateState::Unavailable);
+    assert!(result.latest.is_none());
+}
+
+#[test]
+fn test_messy_local_version_matches_remote() {
+    let latest = ModDownloadInfo {
+        filename: "sodium-fabric-0.5.3+mc1.20.1.jar".to_string(),
+        name: "Sodium".to_string(),
+        slug: "sodium".to_string(),
+        url: String::new(),
+        project_id: "AANobbMI".to_string(),
+        version_remote: "mc1.20.1-0.5.3".to_string(),
+        date_published: "2023-09-21".to_string(),
+        expected: Default::default(),
+        dependencies: Vec::new(),
+    };
+    // Jar renombrado y sin hashes: solo queda la versión del fabric.mod.json
+    let mut mi = sodium(None);
+    mi.version_local = Some("0.5.3+mc1.20.1".to_string());
+    assert_eq!(compare_with_local(&mi, &latest), UpdateState::UpToDate);
+
+    mi.version_local = Some("0.5.2+mc1.20.1".to_string());
+    assert_eq!(compare_with_local(&mi, &latest), UpdateState::Outdated);
+}
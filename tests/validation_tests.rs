@@ -0,0 +1,198 @@
+mod common;
+
+use std::path::Path;
+
+use mods_updater::local_mods_ops::read_single_mod;
+use mods_updater::local_mods_ops::validation::{validate_modpack, java_for_minecraft, DependencyIssue, ValidationTarget};
+use mods_updater::local_mods_ops::{BundledJar, ModInfo};
+
+fn fabric_jar(dir: &Path, file: &str, id: &str, version: &str, depends: &str) -> ModInfo {
+    let json = format!(
+        r#"{{ "schemaVersion": 1, "id": "{}", "name": "{}", "version": "{}", "depends": {{ {} }} }}"#,
+        id, id, version, depends
+    );
+    let path = dir.join(file);
+    common::write_jar(&path, &[("fabric.mod.json", json.as_bytes())]);
+    read_single_mod(&path).expect("Fixture jar should parse")
+}
+
+fn target(mc: &str, loader: &str, loader_version: Option<&str>) -> ValidationTarget {
+    ValidationTarget {
+        mc_version: mc.to_string(),
+        loader: loader.to_string(),
+        loader_version: loader_version.map(|v| v.to_string()),
+    }
+}
+
+#[test]
+fn test_validate_fabric_modpack() {
+    let dir = common::temp_dir("validation_fabric");
+    let api = fabric_jar(&dir, "fabric-api.jar", "fabric-api", "0.92.2+1.20.1", r#""fabricloader": ">=0.14.22""#);
+    let sodium = fabric_jar(&dir, "sodium.jar", "sodium", "0.5.3+mc1.20.1",
+        r#""fabricloader": ">=0.12.0", "minecraft": "1.20.1", "java": ">=17", "fabric-rendering-data-attachment-v1": ">=0.1""#);
+    let iris = fabric_jar(&dir, "iris.jar", "iris", "1.6.11",
+        r#""minecraft": ">=1.20 <1.20.2", "sodium": ">=0.5.5 <0.6", "fabric-api": "*""#);
+    let waystones = fabric_jar(&dir, "waystones.jar", "waystones", "14.1.3", r#""balm": ">=7.0", "minecraft": "~1.20""#);
+    let iris_old = fabric_jar(&dir, "iris-old.jar", "iris", "1.6.4", r#""minecraft": "1.20.1""#);
+
+    let issues = validate_modpack([&api, &sodium, &iris, &waystones, &iris_old], &target("1.20.1", "Fabric", Some("0.14.21")));
+    assert_eq!(issues, vec![
+        DependencyIssue::Duplicate { mod_id: "iris".to_string(), keys: vec!["iris-old.jar".to_string(), "iris.jar".to_string()] },
+        DependencyIssue::VersionMismatch {
+            mod_key: "fabric-api.jar".to_string(), dependency: "fabricloader".to_string(),
+            range: ">=0.14.22".to_string(), found: "0.14.21".to_string(),
+        },
+        DependencyIssue::VersionMismatch {
+            mod_key: "iris.jar".to_string(), dependency: "sodium".to_string(),
+            range: ">=0.5.5 <0.6".to_string(), found: "0.5.3+mc1.20.1".to_string(),
+        },
+        DependencyIssue::Missing { mod_key: "waystones.jar".to_string(), dependency: "balm".to_string(), range: ">=7.0".to_string() },
+    ]);
+
+    // Otra versión de Minecraft: ahora fallan minecraft y java; sin versión del loader no se mira fabricloader
+    let issues = validate_modpack([&api, &sodium], &target("1.16.5", "Fabric", None));
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(issues.iter().all(|i| matches!(i, DependencyIssue::VersionMismatch { mod_key, .. } if mod_key == "sodium.jar")));
+    assert!(issues[0].describe().contains("sodium.jar necesita java >=17 (hay 8)"), "{}", issues[0].describe());
+}
+
+#[test]
+fn test_validate_forge_ranges() {
+    let dir = common::temp_dir("validation_forge");
+    let toml = br#"
+[[mods]]
+modId = "jeiaddon"
+version = "2.0"
+
+[[dependencies.jeiaddon]]
+modId = "forge"
+mandatory = true
+versionRange = "[47,)"
+
+[[dependencies.jeiaddon]]
+modId = "minecraft"
+mandatory = true
+versionRange = "[1.20,1.21)"
+
+[[dependencies.jeiaddon]]
+modId = "jei"
+mandatory = true
+versionRange = "[15.2,16)"
+"#;
+    let path = dir.join("jeiaddon.jar");
+    common::write_jar(&path, &[("META-INF/mods.toml", toml)]);
+    let addon = read_single_mod(&path).unwrap();
+
+    assert_eq!(validate_modpack([&addon], &target("1.20.1", "Forge", Some("47.2.0"))), vec![
+        DependencyIssue::Missing { mod_key: "jeiaddon.jar".to_string(), dependency: "jei".to_string(), range: "[15.2,16)".to_string() },
+    ]);
+
+    let jei = ModInfo {
+        key: "jei.jar".to_string(),
+        detected_project_id: Some("jei".to_string()),
+        version_local: Some("15.2.0.27".to_string()),
+        ..Default::default()
+    };
+    assert!(validate_modpack([&addon, &jei], &target("1.20.1", "Forge", Some("47.2.0"))).is_empty());
+
+    let issues = validate_modpack([&addon, &jei], &target("1.21", "Forge", Some("46.0.1")));
+    let broken: Vec<&str> = issues.iter().map(|i| match i {
+        DependencyIssue::VersionMismatch { dependency, .. } => dependency.as_str(),
+        other => panic!("Unexpected issue {:?}", other),
+    }).collect();
+    assert_eq!(broken, vec!["forge", "minecraft"]);
+}
+
+#[test]
+fn test_forge_bare_versions_are_maven_recommendations() {
+    let dir = common::temp_dir("validation_forge_bare");
+    // mods.toml con una versión suelta: en Maven es "cualquiera, preferiblemente esta"
+    let toml = br#"
+[[mods]]
+modId = "bare"
+version = "1.0"
+
+[[dependencies.bare]]
+modId = "forge"
+mandatory = true
+versionRange = "47.1.0"
+"#;
+    let path = dir.join("bare.jar");
+    common::write_jar(&path, &[("META-INF/mods.toml", toml)]);
+    let bare = read_single_mod(&path).unwrap();
+    assert!(validate_modpack([&bare], &target("1.20.1", "Forge", Some("47.2.0"))).is_empty());
+
+    // mcmod.info: Forge se llama también FML o mod_MinecraftForge, y son del loader, no jars que falten
+    let info = br#"[{ "modid": "oldmod", "name": "Old Mod", "version": "1.0",
+        "requiredMods": ["Forge@10.13.4", "FML@[7.10.99,)", "mod_MinecraftForge@[11.0,)"] }]"#;
+    let path = dir.join("oldmod.jar");
+    common::write_jar(&path, &[("mcmod.info", info)]);
+    let old = read_single_mod(&path).unwrap();
+    assert_eq!(validate_modpack([&old], &target("1.7.10", "Forge", Some("10.13.4.1614"))), vec![
+        DependencyIssue::VersionMismatch {
+            mod_key: "oldmod.jar".to_string(), dependency: "mod_MinecraftForge".to_string(),
+            range: "[11.0,)".to_string(), found: "10.13.4.1614".to_string(),
+        },
+    ]);
+}
+
+#[test]
+fn test_java_for_minecraft() {
+    assert_eq!(java_for_minecraft("1.12.2"), 8);
+    assert_eq!(java_for_minecraft("1.17.1"), 16);
+    assert_eq!(java_for_minecraft("1.20.4"), 17);
+    assert_eq!(java_for_minecraft("1.20.5"), 21);
+    assert_eq!(java_for_minecraft("1.21"), 21);
+}
+
+#[test]
+fn test_bundled_jars_satisfy_dependencies() {
+    let dir = common::temp_dir("validation_bundled");
+    // Jar incluido con su propio fabric.mod.json
+    let nested = dir.join("cloth-config.jar");
+    common::write_jar(&nested, &[("fabric.mod.json", br#"{ "schemaVersion": 1, "id": "cloth-config", "version": "11.1.106" }"#)]);
+    let nested_bytes = std::fs::read(&nested).unwrap();
+    std::fs::remove_file(&nested).unwrap();
+
+    let outer = br#"{ "schemaVersion": 1, "id": "modmenu", "name": "Mod Menu", "version": "7.2.2",
+        "depends": { "cloth-config": ">=11" }, "jars": [ { "file": "META-INF/jars/cloth-config.jar" } ] }"#;
+    let path = dir.join("modmenu.jar");
+    common::write_jar(&path, &[("fabric.mod.json", outer), ("META-INF/jars/cloth-config.jar", &nested_bytes)]);
+    let modmenu = read_single_mod(&path).unwrap();
+    assert_eq!(modmenu.bundled.as_ref().map(|b| b.len()), Some(1));
+    let jar = &modmenu.bundled.as_ref().unwrap()[0];
+    assert_eq!((jar.mod_id.as_deref(), jar.version.as_deref()), (Some("cloth-config"), Some("11.1.106")));
+
+    // Forge: librería sin metadata de mod, solo la de jarjar
+    let jarjar = br#"{ "jars": [ { "identifier": { "group": "io.github", "artifact": "mixinextras" },
+        "version": { "range": "[0.3,)", "artifactVersion": "0.3.5" }, "path": "META-INF/jarjar/mixinextras.jar" } ] }"#;
+    let path = dir.join("create.jar");
+    common::write_jar(&path, &[
+        ("META-INF/mods.toml", b"[[mods]]\nmodId = \"create\"\nversion = \"0.5.1\"\n"),
+        ("META-INF/jarjar/metadata.json", jarjar),
+        ("META-INF/jarjar/mixinextras.jar", b"not a zip"),
+    ]);
+    let create = read_single_mod(&path).unwrap();
+    let jar = &create.bundled.as_ref().unwrap()[0];
+    assert_eq!((jar.mod_id.as_deref(), jar.version.as_deref()), (Some("mixinextras"), Some("0.3.5")));
+
+    assert!(validate_modpack([&modmenu], &target("1.20.1", "Fabric", None)).is_empty());
+
+    // Dos mods que incluyen versiones distintas de la misma librería
+    let other = ModInfo {
+        key: "other.jar".to_string(),
+        detected_project_id: Some("other".to_string()),
+        bundled: Some(vec![BundledJar {
+            path: "META-INF/jars/cloth.jar".to_string(),
+            mod_id: Some("cloth-config".to_string()),
+            version: Some("9.0.94".to_string()),
+        }]),
+        ..Default::default()
+    };
+    assert_eq!(validate_modpack([&modmenu, &other], &target("1.20.1", "Fabric", None)), vec![
+        DependencyIssue::ConflictingBundles {
+            mod_id: "cloth-config".to_string(),
+            copies: vec![("modmenu.jar".to_string(), "11.1.106".to_string()), ("other.jar".to_string(), "9.0.94".to_string())],
+        },
+    ]);
+}
@@ -0,0 +1,76 @@
+use std::cmp::Ordering;
+
+use mods_updater::common::version_range::{compare_versions, satisfies, Version, VersionRange};
+
+#[test]
+fn test_compare_messy_versions() {
+    assert_eq!(compare_versions("0.5.3+mc1.20.1", "0.5.3"), Ordering::Equal, "Build metadata is ignored");
+    assert_eq!(compare_versions("v1.2", "1.2.0"), Ordering::Equal);
+    assert_eq!(compare_versions("0.5.10", "0.5.9"), Ordering::Greater, "Components compare numerically");
+    assert_eq!(compare_versions("1.20.1-pre2", "1.20.1"), Ordering::Less);
+    assert_eq!(compare_versions("1.20.1-pre2", "1.20.1-pre10"), Ordering::Less);
+    assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-alpha.1"), Ordering::Less);
+    assert_eq!(compare_versions("15.2.0.27", "15.2"), Ordering::Greater);
+    assert!(Version::parse("1.20") == Version::parse("1.20.0"));
+}
+
+#[test]
+fn test_fabric_predicates() {
+    let cases: &[(&str, &str, bool)] = &[
+        ("*", "anything", true),
+        (">=1.2 <2", "1.9.9", true),
+        (">=1.2 <2", "2.0.0", false),
+        (">=1.2 <2", "1.1", false),
+        ("1.19.4 || 1.20.1", "1.20.1", true),
+        ("1.19.4 || 1.20.1", "1.20", false),
+        ("~1.20", "1.20.4", true),
+        ("~1.20", "1.21", false),
+        ("~1.20.1", "1.20.6", true),
+        ("^2.1", "2.9.0", true),
+        ("^2.1", "3.0.0", false),
+        ("^2.1", "2.0.9", false),
+        ("1.20.x", "1.20.6", true),
+        ("1.20.x", "1.21", false),
+        ("<=1.20.x", "1.20.6", true),
+        ("<=1.20.x", "1.21", false),
+        (">=0.5.3", "0.5.3+mc1.20.1", true),
+        (">=1.20.1-", "1.20.1-rc1", true),
+        (">=1.20.1", "1.20.1-rc1", false),
+        ("^1.2", "2.0-beta", false),
+        ("<2", "2.0-beta", true),
+    ];
+    for (range, version, expected) in cases {
+        assert_eq!(satisfies(range, version), Some(*expected), "{} matches {}", range, version);
+    }
+}
+
+#[test]
+fn test_maven_ranges() {
+    let cases: &[(&str, &str, bool)] = &[
+        ("[47,)", "47.2.0", true),
+        ("[47,)", "46.0.1", false),
+        ("(1.0,2.0]", "1.0", false),
+        ("(1.0,2.0]", "2.0", true),
+        ("(1.0,2.0]", "2.0.1", false),
+        ("[1.2]", "1.2.0", true),
+        ("[1.2]", "1.3", false),
+        ("(,1.20.2)", "1.20.1", true),
+        ("[1,2),[3,)", "2.5", false),
+        ("[1,2),[3,)", "3.1", true),
+        ("[15.2,16)", "15.2.0.27", true),
+    ];
+    for (range, version, expected) in cases {
+        assert_eq!(satisfies(range, version), Some(*expected), "{} matches {}", range, version);
+    }
+
+    // Versión suelta en Maven: recomendación, acepta cualquier cosa
+    assert!(VersionRange::parse_maven("1.0").unwrap().matches("0.1"));
+    assert!(VersionRange::parse_fabric("1.0").unwrap().matches("1.0.0"));
+}
+
+#[test]
+fn test_unparseable_ranges() {
+    assert_eq!(satisfies("${forge_version_range}", "47.2.0"), None);
+    assert_eq!(satisfies("[1.0", "1.0"), None);
+    assert_eq!(satisfies(">=", "1.0"), None);
+}